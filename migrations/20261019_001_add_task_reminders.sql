@@ -0,0 +1,40 @@
+-- Multiple reminders per task, each addressed to one member
+CREATE TABLE IF NOT EXISTS task_reminders (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Exactly one of: an absolute time, or minutes before the task's due date
+    remind_at TIMESTAMP WITH TIME ZONE,
+    offset_minutes INTEGER,
+    -- Resolved firing time; NULL while a relative reminder's task has no due date
+    fire_at TIMESTAMP WITH TIME ZONE,
+    notified_at TIMESTAMP WITH TIME ZONE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT check_reminder_kind CHECK ((remind_at IS NULL) <> (offset_minutes IS NULL)),
+    CONSTRAINT check_reminder_offset CHECK (offset_minutes IS NULL OR offset_minutes >= 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_reminders_task_id ON task_reminders(task_id);
+CREATE INDEX IF NOT EXISTS idx_task_reminders_user_id ON task_reminders(user_id);
+CREATE INDEX IF NOT EXISTS idx_task_reminders_pending ON task_reminders(fire_at) WHERE notified_at IS NULL;
+
+-- Move the single per-task reminder over, then drop the old columns
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'tasks' AND column_name = 'reminder_time'
+    ) THEN
+        INSERT INTO task_reminders (task_id, user_id, remind_at, fire_at, notified_at, created_by)
+        SELECT id, user_id, reminder_time, reminder_time,
+               CASE WHEN notified THEN updated_at END, user_id
+        FROM tasks
+        WHERE reminder_time IS NOT NULL;
+    END IF;
+END $$;
+
+DROP INDEX IF EXISTS idx_tasks_reminder_time;
+DROP INDEX IF EXISTS idx_tasks_notified;
+ALTER TABLE tasks DROP COLUMN IF EXISTS reminder_time;
+ALTER TABLE tasks DROP COLUMN IF EXISTS notified;
@@ -0,0 +1,28 @@
+-- Overdue escalation policies: a per-user default, optionally overridden per task
+CREATE TABLE IF NOT EXISTS escalation_policies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    task_id UUID REFERENCES tasks(id) ON DELETE CASCADE,
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    notify_owner BOOLEAN NOT NULL DEFAULT true,
+    bump_priority_after_hours INTEGER,
+    escalate_after_hours INTEGER,
+    -- NULL means escalate to every task member
+    escalation_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT check_bump_priority_after_hours CHECK (bump_priority_after_hours IS NULL OR bump_priority_after_hours > 0),
+    CONSTRAINT check_escalate_after_hours CHECK (escalate_after_hours IS NULL OR escalate_after_hours > 0)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_escalation_policies_user_default
+    ON escalation_policies(user_id) WHERE task_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_escalation_policies_task
+    ON escalation_policies(task_id) WHERE task_id IS NOT NULL;
+
+-- Escalation steps are recorded in task_activity; look them up quickly
+CREATE INDEX IF NOT EXISTS idx_task_activity_escalations
+    ON task_activity(task_id, action) WHERE action = 'escalated';
+
+CREATE TRIGGER update_escalation_policies_updated_at BEFORE UPDATE ON escalation_policies
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,25 @@
+-- Pending OAuth authorizations, looked up by the state sent to the provider
+CREATE TABLE IF NOT EXISTS oauth_authorizations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    state_hash VARCHAR(64) NOT NULL UNIQUE,
+    provider VARCHAR(50) NOT NULL,
+    pkce_verifier VARCHAR(128) NOT NULL,
+    -- Set when a signed-in user links the provider instead of logging in
+    link_user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    redirect_url TEXT,
+    -- Hash of the cookie that ties a login to the browser that started it
+    browser_binding_hash VARCHAR(64),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_oauth_authorizations_expires_at ON oauth_authorizations(expires_at);
+
+-- Single-use codes the SPA exchanges for tokens after an OAuth login
+CREATE TABLE IF NOT EXISTS oauth_login_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    code_hash VARCHAR(64) NOT NULL UNIQUE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,16 @@
+-- Asymmetric JWT signing keys, rotated on a schedule. A key signs from
+-- activates_at until retires_at and is still accepted, and published in
+-- the JWKS, until expires_at.
+CREATE TABLE IF NOT EXISTS jwt_signing_keys (
+    kid VARCHAR(64) PRIMARY KEY,
+    algorithm VARCHAR(16) NOT NULL,
+    -- PKCS#8 DER for EdDSA, PKCS#1 DER for RS256
+    private_key BYTEA NOT NULL,
+    public_jwk JSONB NOT NULL,
+    activates_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    retires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_jwt_signing_keys_expires_at ON jwt_signing_keys(expires_at);
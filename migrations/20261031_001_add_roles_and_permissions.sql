@@ -0,0 +1,40 @@
+-- Roles are named sets of permissions; a user can hold several
+CREATE TABLE IF NOT EXISTS roles (
+    name VARCHAR(50) PRIMARY KEY,
+    description TEXT NOT NULL DEFAULT '',
+    permissions TEXT[] NOT NULL DEFAULT '{}',
+    built_in BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS user_roles (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role_name VARCHAR(50) NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    assigned_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    assigned_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, role_name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_roles_role_name ON user_roles(role_name);
+
+INSERT INTO roles (name, description, permissions, built_in) VALUES
+    ('admin', 'Full access to user, role and task administration', ARRAY[
+        'users.read', 'users.update', 'users.deactivate', 'users.delete', 'users.manage_security',
+        'roles.read', 'roles.assign', 'roles.manage', 'tasks.read_any'
+    ], true),
+    ('support', 'Look up accounts and help users who are locked out', ARRAY[
+        'users.read', 'users.manage_security', 'tasks.read_any'
+    ], true)
+ON CONFLICT (name) DO NOTHING;
+
+-- Both the role column and the is_admin flag used to mean admin
+INSERT INTO user_roles (user_id, role_name)
+SELECT id, 'admin' FROM users WHERE is_admin OR role = 'admin'
+ON CONFLICT DO NOTHING;
+
+ALTER TABLE users DROP CONSTRAINT IF EXISTS check_role;
+DROP INDEX IF EXISTS idx_users_role;
+DROP INDEX IF EXISTS idx_users_is_admin;
+ALTER TABLE users DROP COLUMN IF EXISTS role;
+ALTER TABLE users DROP COLUMN IF EXISTS is_admin;
@@ -0,0 +1,17 @@
+-- Collaborators become editors; viewers can only read
+ALTER TABLE task_members DROP CONSTRAINT IF EXISTS check_task_member_role;
+
+UPDATE task_members SET role = 'editor' WHERE role = 'collaborator';
+ALTER TABLE task_members ALTER COLUMN role SET DEFAULT 'editor';
+
+-- Every task has an owner row matching tasks.user_id
+INSERT INTO task_members (task_id, user_id, role, added_by)
+SELECT id, user_id, 'owner', user_id FROM tasks
+ON CONFLICT (task_id, user_id) DO UPDATE SET role = 'owner';
+
+UPDATE task_members tm SET role = 'editor'
+FROM tasks t
+WHERE t.id = tm.task_id AND tm.role = 'owner' AND tm.user_id <> t.user_id;
+
+ALTER TABLE task_members ADD CONSTRAINT check_task_member_role
+    CHECK (role IN ('owner', 'editor', 'viewer'));
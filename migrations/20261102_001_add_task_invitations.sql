@@ -0,0 +1,38 @@
+-- Invitations to join a task, addressed to a user or to an email address
+-- that may not be registered yet
+CREATE TABLE IF NOT EXISTS task_invitations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    invitee_user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    -- Lowercased; set when inviting by email
+    invitee_email VARCHAR(255),
+    role VARCHAR(50) NOT NULL DEFAULT 'editor',
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    responded_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT check_task_invitation_role CHECK (role IN ('editor', 'viewer')),
+    CONSTRAINT check_task_invitation_status CHECK (status IN ('pending', 'accepted', 'declined', 'revoked')),
+    CONSTRAINT check_task_invitation_invitee CHECK (invitee_user_id IS NOT NULL OR invitee_email IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_invitations_task_id ON task_invitations(task_id);
+CREATE INDEX IF NOT EXISTS idx_task_invitations_invitee_user_id ON task_invitations(invitee_user_id) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_task_invitations_invitee_email ON task_invitations(invitee_email) WHERE status = 'pending';
+
+-- Shareable links that let anyone signed in join a task with a set role
+CREATE TABLE IF NOT EXISTS task_join_links (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    role VARCHAR(50) NOT NULL DEFAULT 'viewer',
+    use_count INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT check_task_join_link_role CHECK (role IN ('editor', 'viewer'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_join_links_task_id ON task_join_links(task_id);
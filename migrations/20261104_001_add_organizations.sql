@@ -0,0 +1,58 @@
+-- Organizations are the tenants: tasks and messages belong to exactly one,
+-- users may belong to several
+CREATE TABLE IF NOT EXISTS organizations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS organization_members (
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(20) NOT NULL DEFAULT 'member',
+    added_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    joined_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, user_id),
+    CONSTRAINT check_organization_member_role CHECK (role IN ('owner', 'admin', 'member'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_organization_members_user_id ON organization_members(user_id);
+
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE;
+
+-- Everyone shared one space so far: move it all into a single organization,
+-- owned by the administrators (or the first user if there are none)
+DO $$
+DECLARE
+    default_org UUID;
+BEGIN
+    IF EXISTS (SELECT 1 FROM users) THEN
+        INSERT INTO organizations (name) VALUES ('Default') RETURNING id INTO default_org;
+
+        INSERT INTO organization_members (organization_id, user_id, role, joined_at)
+        SELECT default_org, u.id,
+               CASE WHEN EXISTS (SELECT 1 FROM user_roles ur WHERE ur.user_id = u.id AND ur.role_name = 'admin')
+                    THEN 'owner' ELSE 'member' END,
+               u.created_at
+        FROM users u;
+
+        UPDATE organization_members SET role = 'owner'
+        WHERE organization_id = default_org
+          AND user_id = (SELECT id FROM users ORDER BY created_at ASC LIMIT 1)
+          AND NOT EXISTS (
+              SELECT 1 FROM organization_members WHERE organization_id = default_org AND role = 'owner'
+          );
+
+        UPDATE tasks SET organization_id = default_org WHERE organization_id IS NULL;
+        UPDATE messages SET organization_id = default_org WHERE organization_id IS NULL;
+    END IF;
+END $$;
+
+ALTER TABLE tasks ALTER COLUMN organization_id SET NOT NULL;
+ALTER TABLE messages ALTER COLUMN organization_id SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_organization_id ON tasks(organization_id);
+CREATE INDEX IF NOT EXISTS idx_messages_organization_id ON messages(organization_id);
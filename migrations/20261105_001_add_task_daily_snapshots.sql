@@ -0,0 +1,18 @@
+-- Per-user daily task counts behind the analytics trends, refreshed by the scheduler
+CREATE TABLE IF NOT EXISTS task_daily_snapshots (
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Calendar day in the user's time zone
+    day DATE NOT NULL,
+    -- Counted over the tasks the user owns or is a member of
+    created_count INTEGER NOT NULL DEFAULT 0,
+    completed_count INTEGER NOT NULL DEFAULT 0,
+    -- Pending or in-progress tasks, and those of them past due, at the end of the day
+    open_count INTEGER NOT NULL DEFAULT 0,
+    overdue_count INTEGER NOT NULL DEFAULT 0,
+    refreshed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, user_id, day)
+);
+
+-- Status changes are looked up per task in time order
+CREATE INDEX IF NOT EXISTS idx_task_activity_task_id_created_at ON task_activity(task_id, created_at);
@@ -0,0 +1,40 @@
+-- Append-only record of admin and security-relevant actions. There are no
+-- foreign keys: entries must outlive the users and organizations they mention.
+CREATE TABLE IF NOT EXISTS audit_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- The organization the actor was acting in
+    organization_id UUID,
+    actor_id UUID,
+    action VARCHAR(50) NOT NULL,
+    -- What was acted on, e.g. ('user', <user id>) or ('role', <role name>)
+    target_type VARCHAR(30) NOT NULL,
+    target_id VARCHAR(255) NOT NULL,
+    before JSONB,
+    after JSONB,
+    ip_address VARCHAR(45),
+    user_agent TEXT,
+    request_id VARCHAR(128),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_organization_id ON audit_log(organization_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_actor_id ON audit_log(actor_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_target ON audit_log(target_type, target_id, created_at DESC);
+
+CREATE OR REPLACE FUNCTION reject_audit_log_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER audit_log_append_only BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION reject_audit_log_change();
+
+CREATE TRIGGER audit_log_no_truncate BEFORE TRUNCATE ON audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION reject_audit_log_change();
+
+-- Reading the log is a permission of its own, granted to admins
+UPDATE roles
+SET permissions = array_append(permissions, 'audit.read'), updated_at = NOW()
+WHERE name = 'admin' AND NOT ('audit.read' = ANY(permissions));
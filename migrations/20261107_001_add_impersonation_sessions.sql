@@ -0,0 +1,20 @@
+-- Support staff acting as a user through a short-lived token
+CREATE TABLE IF NOT EXISTS impersonation_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    admin_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    reason TEXT,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- Set when the admin ends the session, or to expires_at once it ran out
+    ended_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_impersonation_sessions_open ON impersonation_sessions(expires_at)
+    WHERE ended_at IS NULL;
+
+-- Both built-in roles help users, so both may impersonate
+UPDATE roles
+SET permissions = array_append(permissions, 'users.impersonate'), updated_at = NOW()
+WHERE name IN ('admin', 'support') AND NOT ('users.impersonate' = ANY(permissions));
@@ -0,0 +1,22 @@
+-- Nobody joins an organization without accepting an invitation
+CREATE TABLE IF NOT EXISTS organization_invitations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- Set when inviting by username, or once an email invitation is accepted
+    invitee_user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    -- Lowercased; set when inviting by email
+    invitee_email VARCHAR(255),
+    role VARCHAR(20) NOT NULL DEFAULT 'member',
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    responded_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT check_organization_invitation_role CHECK (role IN ('owner', 'admin', 'member')),
+    CONSTRAINT check_organization_invitation_status CHECK (status IN ('pending', 'accepted', 'declined', 'revoked')),
+    CONSTRAINT check_organization_invitation_invitee CHECK (invitee_user_id IS NOT NULL OR invitee_email IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_organization_invitations_organization_id ON organization_invitations(organization_id);
+CREATE INDEX IF NOT EXISTS idx_organization_invitations_invitee_user_id ON organization_invitations(invitee_user_id) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_organization_invitations_invitee_email ON organization_invitations(invitee_email) WHERE status = 'pending';
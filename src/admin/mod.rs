@@ -1,2 +0,0 @@
-// Declare submodules
-pub mod admin_middleware;
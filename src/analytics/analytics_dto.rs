@@ -0,0 +1,60 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Tasks created and completed in one period, and the open and overdue
+/// counts on its last day. Days before snapshots were taken have no open
+/// or overdue counts.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct TrendPeriod {
+    pub period_start: NaiveDate,
+    pub created: i64,
+    pub completed: i64,
+    pub open_at_end: Option<i64>,
+    pub overdue_at_end: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrendsResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// `day` or `week`
+    pub group_by: String,
+    pub timezone: String,
+    pub periods: Vec<TrendPeriod>,
+    /// Tasks completed in the range
+    pub completed: i64,
+    /// Average hours from creation to completion
+    pub avg_lead_time_hours: Option<f64>,
+    /// Average hours from starting work (`InProgress`) to completion
+    pub avg_cycle_time_hours: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BurndownPoint {
+    pub date: NaiveDate,
+    /// Selected tasks that existed by the end of the day
+    pub scope: i64,
+    /// Of those, the ones not completed or archived
+    pub remaining: i64,
+    /// Where `remaining` would be burning down evenly to zero on the last day
+    pub ideal: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BurndownResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub timezone: String,
+    pub points: Vec<BurndownPoint>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StreaksResponse {
+    /// Consecutive days, up to today or yesterday, on which you completed a task
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    pub last_completed_on: Option<NaiveDate>,
+    pub timezone: String,
+}
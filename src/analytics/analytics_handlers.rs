@@ -0,0 +1,125 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+
+use crate::{
+    error::Result,
+    middleware::{AuthUser, CurrentOrganization},
+    state::AppState,
+};
+use super::{
+    analytics_dto::{BurndownResponse, StreaksResponse, TrendsResponse},
+    analytics_models::{BurndownFilter, TrendGrouping},
+};
+
+#[derive(Deserialize)]
+pub struct TrendsQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    group_by: Option<TrendGrouping>,
+}
+
+#[derive(Deserialize)]
+pub struct BurndownQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    priority: Option<String>,
+    search: Option<String>,
+    due_from: Option<DateTime<Utc>>,
+    due_to: Option<DateTime<Utc>>,
+}
+
+/// Tasks created vs completed per day or week, with lead and cycle times
+#[utoipa::path(
+    get,
+    path = "/api/analytics/trends",
+    params(
+        ("from" = Option<NaiveDate>, Query, description = "First day (defaults to 29 days before `to`)"),
+        ("to" = Option<NaiveDate>, Query, description = "Last day, inclusive (defaults to today)"),
+        ("group_by" = Option<TrendGrouping>, Query, description = "`day` (default) or `week`")
+    ),
+    responses(
+        (status = 200, description = "Created and completed tasks per period", body = TrendsResponse),
+        (status = 400, description = "Invalid range"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "analytics",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_trends(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Query(query): Query<TrendsQuery>,
+) -> Result<Json<TrendsResponse>> {
+    let trends = state
+        .analytics_service
+        .trends(organization_id, user_id, query.from, query.to, query.group_by.unwrap_or_default())
+        .await?;
+
+    Ok(Json(trends))
+}
+
+/// Burndown of the tasks matching a filter or due-date window
+#[utoipa::path(
+    get,
+    path = "/api/analytics/burndown",
+    params(
+        ("from" = Option<NaiveDate>, Query, description = "First day (defaults to 29 days before `to`)"),
+        ("to" = Option<NaiveDate>, Query, description = "Last day, inclusive (defaults to today)"),
+        ("priority" = Option<String>, Query, description = "Only tasks of this priority"),
+        ("search" = Option<String>, Query, description = "Only tasks whose title or description matches"),
+        ("due_from" = Option<DateTime<Utc>>, Query, description = "Only tasks due at or after this time"),
+        ("due_to" = Option<DateTime<Utc>>, Query, description = "Only tasks due before this time")
+    ),
+    responses(
+        (status = 200, description = "Scope, remaining and ideal remaining tasks per day", body = BurndownResponse),
+        (status = 400, description = "Invalid range"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "analytics",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_burndown(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Query(query): Query<BurndownQuery>,
+) -> Result<Json<BurndownResponse>> {
+    let filter = BurndownFilter {
+        priority: query.priority,
+        search: query.search,
+        due_from: query.due_from,
+        due_to: query.due_to,
+    };
+
+    let burndown = state
+        .analytics_service
+        .burndown(organization_id, user_id, filter, query.from, query.to)
+        .await?;
+
+    Ok(Json(burndown))
+}
+
+/// Current and longest streak of days with a completed task
+#[utoipa::path(
+    get,
+    path = "/api/analytics/streaks",
+    responses(
+        (status = 200, description = "Completion streaks", body = StreaksResponse),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "analytics",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_streaks(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+) -> Result<Json<StreaksResponse>> {
+    let streaks = state.analytics_service.streaks(organization_id, user_id).await?;
+    Ok(Json(streaks))
+}
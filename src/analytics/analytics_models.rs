@@ -0,0 +1,51 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// How trend periods are bucketed; weeks start on Monday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendGrouping {
+    #[default]
+    Day,
+    Week,
+}
+
+impl TrendGrouping {
+    /// The `date_trunc` field for this grouping.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrendGrouping::Day => "day",
+            TrendGrouping::Week => "week",
+        }
+    }
+}
+
+/// Which tasks a burndown follows; unset fields select everything.
+#[derive(Debug, Clone, Default)]
+pub struct BurndownFilter {
+    pub priority: Option<String>,
+    pub search: Option<String>,
+    pub due_from: Option<DateTime<Utc>>,
+    pub due_to: Option<DateTime<Utc>>,
+}
+
+/// Completions in a range with their average lead and cycle times.
+#[derive(Debug, Clone, FromRow)]
+pub struct DeliveryTimes {
+    pub completed: i64,
+    /// Hours from creation to completion
+    pub avg_lead_time_hours: Option<f64>,
+    /// Hours from the first move to `InProgress` to completion
+    pub avg_cycle_time_hours: Option<f64>,
+}
+
+/// Size of a burndown's task set and how much of it was still open at the
+/// end of a day.
+#[derive(Debug, Clone, FromRow)]
+pub struct BurndownDay {
+    pub day: NaiveDate,
+    pub scope: i64,
+    pub remaining: i64,
+}
@@ -0,0 +1,326 @@
+use crate::{error::Result, task::task_repository::ACCESSIBLE_TASKS};
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::{
+    analytics_dto::TrendPeriod,
+    analytics_models::{BurndownDay, BurndownFilter, DeliveryTimes, TrendGrouping},
+};
+
+/// The status a `task_activity` row `a` set, or NULL if it left the status alone.
+const NEW_STATUS: &str = "CASE a.action
+        WHEN 'status_updated' THEN a.details->>'new_status'
+        WHEN 'updated' THEN a.details->>'status'
+    END";
+
+/// The status task `task` had at `at`, replayed from the activity log: the
+/// last change before then, else `Pending` if changes were only logged
+/// later. Tasks with no logged change at all keep their current status.
+fn status_at(task: &str, at: &str) -> String {
+    format!(
+        "COALESCE(
+            (SELECT {status} FROM task_activity a
+             WHERE a.task_id = {task}.id AND a.created_at < {at} AND {status} IS NOT NULL
+             ORDER BY a.created_at DESC LIMIT 1),
+            CASE WHEN EXISTS (
+                    SELECT 1 FROM task_activity a
+                    WHERE a.task_id = {task}.id AND {status} IS NOT NULL)
+                 THEN 'Pending' ELSE {task}.status END)",
+        status = NEW_STATUS,
+        task = task,
+        at = at,
+    )
+}
+
+/// Historical analytics derived from the `task_activity` status changes.
+/// Everything is scoped to an organization and the tasks a user owns or is a
+/// member of.
+#[derive(Clone)]
+pub struct AnalyticsRepository {
+    pool: PgPool,
+}
+
+impl AnalyticsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Created and completed counts per period from the daily snapshots,
+    /// with the open and overdue counts of the last snapshot in each period.
+    pub async fn find_trends(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+        group_by: TrendGrouping,
+    ) -> Result<Vec<TrendPeriod>> {
+        let periods = sqlx::query_as::<_, TrendPeriod>(
+            "SELECT
+                date_trunc($5, d)::date AS period_start,
+                COALESCE(SUM(s.created_count), 0)::BIGINT AS created,
+                COALESCE(SUM(s.completed_count), 0)::BIGINT AS completed,
+                ((ARRAY_AGG(s.open_count ORDER BY d DESC) FILTER (WHERE s.day IS NOT NULL))[1])::BIGINT AS open_at_end,
+                ((ARRAY_AGG(s.overdue_count ORDER BY d DESC) FILTER (WHERE s.day IS NOT NULL))[1])::BIGINT AS overdue_at_end
+             FROM generate_series($3::date, $4::date, INTERVAL '1 day') d
+             LEFT JOIN task_daily_snapshots s
+                ON s.organization_id = $1 AND s.user_id = $2 AND s.day = d::date
+             GROUP BY 1
+             ORDER BY 1"
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .bind(group_by.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(periods)
+    }
+
+    /// Tasks completed in `[from, to)` with their average lead time (since
+    /// creation) and cycle time (since first moving to `InProgress`). A task
+    /// completed several times counts once, at its last completion.
+    pub async fn find_delivery_times(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<DeliveryTimes> {
+        let query = format!(
+            "WITH completions AS (
+                SELECT DISTINCT ON (a.task_id) a.task_id, a.created_at AS completed_at
+                FROM task_activity a
+                WHERE a.task_id IN (SELECT t.id {accessible})
+                  AND {status} = 'Completed'
+                  AND a.created_at >= $3 AND a.created_at < $4
+                ORDER BY a.task_id, a.created_at DESC
+            )
+            SELECT
+                COUNT(*) AS completed,
+                (AVG(EXTRACT(EPOCH FROM c.completed_at - t.created_at)) / 3600)::FLOAT8 AS avg_lead_time_hours,
+                (AVG(EXTRACT(EPOCH FROM c.completed_at - started.started_at)) / 3600)::FLOAT8 AS avg_cycle_time_hours
+             FROM completions c
+             JOIN tasks t ON t.id = c.task_id
+             LEFT JOIN LATERAL (
+                SELECT MIN(a.created_at) AS started_at
+                FROM task_activity a
+                WHERE a.task_id = c.task_id AND a.created_at <= c.completed_at
+                  AND {status} = 'InProgress'
+             ) started ON true",
+            accessible = ACCESSIBLE_TASKS,
+            status = NEW_STATUS,
+        );
+
+        let times = sqlx::query_as::<_, DeliveryTimes>(&query)
+            .bind(organization_id)
+            .bind(user_id)
+            .bind(from)
+            .bind(to)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(times)
+    }
+
+    /// For each day from `from` to `to` in `timezone`, how many of the
+    /// selected tasks existed by the end of the day and how many of them
+    /// were not yet completed or archived.
+    pub async fn find_burndown(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        filter: &BurndownFilter,
+        from: NaiveDate,
+        to: NaiveDate,
+        timezone: &str,
+    ) -> Result<Vec<BurndownDay>> {
+        let query = format!(
+            "WITH selected AS (
+                SELECT DISTINCT t.id, t.status, t.created_at
+                {accessible}
+                  AND ($3::varchar IS NULL OR t.priority = $3)
+                  AND ($4::varchar IS NULL OR t.title ILIKE $4 OR t.description ILIKE $4)
+                  AND ($5::timestamptz IS NULL OR t.due_date >= $5)
+                  AND ($6::timestamptz IS NULL OR t.due_date < $6)
+            ),
+            days AS (
+                SELECT d::date AS day, ((d::date + 1)::timestamp AT TIME ZONE $9) AS day_end
+                FROM generate_series($7::date, $8::date, INTERVAL '1 day') d
+            ),
+            states AS (
+                SELECT d.day, s.id, s.created_at < d.day_end AS existed, {status} AS status
+                FROM days d
+                LEFT JOIN selected s ON true
+            )
+            SELECT
+                day,
+                COUNT(id) FILTER (WHERE existed) AS scope,
+                COUNT(id) FILTER (WHERE existed AND status NOT IN ('Completed', 'Archived')) AS remaining
+             FROM states
+             GROUP BY day
+             ORDER BY day",
+            accessible = ACCESSIBLE_TASKS,
+            status = status_at("s", "d.day_end"),
+        );
+
+        let days = sqlx::query_as::<_, BurndownDay>(&query)
+            .bind(organization_id)
+            .bind(user_id)
+            .bind(filter.priority.as_deref())
+            .bind(filter.search.as_ref().map(|search| format!("%{}%", search)))
+            .bind(filter.due_from)
+            .bind(filter.due_to)
+            .bind(from)
+            .bind(to)
+            .bind(timezone)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(days)
+    }
+
+    /// The days, in `timezone` and oldest first, on which the user completed
+    /// at least one task of the organization.
+    pub async fn find_completion_days(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        timezone: &str,
+    ) -> Result<Vec<NaiveDate>> {
+        let query = format!(
+            "SELECT DISTINCT (a.created_at AT TIME ZONE $3)::date AS day
+             FROM task_activity a
+             JOIN tasks t ON t.id = a.task_id AND t.organization_id = $1
+             WHERE a.user_id = $2 AND {status} = 'Completed'
+             ORDER BY day ASC",
+            status = NEW_STATUS,
+        );
+
+        let days: Vec<NaiveDate> = sqlx::query_scalar(&query)
+            .bind(organization_id)
+            .bind(user_id)
+            .bind(timezone)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(days)
+    }
+
+    pub async fn has_snapshots(&self) -> Result<bool> {
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM task_daily_snapshots)")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(exists)
+    }
+
+    /// Recompute the snapshots of every organization member for today and
+    /// the `days_back` days before it, each in the member's own time zone.
+    /// Returns the rows written.
+    pub async fn refresh_snapshots(&self, days_back: i32) -> Result<u64> {
+        let query = upsert_snapshots(
+            "SELECT m.organization_id, m.user_id, d.day,
+                (d.day::timestamp AT TIME ZONE u.timezone) AS day_start,
+                ((d.day + 1)::timestamp AT TIME ZONE u.timezone) AS day_end
+            FROM organization_members m
+            JOIN users u ON u.id = m.user_id
+            CROSS JOIN LATERAL (
+                SELECT (NOW() AT TIME ZONE u.timezone)::date - s AS day
+                FROM generate_series(0, $1) s
+            ) d",
+        );
+
+        let result = sqlx::query(&query)
+            .bind(days_back)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Write the snapshots the user has no row for between `from` and `to`,
+    /// up to today in the user's time zone, e.g. days from before the
+    /// scheduler's first backfill or before the user joined. Returns the rows
+    /// written.
+    pub async fn fill_missing_snapshots(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<u64> {
+        let query = upsert_snapshots(
+            "SELECT $1::uuid AS organization_id, u.id AS user_id, d::date AS day,
+                (d::date::timestamp AT TIME ZONE u.timezone) AS day_start,
+                ((d::date + 1)::timestamp AT TIME ZONE u.timezone) AS day_end
+            FROM users u
+            CROSS JOIN generate_series($3::date, $4::date, INTERVAL '1 day') d
+            WHERE u.id = $2
+              AND d::date <= (NOW() AT TIME ZONE u.timezone)::date
+              AND NOT EXISTS (
+                SELECT 1 FROM task_daily_snapshots s
+                WHERE s.organization_id = $1 AND s.user_id = u.id AND s.day = d::date)",
+        );
+
+        let result = sqlx::query(&query)
+            .bind(organization_id)
+            .bind(user_id)
+            .bind(from)
+            .bind(to)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Upsert the snapshots of the `(organization_id, user_id, day, day_start,
+/// day_end)` rows selected by `days`. Open and overdue counts are replayed
+/// from the activity log, so older days come out as they stood at the time.
+fn upsert_snapshots(days: &str) -> String {
+    format!(
+        "WITH days AS ({days}),
+        user_tasks AS (
+            SELECT DISTINCT m.organization_id, m.user_id, t.id, t.status, t.created_at, t.due_date
+            FROM (SELECT DISTINCT organization_id, user_id FROM days) m
+            JOIN tasks t ON t.organization_id = m.organization_id
+            LEFT JOIN task_members tm ON tm.task_id = t.id AND tm.user_id = m.user_id
+            WHERE t.user_id = m.user_id OR tm.user_id IS NOT NULL
+        ),
+        states AS (
+            SELECT d.organization_id, d.user_id, d.day, d.day_start, d.day_end,
+                ut.id, ut.created_at, ut.due_date,
+                {status} AS status,
+                EXISTS (
+                    SELECT 1 FROM task_activity a
+                    WHERE a.task_id = ut.id AND {new_status} = 'Completed'
+                      AND a.created_at >= d.day_start AND a.created_at < d.day_end
+                ) AS completed_that_day
+            FROM days d
+            LEFT JOIN user_tasks ut ON ut.organization_id = d.organization_id AND ut.user_id = d.user_id
+        )
+        INSERT INTO task_daily_snapshots
+            (organization_id, user_id, day, created_count, completed_count, open_count, overdue_count)
+        SELECT organization_id, user_id, day,
+            COUNT(id) FILTER (WHERE created_at >= day_start AND created_at < day_end),
+            COUNT(id) FILTER (WHERE completed_that_day),
+            COUNT(id) FILTER (WHERE created_at < day_end AND status IN ('Pending', 'InProgress')),
+            COUNT(id) FILTER (WHERE created_at < day_end AND status IN ('Pending', 'InProgress')
+                AND due_date < LEAST(day_end, NOW()))
+        FROM states
+        GROUP BY organization_id, user_id, day
+        ON CONFLICT (organization_id, user_id, day) DO UPDATE SET
+            created_count = EXCLUDED.created_count,
+            completed_count = EXCLUDED.completed_count,
+            open_count = EXCLUDED.open_count,
+            overdue_count = EXCLUDED.overdue_count,
+            refreshed_at = NOW()",
+        days = days,
+        status = status_at("ut", "d.day_end"),
+        new_status = NEW_STATUS,
+    )
+}
@@ -0,0 +1,261 @@
+use chrono::{Duration, NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, Result},
+    task::task_calendar::{local_date, local_day_start},
+    user::user_repository::UserRepository,
+};
+use super::{
+    analytics_dto::{BurndownPoint, BurndownResponse, StreaksResponse, TrendsResponse},
+    analytics_models::{BurndownFilter, TrendGrouping},
+    analytics_repository::AnalyticsRepository,
+};
+
+/// Longest range an analytics request may cover, in days.
+const MAX_ANALYTICS_DAYS: i64 = 366;
+/// Range used when none is given: the last 30 days, today included.
+const DEFAULT_ANALYTICS_DAYS: i64 = 30;
+/// Days before today redone on each refresh, to catch changes made close
+/// to midnight.
+const SNAPSHOT_REFRESH_DAYS: i32 = 1;
+/// History taken from the activity log when there are no snapshots yet.
+const SNAPSHOT_BACKFILL_DAYS: i32 = 90;
+
+#[derive(Clone)]
+pub struct AnalyticsService {
+    repo: AnalyticsRepository,
+    user_repo: UserRepository,
+}
+
+impl AnalyticsService {
+    pub fn new(repo: AnalyticsRepository, user_repo: UserRepository) -> Self {
+        Self { repo, user_repo }
+    }
+
+    /// Created vs completed tasks per day or week from the daily snapshots,
+    /// filling in any the range is missing first, plus lead and cycle times
+    /// of the tasks completed in the range. Dates are days in the user's time
+    /// zone.
+    pub async fn trends(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        group_by: TrendGrouping,
+    ) -> Result<TrendsResponse> {
+        let tz = self.user_tz(user_id).await?;
+        let (from, to) = resolve_range(from, to, local_date(Utc::now(), tz))?;
+
+        // Days the scheduler never covered are worked out from the activity log
+        self.repo.fill_missing_snapshots(organization_id, user_id, from, to).await?;
+        let periods = self.repo.find_trends(organization_id, user_id, from, to, group_by).await?;
+        let times = self.repo
+            .find_delivery_times(
+                organization_id,
+                user_id,
+                local_day_start(from, tz),
+                local_day_start(shift(to, Duration::days(1))?, tz),
+            )
+            .await?;
+
+        Ok(TrendsResponse {
+            from,
+            to,
+            group_by: group_by.as_str().to_string(),
+            timezone: tz.name().to_string(),
+            periods,
+            completed: times.completed,
+            avg_lead_time_hours: times.avg_lead_time_hours,
+            avg_cycle_time_hours: times.avg_cycle_time_hours,
+        })
+    }
+
+    /// Day-by-day scope and remaining work of the tasks picked by `filter`.
+    /// Days after today are left out; the ideal line still runs to `to`.
+    pub async fn burndown(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        filter: BurndownFilter,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<BurndownResponse> {
+        let tz = self.user_tz(user_id).await?;
+        let today = local_date(Utc::now(), tz);
+        let (from, to) = resolve_range(from, to, today)?;
+
+        let days = if from <= today {
+            self.repo
+                .find_burndown(organization_id, user_id, &filter, from, to.min(today), tz.name())
+                .await?
+        } else {
+            Vec::new()
+        };
+
+        let total_days = (to - from).num_days() + 1;
+        let starting_scope = days.first().map(|day| day.remaining).unwrap_or(0);
+        let points = days
+            .into_iter()
+            .enumerate()
+            .map(|(index, day)| BurndownPoint {
+                date: day.day,
+                scope: day.scope,
+                remaining: day.remaining,
+                ideal: ideal_remaining(starting_scope, index as i64, total_days),
+            })
+            .collect();
+
+        Ok(BurndownResponse {
+            from,
+            to,
+            timezone: tz.name().to_string(),
+            points,
+        })
+    }
+
+    /// Runs of consecutive days on which the user completed a task.
+    pub async fn streaks(&self, organization_id: Uuid, user_id: Uuid) -> Result<StreaksResponse> {
+        let tz = self.user_tz(user_id).await?;
+        let days = self.repo.find_completion_days(organization_id, user_id, tz.name()).await?;
+        let (current_streak, longest_streak) = compute_streaks(&days, local_date(Utc::now(), tz));
+
+        Ok(StreaksResponse {
+            current_streak,
+            longest_streak,
+            last_completed_on: days.last().copied(),
+            timezone: tz.name().to_string(),
+        })
+    }
+
+    /// Bring the daily snapshots up to date; called by the scheduler. The
+    /// first run fills in recent history from the activity log.
+    pub async fn refresh_snapshots(&self) -> Result<u64> {
+        let days_back = if self.repo.has_snapshots().await? {
+            SNAPSHOT_REFRESH_DAYS
+        } else {
+            SNAPSHOT_BACKFILL_DAYS
+        };
+
+        self.repo.refresh_snapshots(days_back).await
+    }
+
+    async fn user_tz(&self, user_id: Uuid) -> Result<chrono_tz::Tz> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        Ok(user.tz())
+    }
+}
+
+/// Fill in a missing end of the range and check its length. Without dates
+/// the range is the last `DEFAULT_ANALYTICS_DAYS` days up to today.
+fn resolve_range(
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    today: NaiveDate,
+) -> Result<(NaiveDate, NaiveDate)> {
+    let default_span = Duration::days(DEFAULT_ANALYTICS_DAYS - 1);
+    let (from, to) = match (from, to) {
+        (Some(from), Some(to)) => (from, to),
+        (Some(from), None) => (from, shift(from, default_span)?.min(today).max(from)),
+        (None, Some(to)) => (shift(to, -default_span)?, to),
+        (None, None) => (shift(today, -default_span)?, today),
+    };
+
+    if from > to {
+        return Err(AppError::BadRequest("'from' must not be after 'to'".to_string()));
+    }
+    if (to - from).num_days() + 1 > MAX_ANALYTICS_DAYS {
+        return Err(AppError::BadRequest(format!(
+            "Analytics range cannot exceed {} days",
+            MAX_ANALYTICS_DAYS
+        )));
+    }
+
+    Ok((from, to))
+}
+
+/// `date` moved by `by`, refusing dates chrono cannot represent.
+fn shift(date: NaiveDate, by: Duration) -> Result<NaiveDate> {
+    date.checked_add_signed(by)
+        .ok_or_else(|| AppError::BadRequest("Date is out of range".to_string()))
+}
+
+/// Remaining work on day `index` of `total_days` if `scope` burned down
+/// evenly to zero on the last day.
+fn ideal_remaining(scope: i64, index: i64, total_days: i64) -> f64 {
+    if total_days <= 1 {
+        return 0.0;
+    }
+    scope as f64 * (1.0 - index as f64 / (total_days - 1) as f64)
+}
+
+/// The current and the longest run of consecutive days in `days` (sorted,
+/// without duplicates). The current run still counts when it ended
+/// yesterday, as today is not over yet.
+fn compute_streaks(days: &[NaiveDate], today: NaiveDate) -> (i64, i64) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+
+    for &day in days {
+        run = match previous {
+            Some(previous) if day - previous == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+
+    let current = match previous {
+        Some(last) if today - last <= Duration::days(1) => run,
+        _ => 0,
+    };
+
+    (current, longest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_streaks_count_consecutive_days() {
+        let days = [
+            date("2026-10-01"),
+            date("2026-10-02"),
+            date("2026-10-03"),
+            date("2026-10-07"),
+            date("2026-10-08"),
+        ];
+
+        assert_eq!(compute_streaks(&days, date("2026-10-08")), (2, 3));
+        assert_eq!(compute_streaks(&days, date("2026-10-09")), (2, 3));
+        assert_eq!(compute_streaks(&days, date("2026-10-10")), (0, 3));
+        assert_eq!(compute_streaks(&[], date("2026-10-10")), (0, 0));
+    }
+
+    #[test]
+    fn test_resolve_range_defaults_and_limits() {
+        let today = date("2026-10-18");
+
+        assert_eq!(resolve_range(None, None, today).unwrap(), (date("2026-09-19"), today));
+        assert_eq!(
+            resolve_range(Some(date("2026-10-10")), None, today).unwrap(),
+            (date("2026-10-10"), today)
+        );
+        assert!(resolve_range(Some(today), Some(date("2026-10-01")), today).is_err());
+        assert!(resolve_range(Some(date("2025-01-01")), Some(today), today).is_err());
+        assert!(resolve_range(None, Some(NaiveDate::MIN), today).is_err());
+        assert!(resolve_range(Some(NaiveDate::MAX), None, today).is_err());
+    }
+}
@@ -0,0 +1,6 @@
+// Declare submodules
+pub mod analytics_models;
+pub mod analytics_dto;
+pub mod analytics_repository;
+pub mod analytics_handlers;
+pub mod analytics_service;
@@ -0,0 +1,134 @@
+use axum::{
+    extract::{Query, State},
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, Result},
+    middleware::Authorized,
+    role::permission::Permission,
+    state::AppState,
+    task::task_dto::PaginatedResponse,
+};
+use super::audit_models::{AuditAction, AuditLogEntry, AuditLogFilter};
+
+#[derive(Debug, Deserialize)]
+pub struct AuditLogQuery {
+    page: Option<u32>,
+    limit: Option<u32>,
+    actor_id: Option<Uuid>,
+    action: Option<String>,
+    target_type: Option<String>,
+    target_id: Option<String>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+impl AuditLogQuery {
+    fn filter(&self) -> Result<AuditLogFilter> {
+        let action = match self.action.as_deref() {
+            Some(action) => Some(
+                AuditAction::parse(action)
+                    .ok_or_else(|| AppError::BadRequest(format!("Unknown audit action: {}", action)))?,
+            ),
+            None => None,
+        };
+
+        Ok(AuditLogFilter {
+            actor_id: self.actor_id,
+            action,
+            target_type: self.target_type.clone(),
+            target_id: self.target_id.clone(),
+            from: self.from,
+            to: self.to,
+        })
+    }
+}
+
+/// List audit log entries of the organization, newest first (requires `audit.read`)
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit-log",
+    params(
+        ("page" = Option<u32>, Query, description = "Page number (default 1)"),
+        ("limit" = Option<u32>, Query, description = "Entries per page (default 50, max 200)"),
+        ("actor_id" = Option<Uuid>, Query, description = "Only actions by this user"),
+        ("action" = Option<String>, Query, description = "Only this action, e.g. `user.deactivated`"),
+        ("target_type" = Option<String>, Query, description = "`user`, `role`, `lockout` or `invitation`"),
+        ("target_id" = Option<String>, Query, description = "Only entries about this user, role, lockout or invitation"),
+        ("from" = Option<DateTime<Utc>>, Query, description = "Only entries at or after this time"),
+        ("to" = Option<DateTime<Utc>>, Query, description = "Only entries before this time")
+    ),
+    responses(
+        (status = 200, description = "Matching audit log entries", body = PaginatedResponse<AuditLogEntry>),
+        (status = 400, description = "Unknown action"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission")
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_audit_log(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<Json<PaginatedResponse<AuditLogEntry>>> {
+    authorized.require(Permission::AuditRead)?;
+
+    let filter = query.filter()?;
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let entries = state
+        .audit_service
+        .list(authorized.organization_id, &filter, page, limit)
+        .await?;
+    Ok(Json(entries))
+}
+
+/// Download matching audit log entries as CSV (requires `audit.read`)
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit-log/export",
+    params(
+        ("actor_id" = Option<Uuid>, Query, description = "Only actions by this user"),
+        ("action" = Option<String>, Query, description = "Only this action, e.g. `user.deactivated`"),
+        ("target_type" = Option<String>, Query, description = "`user`, `role`, `lockout` or `invitation`"),
+        ("target_id" = Option<String>, Query, description = "Only entries about this user, role, lockout or invitation"),
+        ("from" = Option<DateTime<Utc>>, Query, description = "Only entries at or after this time"),
+        ("to" = Option<DateTime<Utc>>, Query, description = "Only entries before this time")
+    ),
+    responses(
+        (status = 200, description = "CSV file of the matching entries, newest first", content_type = "text/csv"),
+        (status = 400, description = "Unknown action or too many entries"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission")
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn export_audit_log(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<impl IntoResponse> {
+    authorized.require(Permission::AuditRead)?;
+
+    let csv = state
+        .audit_service
+        .export_csv(authorized.organization_id, &query.filter()?)
+        .await?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (CONTENT_DISPOSITION, "attachment; filename=\"audit-log.csv\""),
+        ],
+        csv,
+    ))
+}
@@ -0,0 +1,236 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// An admin or security-relevant action worth keeping a trace of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    UserUpdated,
+    UserActivated,
+    UserDeactivated,
+    UserDeleted,
+    TwoFactorReset,
+    /// The user turned off their own two-factor authentication
+    TwoFactorDisabled,
+    /// The user changed their password
+    PasswordChanged,
+    /// The user set a new password with a reset link
+    PasswordReset,
+    /// One session signed out, by its user or an admin
+    SessionRevoked,
+    /// A user signed out everywhere but the current session
+    OtherSessionsRevoked,
+    /// A reused refresh token got its whole session revoked
+    SessionReuseRevoked,
+    PersonalAccessTokenRevoked,
+    /// Repeated failed logins locked an account or an IP address
+    LockoutCreated,
+    LockoutCleared,
+    RoleCreated,
+    RoleUpdated,
+    RoleDeleted,
+    RoleAssigned,
+    RoleUnassigned,
+    /// Someone was invited into the organization
+    OrganizationMemberInvited,
+    OrganizationInvitationRevoked,
+    /// An invitee accepted and joined
+    OrganizationMemberAdded,
+    OrganizationMemberRoleChanged,
+    OrganizationMemberRemoved,
+    /// An admin started acting as a user
+    ImpersonationStarted,
+    /// The admin ended the impersonation, or its token ran out
+    ImpersonationEnded,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 26] = [
+        AuditAction::UserUpdated,
+        AuditAction::UserActivated,
+        AuditAction::UserDeactivated,
+        AuditAction::UserDeleted,
+        AuditAction::TwoFactorReset,
+        AuditAction::TwoFactorDisabled,
+        AuditAction::PasswordChanged,
+        AuditAction::PasswordReset,
+        AuditAction::SessionRevoked,
+        AuditAction::OtherSessionsRevoked,
+        AuditAction::SessionReuseRevoked,
+        AuditAction::PersonalAccessTokenRevoked,
+        AuditAction::LockoutCreated,
+        AuditAction::LockoutCleared,
+        AuditAction::RoleCreated,
+        AuditAction::RoleUpdated,
+        AuditAction::RoleDeleted,
+        AuditAction::RoleAssigned,
+        AuditAction::RoleUnassigned,
+        AuditAction::OrganizationMemberInvited,
+        AuditAction::OrganizationInvitationRevoked,
+        AuditAction::OrganizationMemberAdded,
+        AuditAction::OrganizationMemberRoleChanged,
+        AuditAction::OrganizationMemberRemoved,
+        AuditAction::ImpersonationStarted,
+        AuditAction::ImpersonationEnded,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.as_str() == value)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UserUpdated => "user.updated",
+            Self::UserActivated => "user.activated",
+            Self::UserDeactivated => "user.deactivated",
+            Self::UserDeleted => "user.deleted",
+            Self::TwoFactorReset => "user.two_factor_reset",
+            Self::TwoFactorDisabled => "user.two_factor_disabled",
+            Self::PasswordChanged => "user.password_changed",
+            Self::PasswordReset => "user.password_reset",
+            Self::SessionRevoked => "session.revoked",
+            Self::OtherSessionsRevoked => "session.others_revoked",
+            Self::SessionReuseRevoked => "session.reuse_revoked",
+            Self::PersonalAccessTokenRevoked => "token.revoked",
+            Self::LockoutCreated => "lockout.created",
+            Self::LockoutCleared => "lockout.cleared",
+            Self::RoleCreated => "role.created",
+            Self::RoleUpdated => "role.updated",
+            Self::RoleDeleted => "role.deleted",
+            Self::RoleAssigned => "role.assigned",
+            Self::RoleUnassigned => "role.unassigned",
+            Self::OrganizationMemberInvited => "organization.member_invited",
+            Self::OrganizationInvitationRevoked => "organization.invitation_revoked",
+            Self::OrganizationMemberAdded => "organization.member_added",
+            Self::OrganizationMemberRoleChanged => "organization.member_role_changed",
+            Self::OrganizationMemberRemoved => "organization.member_removed",
+            Self::ImpersonationStarted => "impersonation.started",
+            Self::ImpersonationEnded => "impersonation.ended",
+        }
+    }
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What kind of thing an audit entry is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditTarget {
+    User,
+    Role,
+    /// A login lockout, which may be for an IP address rather than a user
+    Lockout,
+    /// An organization invitation, which may be for an address with no account
+    Invitation,
+}
+
+impl AuditTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Role => "role",
+            Self::Lockout => "lockout",
+            Self::Invitation => "invitation",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    /// The organization the actor was acting in
+    pub organization_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    /// e.g. `user.deactivated` or `role.assigned`
+    pub action: String,
+    /// `user`, `role`, `lockout` or `invitation`
+    pub target_type: String,
+    /// A user, lockout or invitation ID, or a role name
+    pub target_id: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One action to record; who did it and from where comes with the request.
+#[derive(Debug, Clone)]
+pub struct NewAuditEntry {
+    /// `None` only for automatic actions that concern no user, e.g. an IP lockout
+    pub organization_id: Option<Uuid>,
+    /// `None` for actions the system took on its own
+    pub actor_id: Option<Uuid>,
+    pub action: AuditAction,
+    pub target_type: AuditTarget,
+    pub target_id: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+impl NewAuditEntry {
+    pub fn new(
+        organization_id: Uuid,
+        actor_id: Uuid,
+        action: AuditAction,
+        target_type: AuditTarget,
+        target_id: impl ToString,
+    ) -> Self {
+        Self {
+            organization_id: Some(organization_id),
+            actor_id: Some(actor_id),
+            action,
+            target_type,
+            target_id: target_id.to_string(),
+            before: None,
+            after: None,
+        }
+    }
+
+    /// An action the system took on its own, such as an automatic lockout.
+    pub fn automatic(
+        organization_id: Option<Uuid>,
+        action: AuditAction,
+        target_type: AuditTarget,
+        target_id: impl ToString,
+    ) -> Self {
+        Self {
+            organization_id,
+            actor_id: None,
+            action,
+            target_type,
+            target_id: target_id.to_string(),
+            before: None,
+            after: None,
+        }
+    }
+
+    /// The state of the target before the action.
+    pub fn with_before(mut self, value: impl Serialize) -> Self {
+        self.before = serde_json::to_value(value).ok();
+        self
+    }
+
+    /// The state of the target after the action.
+    pub fn with_after(mut self, value: impl Serialize) -> Self {
+        self.after = serde_json::to_value(value).ok();
+        self
+    }
+}
+
+/// Narrows the audit log; every field is optional.
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub actor_id: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,131 @@
+use crate::{error::Result, middleware::ClientInfo};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::audit_models::{AuditLogEntry, AuditLogFilter, NewAuditEntry};
+
+/// Filters shared by listing and counting; `$1` is the organization.
+const FILTERS: &str = "organization_id = $1
+    AND ($2::uuid IS NULL OR actor_id = $2)
+    AND ($3::varchar IS NULL OR action = $3)
+    AND ($4::varchar IS NULL OR target_type = $4)
+    AND ($5::varchar IS NULL OR target_id = $5)
+    AND ($6::timestamptz IS NULL OR created_at >= $6)
+    AND ($7::timestamptz IS NULL OR created_at < $7)";
+
+/// The `audit_log` table only ever grows; the database refuses updates and
+/// deletes.
+#[derive(Clone)]
+pub struct AuditRepository {
+    pool: PgPool,
+}
+
+impl AuditRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, entry: &NewAuditEntry, client: &ClientInfo) -> Result<AuditLogEntry> {
+        let entry = sqlx::query_as::<_, AuditLogEntry>(
+            "INSERT INTO audit_log
+                (organization_id, actor_id, action, target_type, target_id, before, after,
+                 ip_address, user_agent, request_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             RETURNING *",
+        )
+        .bind(entry.organization_id)
+        .bind(entry.actor_id)
+        .bind(entry.action.as_str())
+        .bind(entry.target_type.as_str())
+        .bind(&entry.target_id)
+        .bind(&entry.before)
+        .bind(&entry.after)
+        .bind(&client.ip_address)
+        .bind(&client.user_agent)
+        .bind(&client.request_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Insert the entry within `tx`, so it is kept only if the change it
+    /// records is.
+    pub async fn create_with_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        entry: &NewAuditEntry,
+        client: &ClientInfo,
+    ) -> Result<AuditLogEntry> {
+        let entry = sqlx::query_as::<_, AuditLogEntry>(
+            "INSERT INTO audit_log
+                (organization_id, actor_id, action, target_type, target_id, before, after,
+                 ip_address, user_agent, request_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             RETURNING *",
+        )
+        .bind(entry.organization_id)
+        .bind(entry.actor_id)
+        .bind(entry.action.as_str())
+        .bind(entry.target_type.as_str())
+        .bind(&entry.target_id)
+        .bind(&entry.before)
+        .bind(&entry.after)
+        .bind(&client.ip_address)
+        .bind(&client.user_agent)
+        .bind(&client.request_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Matching entries of an organization, newest first.
+    pub async fn find(
+        &self,
+        organization_id: Uuid,
+        filter: &AuditLogFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuditLogEntry>> {
+        let query = format!(
+            "SELECT * FROM audit_log
+             WHERE {filters}
+             ORDER BY created_at DESC, id DESC
+             LIMIT $8 OFFSET $9",
+            filters = FILTERS,
+        );
+
+        let entries = sqlx::query_as::<_, AuditLogEntry>(&query)
+            .bind(organization_id)
+            .bind(filter.actor_id)
+            .bind(filter.action.map(|action| action.as_str()))
+            .bind(filter.target_type.as_deref())
+            .bind(filter.target_id.as_deref())
+            .bind(filter.from)
+            .bind(filter.to)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(entries)
+    }
+
+    pub async fn count(&self, organization_id: Uuid, filter: &AuditLogFilter) -> Result<i64> {
+        let query = format!("SELECT COUNT(*) FROM audit_log WHERE {filters}", filters = FILTERS);
+
+        let count: i64 = sqlx::query_scalar(&query)
+            .bind(organization_id)
+            .bind(filter.actor_id)
+            .bind(filter.action.map(|action| action.as_str()))
+            .bind(filter.target_type.as_deref())
+            .bind(filter.target_id.as_deref())
+            .bind(filter.from)
+            .bind(filter.to)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,165 @@
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, Result},
+    middleware::ClientInfo,
+    task::task_dto::PaginatedResponse,
+};
+use super::{
+    audit_models::{AuditLogEntry, AuditLogFilter, NewAuditEntry},
+    audit_repository::AuditRepository,
+};
+
+/// Most entries a single CSV export may hold.
+const MAX_EXPORT_ROWS: i64 = 10_000;
+
+const CSV_HEADER: [&str; 12] = [
+    "id",
+    "created_at",
+    "organization_id",
+    "actor_id",
+    "action",
+    "target_type",
+    "target_id",
+    "before",
+    "after",
+    "ip_address",
+    "user_agent",
+    "request_id",
+];
+
+#[derive(Clone)]
+pub struct AuditService {
+    repo: AuditRepository,
+}
+
+impl AuditService {
+    pub fn new(repo: AuditRepository) -> Self {
+        Self { repo }
+    }
+
+    /// Append an entry, taking the IP address, user agent and request id
+    /// from the request that caused it.
+    ///
+    /// Called once the action is done, so a failure is logged rather than
+    /// returned: the caller must not report an error for a change that happened.
+    pub async fn record(&self, entry: NewAuditEntry, client: &ClientInfo) {
+        if let Err(e) = self.repo.create(&entry, client).await {
+            tracing::error!(
+                "Failed to write audit entry {} on {} {} by {:?} (request {:?}): {:?}",
+                entry.action,
+                entry.target_type.as_str(),
+                entry.target_id,
+                entry.actor_id,
+                client.request_id,
+                e
+            );
+        }
+    }
+
+    /// Append an entry as part of `tx`, for changes that must not happen
+    /// without their audit row.
+    pub async fn record_with_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        entry: NewAuditEntry,
+        client: &ClientInfo,
+    ) -> Result<AuditLogEntry> {
+        self.repo.create_with_tx(tx, &entry, client).await
+    }
+
+    pub async fn list(
+        &self,
+        organization_id: Uuid,
+        filter: &AuditLogFilter,
+        page: u32,
+        limit: u32,
+    ) -> Result<PaginatedResponse<AuditLogEntry>> {
+        let offset = (i64::from(page) - 1) * i64::from(limit);
+        let entries = self.repo.find(organization_id, filter, i64::from(limit), offset).await?;
+        let total = self.repo.count(organization_id, filter).await?;
+
+        Ok(PaginatedResponse {
+            data: entries,
+            total,
+            page,
+            limit,
+            total_pages: ((total as f64) / (limit as f64)).ceil() as u32,
+        })
+    }
+
+    /// Every matching entry as CSV, newest first.
+    pub async fn export_csv(&self, organization_id: Uuid, filter: &AuditLogFilter) -> Result<String> {
+        if self.repo.count(organization_id, filter).await? > MAX_EXPORT_ROWS {
+            return Err(AppError::BadRequest(format!(
+                "An export can hold at most {} entries; narrow the filter",
+                MAX_EXPORT_ROWS
+            )));
+        }
+
+        let entries = self.repo.find(organization_id, filter, MAX_EXPORT_ROWS, 0).await?;
+        Ok(to_csv(&entries))
+    }
+}
+
+fn to_csv(entries: &[AuditLogEntry]) -> String {
+    let mut csv = CSV_HEADER.join(",");
+    csv.push_str("\r\n");
+
+    for entry in entries {
+        let json = |value: &Option<serde_json::Value>| value.as_ref().map(|value| value.to_string());
+        let fields = [
+            Some(entry.id.to_string()),
+            Some(entry.created_at.to_rfc3339()),
+            entry.organization_id.map(|id| id.to_string()),
+            entry.actor_id.map(|id| id.to_string()),
+            Some(entry.action.clone()),
+            Some(entry.target_type.clone()),
+            Some(entry.target_id.clone()),
+            json(&entry.before),
+            json(&entry.after),
+            entry.ip_address.clone(),
+            entry.user_agent.clone(),
+            entry.request_id.clone(),
+        ];
+
+        let row: Vec<String> = fields
+            .iter()
+            .map(|field| csv_field(field.as_deref().unwrap_or("")))
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+/// Quote a value when needed (RFC 4180). Values a spreadsheet would run as a
+/// formula get a leading `'`, since user agents and names are caller-controlled.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field_quotes_and_defuses_formulas() {
+        assert_eq!(csv_field("user.deleted"), "user.deleted");
+        assert_eq!(csv_field("Mozilla/5.0 (X11, Linux)"), "\"Mozilla/5.0 (X11, Linux)\"");
+        assert_eq!(csv_field("{\"is_active\":true}"), "\"{\"\"is_active\"\":true}\"");
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field(""), "");
+    }
+}
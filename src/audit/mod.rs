@@ -0,0 +1,5 @@
+// Declare submodules
+pub mod audit_models;
+pub mod audit_repository;
+pub mod audit_handlers;
+pub mod audit_service;
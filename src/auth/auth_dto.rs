@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use validator::Validate;
@@ -8,7 +9,8 @@ pub struct RegisterRequest {
     pub username: String,
     #[validate(email)]
     pub email: String,
-    #[validate(length(min = 6))]
+    /// Checked against the password policy
+    #[validate(length(min = 1, max = 128))]
     pub password: String,
 }
 
@@ -27,6 +29,15 @@ pub struct AuthResponse {
     pub user: crate::user::user_models::UserResponse,
 }
 
+/// A short-lived access token acting as another user. It cannot be refreshed.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImpersonationResponse {
+    pub access_token: String,
+    pub impersonation_id: uuid::Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub user: crate::user::user_models::UserResponse,
+}
+
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct RefreshTokenRequest {
     pub refresh_token: String,
@@ -37,3 +48,226 @@ pub struct RefreshTokenResponse {
     pub access_token: String,
     pub refresh_token: String,
 }
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SwitchOrganizationRequest {
+    pub refresh_token: String,
+    /// Must be an organization the user belongs to
+    pub organization_id: uuid::Uuid,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ForgotPasswordRequest {
+    #[validate(email)]
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ResetPasswordRequest {
+    #[validate(length(min = 1))]
+    pub token: String,
+    /// Checked against the password policy
+    #[validate(length(min = 1, max = 128))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ChangePasswordRequest {
+    #[validate(length(min = 1, max = 128))]
+    pub current_password: String,
+    /// Checked against the password policy
+    #[validate(length(min = 1, max = 128))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct VerifyEmailRequest {
+    #[validate(length(min = 1))]
+    pub token: String,
+}
+
+/// Returned by login instead of tokens when the account has 2FA enabled
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorChallengeResponse {
+    pub two_factor_required: bool,
+    pub challenge_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Second login step: the challenge plus either a TOTP code or a recovery code
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct TwoFactorVerifyRequest {
+    #[validate(length(min = 1))]
+    pub challenge_token: String,
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorSetupResponse {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    /// `otpauth://` URI to show as a QR code
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct TwoFactorCodeRequest {
+    #[validate(length(equal = 6))]
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecoveryCodesResponse {
+    /// Shown once; each code can replace a TOTP code a single time
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct DisableTwoFactorRequest {
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+/// A signed-in device; the refresh token itself is never exposed
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionResponse {
+    pub id: uuid::Uuid,
+    pub device_name: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Whether this is the session making the request
+    pub current: bool,
+}
+
+impl SessionResponse {
+    pub fn from_token(token: crate::auth::auth_models::RefreshToken, current_session: Option<uuid::Uuid>) -> Self {
+        Self {
+            current: current_session == Some(token.family_id),
+            id: token.family_id,
+            device_name: token.device_name,
+            user_agent: token.user_agent,
+            ip_address: token.ip_address,
+            created_at: token.created_at,
+            last_used_at: token.last_used_at,
+            expires_at: token.expires_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevokedSessionsResponse {
+    pub revoked: u64,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreatePersonalAccessTokenRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    /// Any of `tasks:read`, `tasks:write`, `messages:read`, `messages:write`
+    #[validate(length(min = 1))]
+    pub scopes: Vec<String>,
+    /// Never expires when omitted
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PersonalAccessTokenResponse {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<crate::auth::auth_models::PersonalAccessToken> for PersonalAccessTokenResponse {
+    fn from(token: crate::auth::auth_models::PersonalAccessToken) -> Self {
+        Self {
+            id: token.id,
+            name: token.name,
+            token_prefix: token.token_prefix,
+            scopes: token.scopes,
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedPersonalAccessTokenResponse {
+    /// Shown once; send it as `Authorization: Bearer <token>`
+    pub token: String,
+    pub personal_access_token: PersonalAccessTokenResponse,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OAuthProviderResponse {
+    pub name: String,
+    pub display_name: String,
+    /// Starts a login with this provider
+    pub login_url: String,
+}
+
+/// The one-time code an OAuth login redirect hands to the app
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct OAuthLoginCodeRequest {
+    #[validate(length(min = 1))]
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuthorizationUrlResponse {
+    /// Open in the browser to continue at the provider
+    pub authorization_url: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IdentityResponse {
+    pub id: uuid::Uuid,
+    pub provider: String,
+    pub email: Option<String>,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<crate::auth::auth_models::UserIdentity> for IdentityResponse {
+    fn from(identity: crate::auth::auth_models::UserIdentity) -> Self {
+        Self {
+            id: identity.id,
+            provider: identity.provider,
+            email: identity.email,
+            last_login_at: identity.last_login_at,
+            created_at: identity.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LockoutResponse {
+    pub id: uuid::Uuid,
+    /// `account` (keyed by lowercased email) or `ip`
+    pub scope: String,
+    pub subject: String,
+    pub failed_attempts: i32,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub last_failed_at: DateTime<Utc>,
+}
+
+impl From<crate::auth::auth_models::LoginThrottle> for LockoutResponse {
+    fn from(throttle: crate::auth::auth_models::LoginThrottle) -> Self {
+        Self {
+            id: throttle.id,
+            scope: throttle.scope,
+            subject: throttle.subject,
+            failed_attempts: throttle.failed_attempts,
+            locked_until: throttle.locked_until,
+            last_failed_at: throttle.last_failed_at,
+        }
+    }
+}
@@ -1,22 +1,43 @@
 use crate::{
+    audit::audit_models::{AuditAction, AuditTarget, NewAuditEntry},
     auth::{
-        auth_dto::{AuthResponse, LoginRequest, RegisterRequest, RefreshTokenRequest, RefreshTokenResponse},
-        create_access_token, create_refresh_token, hash_password,
-        oauth::GoogleUserInfo,
+        auth_dto::{
+            AuthResponse, ForgotPasswordRequest, LoginRequest, RegisterRequest, RefreshTokenRequest,
+            RefreshTokenResponse, ResetPasswordRequest, VerifyEmailRequest, DisableTwoFactorRequest,
+            RecoveryCodesResponse, TwoFactorChallengeResponse, TwoFactorCodeRequest, TwoFactorSetupResponse,
+            TwoFactorVerifyRequest, SessionResponse, RevokedSessionsResponse, CreatePersonalAccessTokenRequest,
+            CreatedPersonalAccessTokenResponse, PersonalAccessTokenResponse, AuthorizationUrlResponse,
+            IdentityResponse, OAuthProviderResponse, OAuthLoginCodeRequest, ChangePasswordRequest,
+            SwitchOrganizationRequest,
+        },
+        auth_service::{LoginOutcome, OAuthCompletion},
     },
     error::{AppError, Result},
+    middleware::{AuthUser, ClientInfo, CurrentImpersonation, CurrentOrganization, CurrentSession},
     state::AppState,
 };
-use axum::{extract::{State, Query}, http::StatusCode, response::{IntoResponse, Redirect}, Json};
-use oauth2::{CsrfToken, PkceCodeChallenge, Scope, AuthorizationCode, TokenResponse};
+use axum::{
+    extract::{Path, State, Query},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    Json,
+};
 use serde::Deserialize;
 use validator::Validate;
 
+/// Cookie tying an OAuth login to the browser that started it.
+const OAUTH_BINDING_COOKIE: &str = "oauth_binding";
+
+#[derive(Deserialize)]
+pub struct OAuthStartQuery {
+    redirect_uri: Option<String>,
+}
+
 #[derive(Deserialize)]
-pub struct GoogleCallback {
-    code: String,
-    #[allow(dead_code)]
+pub struct OAuthCallback {
+    code: Option<String>,
     state: String,
+    error: Option<String>,
 }
 
 /// Register a new user
@@ -33,16 +54,14 @@ pub struct GoogleCallback {
 )]
 pub async fn register(
     State(state): State<AppState>,
+    client: ClientInfo,
     Json(payload): Json<RegisterRequest>,
 ) -> Result<impl IntoResponse> {
     payload.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
-           // Hash password using imported function
-    let password_hash = hash_password(&payload.password)?;
-
     let (user, access_token, refresh_token) = state.auth_service
-        .register(&payload.username, &payload.email, &password_hash)
+        .register(&payload.username, &payload.email, &payload.password, &client)
         .await
 
         .map_err(|e| {
@@ -57,8 +76,8 @@ pub async fn register(
     Ok((
         StatusCode::CREATED,
         Json(AuthResponse {
-          access_token: create_access_token(user.id, &user.email, &user.role, &state.config.jwt_secret)?,
-    refresh_token: create_refresh_token(user.id, &user.email, &user.role, &state.config.jwt_secret)?,
+            access_token,
+            refresh_token,
             user: user.into(),
         }),
     ))
@@ -71,6 +90,7 @@ pub async fn register(
     request_body = LoginRequest,
     responses(
         (status = 200, description = "Login successful", body = AuthResponse),
+        (status = 202, description = "Password accepted, second factor required", body = TwoFactorChallengeResponse),
         (status = 401, description = "Invalid credentials"),
         (status = 500, description = "Internal server error")
     ),
@@ -78,6 +98,7 @@ pub async fn register(
 )]
 pub async fn login(
     State(state): State<AppState>,
+    client: ClientInfo,
     Json(payload): Json<LoginRequest>,
 ) -> Result<impl IntoResponse> {
     payload.validate()
@@ -86,8 +107,59 @@ pub async fn login(
     // let (user, access_token, refresh_token) = state.auth_service
     //     .login(&payload.email, &payload.password)
     //     .await?;
+    let outcome = state.auth_service
+        .login(&payload.email, &payload.password, &client)
+        .await?;
+
+    Ok(login_response(outcome))
+}
+
+fn login_response(outcome: LoginOutcome) -> Response {
+    match outcome {
+        LoginOutcome::Authenticated(user, access_token, refresh_token) => Json(AuthResponse {
+            access_token,
+            refresh_token,
+            user: (*user).into(),
+        })
+        .into_response(),
+        LoginOutcome::TwoFactorRequired { challenge_token, expires_at } => (
+            StatusCode::ACCEPTED,
+            Json(TwoFactorChallengeResponse {
+                two_factor_required: true,
+                challenge_token,
+                expires_at,
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Complete a login with a TOTP code or recovery code
+#[utoipa::path(
+    post,
+    path = "/api/auth/2fa/verify",
+    request_body = TwoFactorVerifyRequest,
+    responses(
+        (status = 200, description = "Login successful", body = AuthResponse),
+        (status = 401, description = "Invalid code or expired challenge")
+    ),
+    tag = "auth"
+)]
+pub async fn verify_two_factor(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Json(payload): Json<TwoFactorVerifyRequest>,
+) -> Result<Json<AuthResponse>> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
     let (user, access_token, refresh_token) = state.auth_service
-        .login(&payload.email, &payload.password)
+        .verify_two_factor_login(
+            &payload.challenge_token,
+            payload.code.as_deref(),
+            payload.recovery_code.as_deref(),
+            &client,
+        )
         .await?;
 
     Ok(Json(AuthResponse {
@@ -97,6 +169,93 @@ pub async fn login(
     }))
 }
 
+/// Start two-factor enrollment
+#[utoipa::path(
+    post,
+    path = "/api/auth/2fa/setup",
+    responses(
+        (status = 200, description = "Secret and otpauth URI for the authenticator app", body = TwoFactorSetupResponse),
+        (status = 400, description = "Two-factor authentication is already enabled"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn setup_two_factor(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<TwoFactorSetupResponse>> {
+    let (secret, otpauth_uri) = state.auth_service.begin_two_factor_setup(user_id).await?;
+    Ok(Json(TwoFactorSetupResponse { secret, otpauth_uri }))
+}
+
+/// Confirm two-factor enrollment with a first code
+#[utoipa::path(
+    post,
+    path = "/api/auth/2fa/confirm",
+    request_body = TwoFactorCodeRequest,
+    responses(
+        (status = 200, description = "Two-factor authentication enabled", body = RecoveryCodesResponse),
+        (status = 400, description = "Invalid code or setup not started"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn confirm_two_factor(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> Result<Json<RecoveryCodesResponse>> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let recovery_codes = state.auth_service.confirm_two_factor_setup(user_id, &payload.code).await?;
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+/// Disable two-factor authentication
+#[utoipa::path(
+    post,
+    path = "/api/auth/2fa/disable",
+    request_body = DisableTwoFactorRequest,
+    responses(
+        (status = 204, description = "Two-factor authentication disabled"),
+        (status = 400, description = "Invalid code"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn disable_two_factor(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    client: ClientInfo,
+    Json(payload): Json<DisableTwoFactorRequest>,
+) -> Result<StatusCode> {
+    state.auth_service
+        .disable_two_factor(user_id, payload.code.as_deref(), payload.recovery_code.as_deref())
+        .await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                organization_id,
+                user_id,
+                AuditAction::TwoFactorDisabled,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_before(serde_json::json!({ "two_factor_enabled": true }))
+            .with_after(serde_json::json!({ "two_factor_enabled": false })),
+            &client,
+        )
+        .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 /// Refresh access token
 #[utoipa::path(
     post,
@@ -110,6 +269,7 @@ pub async fn login(
 )]
 pub async fn refresh_token(
     State(state): State<AppState>,
+    client: ClientInfo,
     Json(payload): Json<RefreshTokenRequest>,
 ) -> Result<impl IntoResponse> {
     // let (access_token, _refresh_token) = state.auth_service
@@ -120,7 +280,34 @@ pub async fn refresh_token(
     //     access_token:create_access_token(&user)?,
     // }))
     let (access_token, refresh_token) = state.auth_service
-        .refresh_access_token(&payload.refresh_token)
+        .refresh_access_token(&payload.refresh_token, &client)
+        .await?;
+
+    Ok(Json(RefreshTokenResponse {
+        access_token,
+        refresh_token,
+    }))
+}
+
+/// Rotate a refresh token into tokens for another of the user's organizations
+#[utoipa::path(
+    post,
+    path = "/api/auth/switch-organization",
+    request_body = SwitchOrganizationRequest,
+    responses(
+        (status = 200, description = "Tokens acting in the chosen organization", body = RefreshTokenResponse),
+        (status = 401, description = "Invalid or expired refresh token"),
+        (status = 403, description = "Not a member of the organization")
+    ),
+    tag = "auth"
+)]
+pub async fn switch_organization(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Json(payload): Json<SwitchOrganizationRequest>,
+) -> Result<impl IntoResponse> {
+    let (access_token, refresh_token) = state.auth_service
+        .switch_organization(&payload.refresh_token, payload.organization_id, &client)
         .await?;
 
     Ok(Json(RefreshTokenResponse {
@@ -148,27 +335,492 @@ pub async fn logout(
     Ok(StatusCode::OK)
 }
 
+/// List the signed-in devices of the current user
+#[utoipa::path(
+    get,
+    path = "/api/auth/sessions",
+    responses(
+        (status = 200, description = "Active sessions", body = Vec<SessionResponse>),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentSession(current): CurrentSession,
+) -> Result<Json<Vec<SessionResponse>>> {
+    let sessions = state.auth_service.list_sessions(user_id).await?;
+
+    Ok(Json(
+        sessions
+            .into_iter()
+            .map(|session| SessionResponse::from_token(session, current))
+            .collect(),
+    ))
+}
+
+/// Sign out one of the current user's devices
+#[utoipa::path(
+    delete,
+    path = "/api/auth/sessions/{session_id}",
+    params(
+        ("session_id" = uuid::Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Session not found")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    client: ClientInfo,
+    Path(session_id): Path<uuid::Uuid>,
+) -> Result<impl IntoResponse> {
+    state.auth_service.revoke_session(user_id, session_id).await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                organization_id,
+                user_id,
+                AuditAction::SessionRevoked,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_before(serde_json::json!({ "session_id": session_id })),
+            &client,
+        )
+        .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Sign out every device except the current one
+#[utoipa::path(
+    post,
+    path = "/api/auth/sessions/revoke-others",
+    responses(
+        (status = 200, description = "Other sessions revoked", body = RevokedSessionsResponse),
+        (status = 400, description = "Current session unknown"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_other_sessions(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    CurrentSession(current): CurrentSession,
+    client: ClientInfo,
+) -> Result<Json<RevokedSessionsResponse>> {
+    let current = current.ok_or_else(|| {
+        AppError::BadRequest("Current session unknown, please log in again".to_string())
+    })?;
+
+    let revoked = state.auth_service.revoke_other_sessions(user_id, current).await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                organization_id,
+                user_id,
+                AuditAction::OtherSessionsRevoked,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_after(serde_json::json!({ "kept_session_id": current, "revoked": revoked })),
+            &client,
+        )
+        .await;
+    Ok(Json(RevokedSessionsResponse { revoked }))
+}
+
+/// End the impersonation session of the token in use
+#[utoipa::path(
+    post,
+    path = "/api/auth/impersonation/end",
+    responses(
+        (status = 204, description = "Impersonation ended; the token no longer works"),
+        (status = 400, description = "Not an impersonation token"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn end_impersonation(
+    State(state): State<AppState>,
+    CurrentImpersonation(impersonation): CurrentImpersonation,
+    client: ClientInfo,
+) -> Result<impl IntoResponse> {
+    let impersonation = impersonation
+        .ok_or_else(|| AppError::BadRequest("Not impersonating a user".to_string()))?;
+
+    let session = state.auth_service.end_impersonation(impersonation.session_id).await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                session.organization_id,
+                session.admin_id,
+                AuditAction::ImpersonationEnded,
+                AuditTarget::User,
+                session.user_id,
+            )
+            .with_after(serde_json::json!({ "impersonation_id": session.id, "ended": "by_admin" })),
+            &client,
+        )
+        .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the current user's personal access tokens
+#[utoipa::path(
+    get,
+    path = "/api/auth/tokens",
+    responses(
+        (status = 200, description = "Personal access tokens", body = Vec<PersonalAccessTokenResponse>),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_personal_access_tokens(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<Vec<PersonalAccessTokenResponse>>> {
+    let tokens = state.auth_service.list_personal_access_tokens(user_id).await?;
+    Ok(Json(tokens.into_iter().map(Into::into).collect()))
+}
+
+/// Create a personal access token for scripts and CI
+#[utoipa::path(
+    post,
+    path = "/api/auth/tokens",
+    request_body = CreatePersonalAccessTokenRequest,
+    responses(
+        (status = 201, description = "Token created; it is shown only once", body = CreatedPersonalAccessTokenResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Validation error")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn create_personal_access_token(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Json(payload): Json<CreatePersonalAccessTokenRequest>,
+) -> Result<impl IntoResponse> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let (token, stored) = state
+        .auth_service
+        .create_personal_access_token(user_id, &payload.name, &payload.scopes, payload.expires_at)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedPersonalAccessTokenResponse {
+            token,
+            personal_access_token: stored.into(),
+        }),
+    ))
+}
+
+/// Revoke a personal access token
+#[utoipa::path(
+    delete,
+    path = "/api/auth/tokens/{token_id}",
+    params(
+        ("token_id" = uuid::Uuid, Path, description = "Token ID")
+    ),
+    responses(
+        (status = 204, description = "Token revoked"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Token not found")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_personal_access_token(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    client: ClientInfo,
+    Path(token_id): Path<uuid::Uuid>,
+) -> Result<impl IntoResponse> {
+    state.auth_service.revoke_personal_access_token(user_id, token_id).await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                organization_id,
+                user_id,
+                AuditAction::PersonalAccessTokenRevoked,
+                AuditTarget::User,
+                user_id,
+            )
+        .with_before(serde_json::json!({ "token_id": token_id })),
+            &client,
+        )
+        .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Request a password reset email
+#[utoipa::path(
+    post,
+    path = "/api/auth/forgot-password",
+    request_body = ForgotPasswordRequest,
+    responses(
+        (status = 202, description = "If the email belongs to an account, a reset link has been sent"),
+        (status = 400, description = "Validation error")
+    ),
+    tag = "auth"
+)]
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    Json(payload): Json<ForgotPasswordRequest>,
+) -> Result<impl IntoResponse> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    state.auth_service.request_password_reset(&payload.email).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Choose a new password with a reset token
+#[utoipa::path(
+    post,
+    path = "/api/auth/reset-password",
+    request_body = ResetPasswordRequest,
+    responses(
+        (status = 200, description = "Password changed and all sessions signed out"),
+        (status = 400, description = "Invalid or expired reset token")
+    ),
+    tag = "auth"
+)]
+pub async fn reset_password(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Json(payload): Json<ResetPasswordRequest>,
+) -> Result<impl IntoResponse> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    state.auth_service.reset_password(&payload.token, &payload.new_password, &client).await?;
+    Ok(StatusCode::OK)
+}
+
+/// Change the current user's password, signing out their other devices
+#[utoipa::path(
+    post,
+    path = "/api/auth/change-password",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 204, description = "Password changed and other sessions signed out"),
+        (status = 400, description = "Password rejected by the policy"),
+        (status = 401, description = "Current password is incorrect"),
+        (status = 429, description = "Too many failed attempts")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn change_password(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    CurrentSession(current): CurrentSession,
+    client: ClientInfo,
+    Json(payload): Json<ChangePasswordRequest>,
+) -> Result<impl IntoResponse> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    state
+        .auth_service
+        .change_password(user_id, &payload.current_password, &payload.new_password, current, &client)
+        .await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                organization_id,
+                user_id,
+                AuditAction::PasswordChanged,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_after(serde_json::json!({ "kept_session_id": current })),
+            &client,
+        )
+        .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Verify an email address with the token from the verification email
+#[utoipa::path(
+    post,
+    path = "/api/auth/verify-email",
+    request_body = VerifyEmailRequest,
+    responses(
+        (status = 200, description = "Email verified"),
+        (status = 400, description = "Invalid or expired verification token")
+    ),
+    tag = "auth"
+)]
+pub async fn verify_email(
+    State(state): State<AppState>,
+    Json(payload): Json<VerifyEmailRequest>,
+) -> Result<impl IntoResponse> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    state.auth_service.verify_email(&payload.token).await?;
+    Ok(StatusCode::OK)
+}
+
+/// Resend the verification email to the current user
+#[utoipa::path(
+    post,
+    path = "/api/auth/verify-email/resend",
+    responses(
+        (status = 202, description = "Verification email sent"),
+        (status = 400, description = "Email is already verified"),
+        (status = 401, description = "Unauthorized"),
+        (status = 429, description = "Requested again too soon")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn resend_verification_email(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+) -> Result<impl IntoResponse> {
+    state.auth_service.resend_email_verification(user_id).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// List the enabled login providers
+#[utoipa::path(
+    get,
+    path = "/api/auth/oauth/providers",
+    responses(
+        (status = 200, description = "Login providers", body = Vec<OAuthProviderResponse>),
+    ),
+    tag = "auth"
+)]
+pub async fn list_oauth_providers(State(state): State<AppState>) -> Json<Vec<OAuthProviderResponse>> {
+    Json(
+        state
+            .oauth_providers
+            .list()
+            .into_iter()
+            .map(|provider| OAuthProviderResponse {
+                name: provider.name.clone(),
+                display_name: provider.display_name.clone(),
+                login_url: format!("/api/auth/oauth/{}", provider.name),
+            })
+            .collect(),
+    )
+}
+
+/// Start a login with an OAuth / OpenID Connect provider
+#[utoipa::path(
+    get,
+    path = "/api/auth/oauth/{provider}",
+    params(
+        ("provider" = String, Path, description = "Provider name, e.g. google"),
+        ("redirect_uri" = Option<String>, Query, description = "Allow-listed app URL to return to with a one-time login code")
+    ),
+    responses(
+        (status = 302, description = "Redirect to the provider"),
+        (status = 400, description = "redirect_uri is not allowed"),
+        (status = 404, description = "Login provider not found")
+    ),
+    tag = "auth"
+)]
+pub async fn oauth_login(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    Query(query): Query<OAuthStartQuery>,
+) -> Result<Response> {
+    let provider = state.oauth_providers.get(&provider)?;
+    let start = state.auth_service
+        .begin_oauth(provider, None, query.redirect_uri.as_deref())
+        .await?;
+
+    let secure = if provider.redirect_uri.starts_with("https://") { "; Secure" } else { "" };
+    let cookie = format!(
+        "{}={}; HttpOnly; SameSite=Lax; Path=/api/auth; Max-Age=600{}",
+        OAUTH_BINDING_COOKIE,
+        start.browser_binding.unwrap_or_default(),
+        secure
+    );
+
+    Ok(([(header::SET_COOKIE, cookie)], Redirect::to(&start.authorization_url)).into_response())
+}
+
+/// Handle the provider's callback: log in, or finish linking
+#[utoipa::path(
+    get,
+    path = "/api/auth/oauth/{provider}/callback",
+    params(
+        ("provider" = String, Path, description = "Provider name, e.g. google"),
+        ("code" = Option<String>, Query, description = "Authorization code"),
+        ("state" = String, Query, description = "State passed to the provider"),
+        ("error" = Option<String>, Query, description = "Set by the provider when the login was not approved")
+    ),
+    responses(
+        (status = 200, description = "Logged in, or identity linked", body = AuthResponse),
+        (status = 202, description = "Second factor required", body = TwoFactorChallengeResponse),
+        (status = 303, description = "Back to the app's redirect URL with a login code, or an error"),
+        (status = 400, description = "Invalid state, another browser, email already registered, or login linked to another account"),
+        (status = 401, description = "Code exchange failed"),
+        (status = 404, description = "Login provider not found")
+    ),
+    tag = "auth"
+)]
+pub async fn oauth_callback(
+    State(state): State<AppState>,
+    client_info: ClientInfo,
+    headers: HeaderMap,
+    Path(provider): Path<String>,
+    Query(params): Query<OAuthCallback>,
+) -> Result<Response> {
+    complete_oauth(&state, &client_info, &headers, &provider, params).await
+}
+
 /// Initiate Google OAuth flow
 #[utoipa::path(
     get,
     path = "/api/auth/google",
+    params(
+        ("redirect_uri" = Option<String>, Query, description = "Allow-listed app URL to return to with a one-time login code")
+    ),
     responses(
         (status = 302, description = "Redirect to Google OAuth"),
+        (status = 400, description = "redirect_uri is not allowed"),
     ),
     tag = "auth"
 )]
-pub async fn google_login(State(state): State<AppState>) -> impl IntoResponse {
-    let (pkce_challenge, _pkce_verifier) = PkceCodeChallenge::new_random_sha256();
-
-    let (auth_url, _csrf_token) = state
-        .oauth_client
-        .authorize_url(CsrfToken::new_random)
-        .add_scope(Scope::new("email".to_string()))
-        .add_scope(Scope::new("profile".to_string()))
-        .set_pkce_challenge(pkce_challenge)
-        .url();
-
-    Redirect::to(auth_url.as_str())
+pub async fn google_login(
+    State(state): State<AppState>,
+    query: Query<OAuthStartQuery>,
+) -> Result<Response> {
+    oauth_login(State(state), Path("google".to_string()), query).await
 }
 
 /// Handle Google OAuth callback
@@ -176,51 +828,185 @@ pub async fn google_login(State(state): State<AppState>) -> impl IntoResponse {
     get,
     path = "/api/auth/google/callback",
     params(
-        ("code" = String, Query, description = "Authorization code from Google"),
-        ("state" = String, Query, description = "CSRF token")
+        ("code" = Option<String>, Query, description = "Authorization code from Google"),
+        ("state" = String, Query, description = "State passed to Google"),
+        ("error" = Option<String>, Query, description = "Set by Google when the login was not approved")
     ),
     responses(
         (status = 200, description = "OAuth successful", body = AuthResponse),
+        (status = 202, description = "Second factor required", body = TwoFactorChallengeResponse),
+        (status = 303, description = "Back to the app's redirect URL with a login code, or an error"),
+        (status = 400, description = "Invalid state, or login started in another browser"),
         (status = 500, description = "OAuth failed")
     ),
     tag = "auth"
 )]
 pub async fn google_callback(
     State(state): State<AppState>,
-    Query(params): Query<GoogleCallback>,
-) -> Result<Json<AuthResponse>> {
-    let token_result = state
-        .oauth_client
-        .exchange_code(AuthorizationCode::new(params.code))
-        .request_async(oauth2::reqwest::async_http_client)
-        .await
-        .map_err(|_| AppError::Authentication("Failed to exchange code".to_string()))?;
+    client_info: ClientInfo,
+    headers: HeaderMap,
+    Query(params): Query<OAuthCallback>,
+) -> Result<Response> {
+    complete_oauth(&state, &client_info, &headers, "google", params).await
+}
 
-    let access_token_google = token_result.access_token().secret();
+async fn complete_oauth(
+    state: &AppState,
+    client_info: &ClientInfo,
+    headers: &HeaderMap,
+    provider_name: &str,
+    params: OAuthCallback,
+) -> Result<Response> {
+    let provider = state.oauth_providers.get(provider_name)?;
+    let completion = state.auth_service
+        .complete_oauth(
+            provider,
+            &params.state,
+            params.code.as_deref(),
+            params.error.as_deref(),
+            cookie_value(headers, OAUTH_BINDING_COOKIE),
+            client_info,
+        )
+        .await?;
 
-    let client = reqwest::Client::new();
-    let user_info: GoogleUserInfo = client
-        .get("https://www.googleapis.com/oauth2/v2/userinfo")
-        .bearer_auth(access_token_google)
-        .send()
-        .await
-        .map_err(|_| AppError::Authentication("Failed to get user info".to_string()))?
-        .json()
-        .await
-        .map_err(|_| AppError::Authentication("Failed to parse user info".to_string()))?;
+    let response = match completion {
+        OAuthCompletion::LoggedIn(outcome) => login_response(outcome),
+        OAuthCompletion::Linked(identity) => Json(IdentityResponse::from(identity)).into_response(),
+        OAuthCompletion::Redirect(url) => Redirect::to(&url).into_response(),
+    };
 
-    let (user, access_token, refresh_token) = state.auth_service
-        .google_login_or_register(
-            &user_info.name,
-            &user_info.email,
-            &user_info.id,
-            user_info.picture.as_deref().unwrap_or(""),
-        )
+    let clear_cookie = format!("{}=; HttpOnly; SameSite=Lax; Path=/api/auth; Max-Age=0", OAUTH_BINDING_COOKIE);
+    Ok(([(header::SET_COOKIE, clear_cookie)], response).into_response())
+}
+
+fn cookie_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Trade the code from an OAuth login redirect for tokens
+#[utoipa::path(
+    post,
+    path = "/api/auth/oauth/exchange",
+    request_body = OAuthLoginCodeRequest,
+    responses(
+        (status = 200, description = "Logged in", body = AuthResponse),
+        (status = 202, description = "Second factor required", body = TwoFactorChallengeResponse),
+        (status = 400, description = "Invalid or expired login code")
+    ),
+    tag = "auth"
+)]
+pub async fn exchange_oauth_login_code(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Json(payload): Json<OAuthLoginCodeRequest>,
+) -> Result<Response> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let outcome = state.auth_service
+        .exchange_oauth_login_code(&payload.code, &client)
         .await?;
 
-    Ok(Json(AuthResponse {
-        access_token,
-        refresh_token,
-        user: user.into(),
+    Ok(login_response(outcome))
+}
+
+/// List the login providers linked to the current user
+#[utoipa::path(
+    get,
+    path = "/api/auth/identities",
+    responses(
+        (status = 200, description = "Linked identities", body = Vec<IdentityResponse>),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_identities(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<Vec<IdentityResponse>>> {
+    let identities = state.auth_service.list_identities(user_id).await?;
+    Ok(Json(identities.into_iter().map(Into::into).collect()))
+}
+
+/// Start linking a login provider to the current user
+#[utoipa::path(
+    post,
+    path = "/api/auth/oauth/{provider}/link",
+    params(
+        ("provider" = String, Path, description = "Provider name, e.g. github"),
+        ("redirect_uri" = Option<String>, Query, description = "Allow-listed app URL to return to when done")
+    ),
+    responses(
+        (status = 200, description = "Open the URL to continue at the provider", body = AuthorizationUrlResponse),
+        (status = 400, description = "redirect_uri is not allowed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Login provider not found")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn link_identity(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(provider): Path<String>,
+    Query(query): Query<OAuthStartQuery>,
+) -> Result<Json<AuthorizationUrlResponse>> {
+    let provider = state.oauth_providers.get(&provider)?;
+    let start = state.auth_service
+        .begin_oauth(provider, Some(user_id), query.redirect_uri.as_deref())
+        .await?;
+
+    Ok(Json(AuthorizationUrlResponse {
+        authorization_url: start.authorization_url,
     }))
 }
+
+/// Unlink a login provider from the current user
+#[utoipa::path(
+    delete,
+    path = "/api/auth/identities/{identity_id}",
+    params(
+        ("identity_id" = uuid::Uuid, Path, description = "Identity ID")
+    ),
+    responses(
+        (status = 204, description = "Identity unlinked"),
+        (status = 400, description = "It is the only way left to sign in"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Identity not found")
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn unlink_identity(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(identity_id): Path<uuid::Uuid>,
+) -> Result<impl IntoResponse> {
+    state.auth_service.unlink_identity(user_id, identity_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Public keys for verifying our JWTs, by `kid`
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    responses(
+        (status = 200, description = "JSON Web Key Set", body = Object),
+    ),
+    tag = "auth"
+)]
+pub async fn jwks(State(state): State<AppState>) -> impl IntoResponse {
+    // Keys are published a day before they sign, so short caching is safe
+    (
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(state.jwt_keys.jwks()),
+    )
+}
@@ -6,7 +6,167 @@ use uuid::Uuid;
 pub struct RefreshToken {
     pub id: Uuid,
     pub user_id: Uuid,
-    pub token: String,
+    /// Shared by every token of one session; it is the session id
+    pub family_id: Uuid,
+    pub token_hash: String,
     pub expires_at: DateTime<Utc>,
+    /// Set once the token has been rotated or its family revoked
+    pub revoked: bool,
+    /// When the session started; carried over on rotation
     pub created_at: DateTime<Utc>,
+    pub device_name: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub last_used_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PasswordResetToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct EmailVerificationToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UserTwoFactor {
+    pub user_id: Uuid,
+    pub secret: String,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TwoFactorChallenge {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub attempts: i32,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PersonalAccessToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub token_hash: String,
+    /// First characters of the token, to tell tokens apart in listings
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UserIdentity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Name of the login provider, e.g. `google`
+    pub provider: String,
+    /// The user's id at the provider
+    pub subject: String,
+    pub email: Option<String>,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OAuthAuthorization {
+    pub id: Uuid,
+    pub state_hash: String,
+    pub provider: String,
+    pub pkce_verifier: String,
+    pub link_user_id: Option<Uuid>,
+    pub redirect_url: Option<String>,
+    pub browser_binding_hash: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LoginThrottle {
+    pub id: Uuid,
+    pub scope: String,
+    pub subject: String,
+    pub failed_attempts: i32,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub last_failed_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Private keys never leave the server; only `public_jwk` is published.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct JwtSigningKey {
+    pub kid: String,
+    pub algorithm: String,
+    pub private_key: Vec<u8>,
+    pub public_jwk: serde_json::Value,
+    pub activates_at: DateTime<Utc>,
+    pub retires_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// An admin acting as a user; open until `ended_at` is set.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ImpersonationSession {
+    pub id: Uuid,
+    pub admin_id: Uuid,
+    pub user_id: Uuid,
+    pub organization_id: Uuid,
+    pub reason: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SecurityEvent {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub event_type: String,
+    pub details: Option<serde_json::Value>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityEventKind {
+    /// An already rotated refresh token was presented again
+    RefreshTokenReuse,
+    /// Too many failed logins locked an account or an IP address
+    LoginLockout,
+    /// An admin lifted a lockout early
+    LockoutCleared,
+    /// The user changed their password while signed in
+    PasswordChanged,
+}
+
+impl std::fmt::Display for SecurityEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecurityEventKind::RefreshTokenReuse => write!(f, "refresh_token_reuse"),
+            SecurityEventKind::LoginLockout => write!(f, "login_lockout"),
+            SecurityEventKind::LockoutCleared => write!(f, "lockout_cleared"),
+            SecurityEventKind::PasswordChanged => write!(f, "password_changed"),
+        }
+    }
 }
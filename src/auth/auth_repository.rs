@@ -1,8 +1,14 @@
 use crate::error::Result;
+use crate::middleware::ClientInfo;
 use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
-use super::auth_models::RefreshToken;
+use super::lockout::{LockoutScope, FAILED_ATTEMPT_WINDOW_HOURS};
+use super::auth_models::{
+    EmailVerificationToken, PasswordResetToken, PersonalAccessToken, RefreshToken, SecurityEvent, SecurityEventKind,
+    ImpersonationSession, JwtSigningKey, LoginThrottle, OAuthAuthorization, TwoFactorChallenge, UserIdentity,
+    UserTwoFactor,
+};
 
 #[derive(Clone)]
 pub struct RefreshTokenRepository {
@@ -14,20 +20,27 @@ impl RefreshTokenRepository {
         Self { pool }
     }
 
+    /// Start a new family (session) with its first token.
     pub async fn create(
         &self,
+        family_id: Uuid,
         user_id: Uuid,
-        token: &str,
+        token_hash: &str,
         expires_at: DateTime<Utc>,
+        client: &ClientInfo,
     ) -> Result<RefreshToken> {
         let refresh_token = sqlx::query_as::<_, RefreshToken>(
-            "INSERT INTO refresh_tokens (user_id, token, expires_at)
-             VALUES ($1, $2, $3)
+            "INSERT INTO refresh_tokens (family_id, user_id, token_hash, expires_at, device_name, user_agent, ip_address)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
              RETURNING *",
         )
+        .bind(family_id)
         .bind(user_id)
-        .bind(token)
+        .bind(token_hash)
         .bind(expires_at)
+        .bind(&client.device_name)
+        .bind(&client.user_agent)
+        .bind(&client.ip_address)
         .fetch_one(&self.pool)
         .await?;
 
@@ -37,42 +50,111 @@ impl RefreshTokenRepository {
     pub async fn create_with_tx(
         &self,
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        family_id: Uuid,
         user_id: Uuid,
-        token: &str,
+        token_hash: &str,
         expires_at: DateTime<Utc>,
+        client: &ClientInfo,
     ) -> Result<RefreshToken> {
         let refresh_token = sqlx::query_as::<_, RefreshToken>(
-            "INSERT INTO refresh_tokens (user_id, token, expires_at)
-             VALUES ($1, $2, $3)
+            "INSERT INTO refresh_tokens (family_id, user_id, token_hash, expires_at, device_name, user_agent, ip_address)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
              RETURNING *",
         )
+        .bind(family_id)
         .bind(user_id)
-        .bind(token)
+        .bind(token_hash)
         .bind(expires_at)
+        .bind(&client.device_name)
+        .bind(&client.user_agent)
+        .bind(&client.ip_address)
         .fetch_one(&mut **tx)
         .await?;
 
         Ok(refresh_token)
     }
 
-    pub async fn find_by_token(&self, token: &str) -> Result<Option<RefreshToken>> {
+    /// Revoke `current_id` and issue its successor in the same family, atomically.
+    ///
+    /// Returns `None` if the token was already revoked or expired by the time
+    /// the update ran, e.g. because a concurrent request rotated it first.
+    pub async fn rotate(
+        &self,
+        current_id: Uuid,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+        client: &ClientInfo,
+    ) -> Result<Option<RefreshToken>> {
+        let mut tx = self.pool.begin().await?;
+
+        let current = sqlx::query_as::<_, RefreshToken>(
+            "UPDATE refresh_tokens SET revoked = true
+             WHERE id = $1 AND NOT revoked AND expires_at > NOW()
+             RETURNING *",
+        )
+        .bind(current_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(current) = current else {
+            return Ok(None);
+        };
+
+        let refresh_token = sqlx::query_as::<_, RefreshToken>(
+            "INSERT INTO refresh_tokens
+                (family_id, user_id, token_hash, expires_at, device_name, user_agent, ip_address, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             RETURNING *",
+        )
+        .bind(current.family_id)
+        .bind(current.user_id)
+        .bind(token_hash)
+        .bind(expires_at)
+        .bind(&current.device_name)
+        .bind(client.user_agent.as_ref().or(current.user_agent.as_ref()))
+        .bind(client.ip_address.as_ref().or(current.ip_address.as_ref()))
+        .bind(current.created_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(refresh_token))
+    }
+
+    /// Look up an unexpired token by hash, including ones already rotated.
+    pub async fn find_by_hash(&self, token_hash: &str) -> Result<Option<RefreshToken>> {
         let refresh_token = sqlx::query_as::<_, RefreshToken>(
             "SELECT * FROM refresh_tokens
-             WHERE token = $1 AND expires_at > NOW()",
+             WHERE token_hash = $1 AND expires_at > NOW()",
         )
-        .bind(token)
+        .bind(token_hash)
         .fetch_optional(&self.pool)
         .await?;
 
         Ok(refresh_token)
     }
 
-    pub async fn delete_by_token(&self, token: &str) -> Result<()> {
-        sqlx::query("DELETE FROM refresh_tokens WHERE token = $1")
-            .bind(token)
+    /// Revoke every token of a family, keeping the rows so later use is still detected.
+    pub async fn revoke_family(&self, family_id: Uuid) -> Result<u64> {
+        let result = sqlx::query("UPDATE refresh_tokens SET revoked = true WHERE family_id = $1 AND NOT revoked")
+            .bind(family_id)
             .execute(&self.pool)
             .await?;
 
+        Ok(result.rows_affected())
+    }
+
+    /// End the session the token belongs to.
+    pub async fn delete_family_by_hash(&self, token_hash: &str) -> Result<()> {
+        sqlx::query(
+            "DELETE FROM refresh_tokens
+             WHERE family_id = (SELECT family_id FROM refresh_tokens WHERE token_hash = $1)",
+        )
+        .bind(token_hash)
+        .execute(&self.pool)
+        .await?;
+
         Ok(())
     }
 
@@ -85,6 +167,53 @@ impl RefreshTokenRepository {
         Ok(())
     }
 
+    /// Sessions that can still be refreshed, as the live token of each family,
+    /// most recently used first.
+    pub async fn find_active_by_user(&self, user_id: Uuid) -> Result<Vec<RefreshToken>> {
+        let sessions = sqlx::query_as::<_, RefreshToken>(
+            "SELECT * FROM refresh_tokens
+             WHERE user_id = $1 AND expires_at > NOW() AND NOT revoked
+             ORDER BY last_used_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    pub async fn is_session_active(&self, family_id: Uuid) -> Result<bool> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM refresh_tokens
+             WHERE family_id = $1 AND expires_at > NOW() AND NOT revoked",
+        )
+        .bind(family_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count > 0)
+    }
+
+    pub async fn delete_session(&self, user_id: Uuid, family_id: Uuid) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM refresh_tokens WHERE family_id = $1 AND user_id = $2")
+            .bind(family_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_other_sessions(&self, user_id: Uuid, keep_family_id: Uuid) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM refresh_tokens WHERE user_id = $1 AND family_id <> $2")
+            .bind(user_id)
+            .bind(keep_family_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
     pub async fn delete_expired(&self) -> Result<u64> {
         let result = sqlx::query("DELETE FROM refresh_tokens WHERE expires_at <= NOW()")
             .execute(&self.pool)
@@ -93,3 +222,844 @@ impl RefreshTokenRepository {
         Ok(result.rows_affected())
     }
 }
+
+#[derive(Clone)]
+pub struct PasswordResetRepository {
+    pool: PgPool,
+}
+
+impl PasswordResetRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a new reset token, discarding any the user has not used yet.
+    pub async fn replace_for_user(
+        &self,
+        user_id: Uuid,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<PasswordResetToken> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM password_reset_tokens WHERE user_id = $1 AND used_at IS NULL")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let reset_token = sqlx::query_as::<_, PasswordResetToken>(
+            "INSERT INTO password_reset_tokens (user_id, token_hash, expires_at)
+             VALUES ($1, $2, $3)
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(token_hash)
+        .bind(expires_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(reset_token)
+    }
+
+    /// Mark a valid token as used. Returns `None` if it is unknown, expired or already used.
+    pub async fn consume_with_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        token_hash: &str,
+    ) -> Result<Option<PasswordResetToken>> {
+        let reset_token = sqlx::query_as::<_, PasswordResetToken>(
+            "UPDATE password_reset_tokens SET used_at = NOW()
+             WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+             RETURNING *",
+        )
+        .bind(token_hash)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(reset_token)
+    }
+}
+
+#[derive(Clone)]
+pub struct EmailVerificationRepository {
+    pool: PgPool,
+}
+
+impl EmailVerificationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a new verification token, discarding any the user has not used yet.
+    pub async fn replace_for_user(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<EmailVerificationToken> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM email_verification_tokens WHERE user_id = $1 AND used_at IS NULL")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let verification_token = sqlx::query_as::<_, EmailVerificationToken>(
+            "INSERT INTO email_verification_tokens (user_id, email, token_hash, expires_at)
+             VALUES ($1, $2, $3, $4)
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(email)
+        .bind(token_hash)
+        .bind(expires_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(verification_token)
+    }
+
+    pub async fn find_latest_for_user(&self, user_id: Uuid) -> Result<Option<EmailVerificationToken>> {
+        let verification_token = sqlx::query_as::<_, EmailVerificationToken>(
+            "SELECT * FROM email_verification_tokens
+             WHERE user_id = $1
+             ORDER BY created_at DESC
+             LIMIT 1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(verification_token)
+    }
+
+    /// Mark the user verified if the token is valid and was sent to their current email.
+    /// Returns the user id, or `None` if the token is unknown, expired, used or stale.
+    pub async fn consume(&self, token_hash: &str) -> Result<Option<Uuid>> {
+        let mut tx = self.pool.begin().await?;
+
+        let verification_token = sqlx::query_as::<_, EmailVerificationToken>(
+            "UPDATE email_verification_tokens SET used_at = NOW()
+             WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+             RETURNING *",
+        )
+        .bind(token_hash)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(verification_token) = verification_token else {
+            return Ok(None);
+        };
+
+        let verified = sqlx::query(
+            "UPDATE users SET email_verified = true, updated_at = NOW()
+             WHERE id = $1 AND email = $2",
+        )
+        .bind(verification_token.user_id)
+        .bind(&verification_token.email)
+        .execute(&mut *tx)
+        .await?;
+
+        if verified.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        tx.commit().await?;
+
+        Ok(Some(verification_token.user_id))
+    }
+}
+
+#[derive(Clone)]
+pub struct TwoFactorRepository {
+    pool: PgPool,
+}
+
+impl TwoFactorRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_by_user(&self, user_id: Uuid) -> Result<Option<UserTwoFactor>> {
+        let two_factor = sqlx::query_as::<_, UserTwoFactor>("SELECT * FROM user_two_factor WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(two_factor)
+    }
+
+    /// Start (or restart) enrollment with a new, unconfirmed secret.
+    pub async fn upsert_pending(&self, user_id: Uuid, secret: &str) -> Result<UserTwoFactor> {
+        let two_factor = sqlx::query_as::<_, UserTwoFactor>(
+            "INSERT INTO user_two_factor (user_id, secret)
+             VALUES ($1, $2)
+             ON CONFLICT (user_id) DO UPDATE SET
+                secret = EXCLUDED.secret,
+                confirmed_at = NULL,
+                last_used_step = NULL,
+                created_at = NOW()
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(secret)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(two_factor)
+    }
+
+    /// Remember the time step of an accepted code. Returns `false` if that step
+    /// (or a later one) was already used.
+    pub async fn record_used_step(&self, user_id: Uuid, step: i64) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE user_two_factor SET last_used_step = $2
+             WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)",
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Confirm enrollment, enable 2FA and replace the recovery codes.
+    pub async fn enable(&self, user_id: Uuid, recovery_code_hashes: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE user_two_factor SET confirmed_at = NOW() WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE users SET two_factor_enabled = true, updated_at = NOW() WHERE id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM two_factor_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO two_factor_recovery_codes (user_id, code_hash)
+             SELECT $1, UNNEST($2::text[])",
+        )
+        .bind(user_id)
+        .bind(recovery_code_hashes)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Turn 2FA off and forget the secret, recovery codes and open challenges.
+    pub async fn disable(&self, user_id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for statement in [
+            "DELETE FROM user_two_factor WHERE user_id = $1",
+            "DELETE FROM two_factor_recovery_codes WHERE user_id = $1",
+            "DELETE FROM two_factor_challenges WHERE user_id = $1",
+            "UPDATE users SET two_factor_enabled = false, updated_at = NOW() WHERE id = $1",
+        ] {
+            sqlx::query(statement).bind(user_id).execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Use up a recovery code. Returns `false` if it is unknown or already used.
+    pub async fn consume_recovery_code(&self, user_id: Uuid, code_hash: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE two_factor_recovery_codes SET used_at = NOW()
+             WHERE id = (
+                 SELECT id FROM two_factor_recovery_codes
+                 WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+                 LIMIT 1
+             )",
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn create_challenge(
+        &self,
+        user_id: Uuid,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<TwoFactorChallenge> {
+        let challenge = sqlx::query_as::<_, TwoFactorChallenge>(
+            "INSERT INTO two_factor_challenges (user_id, token_hash, expires_at)
+             VALUES ($1, $2, $3)
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(token_hash)
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(challenge)
+    }
+
+    /// Count an attempt against an open challenge. Returns `None` once the
+    /// challenge is unknown, expired, completed or out of attempts.
+    pub async fn attempt_challenge(&self, token_hash: &str, max_attempts: i32) -> Result<Option<TwoFactorChallenge>> {
+        let challenge = sqlx::query_as::<_, TwoFactorChallenge>(
+            "UPDATE two_factor_challenges SET attempts = attempts + 1
+             WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW() AND attempts < $2
+             RETURNING *",
+        )
+        .bind(token_hash)
+        .bind(max_attempts)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(challenge)
+    }
+
+    pub async fn complete_challenge(&self, challenge_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE two_factor_challenges SET used_at = NOW() WHERE id = $1")
+            .bind(challenge_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct PersonalAccessTokenRepository {
+    pool: PgPool,
+}
+
+impl PersonalAccessTokenRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        token_hash: &str,
+        token_prefix: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<PersonalAccessToken> {
+        let token = sqlx::query_as::<_, PersonalAccessToken>(
+            "INSERT INTO personal_access_tokens (user_id, name, token_hash, token_prefix, scopes, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(token_hash)
+        .bind(token_prefix)
+        .bind(scopes)
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    pub async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<PersonalAccessToken>> {
+        let tokens = sqlx::query_as::<_, PersonalAccessToken>(
+            "SELECT * FROM personal_access_tokens WHERE user_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tokens)
+    }
+
+    /// Find an unexpired token by hash and record that it was just used.
+    pub async fn touch_by_hash(&self, token_hash: &str) -> Result<Option<PersonalAccessToken>> {
+        let token = sqlx::query_as::<_, PersonalAccessToken>(
+            "UPDATE personal_access_tokens SET last_used_at = NOW()
+             WHERE token_hash = $1 AND (expires_at IS NULL OR expires_at > NOW())
+             RETURNING *",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM personal_access_tokens WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[derive(Clone)]
+pub struct IdentityRepository {
+    pool: PgPool,
+}
+
+impl IdentityRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create_with_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: Uuid,
+        provider: &str,
+        subject: &str,
+        email: Option<&str>,
+    ) -> Result<UserIdentity> {
+        let identity = sqlx::query_as::<_, UserIdentity>(
+            "INSERT INTO user_identities (user_id, provider, subject, email, last_login_at)
+             VALUES ($1, $2, $3, $4, NOW())
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(provider)
+        .bind(subject)
+        .bind(email)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(identity)
+    }
+
+    pub async fn find_by_subject(&self, provider: &str, subject: &str) -> Result<Option<UserIdentity>> {
+        let identity = sqlx::query_as::<_, UserIdentity>(
+            "SELECT * FROM user_identities WHERE provider = $1 AND subject = $2",
+        )
+        .bind(provider)
+        .bind(subject)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(identity)
+    }
+
+    pub async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<UserIdentity>> {
+        let identities = sqlx::query_as::<_, UserIdentity>(
+            "SELECT * FROM user_identities WHERE user_id = $1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(identities)
+    }
+
+    /// Note a login and keep the email the provider reports up to date.
+    pub async fn record_login(&self, id: Uuid, email: Option<&str>) -> Result<()> {
+        sqlx::query(
+            "UPDATE user_identities SET last_login_at = NOW(), email = COALESCE($2, email) WHERE id = $1",
+        )
+        .bind(id)
+        .bind(email)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM user_identities WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[derive(Clone)]
+pub struct OAuthStateRepository {
+    pool: PgPool,
+}
+
+impl OAuthStateRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_authorization(
+        &self,
+        state_hash: &str,
+        provider: &str,
+        pkce_verifier: &str,
+        link_user_id: Option<Uuid>,
+        redirect_url: Option<&str>,
+        browser_binding_hash: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<OAuthAuthorization> {
+        // Abandoned logins are cleaned up whenever a new one starts
+        sqlx::query("DELETE FROM oauth_authorizations WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        let authorization = sqlx::query_as::<_, OAuthAuthorization>(
+            "INSERT INTO oauth_authorizations
+                (state_hash, provider, pkce_verifier, link_user_id, redirect_url, browser_binding_hash, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING *",
+        )
+        .bind(state_hash)
+        .bind(provider)
+        .bind(pkce_verifier)
+        .bind(link_user_id)
+        .bind(redirect_url)
+        .bind(browser_binding_hash)
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(authorization)
+    }
+
+    /// Take a pending authorization; each state can be used once.
+    pub async fn consume_authorization(&self, state_hash: &str, provider: &str) -> Result<Option<OAuthAuthorization>> {
+        let authorization = sqlx::query_as::<_, OAuthAuthorization>(
+            "DELETE FROM oauth_authorizations
+             WHERE state_hash = $1 AND provider = $2 AND expires_at > NOW()
+             RETURNING *",
+        )
+        .bind(state_hash)
+        .bind(provider)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(authorization)
+    }
+
+    pub async fn create_login_code(&self, code_hash: &str, user_id: Uuid, expires_at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("DELETE FROM oauth_login_codes WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query("INSERT INTO oauth_login_codes (code_hash, user_id, expires_at) VALUES ($1, $2, $3)")
+            .bind(code_hash)
+            .bind(user_id)
+            .bind(expires_at)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The user a login code was issued for; each code works once.
+    pub async fn consume_login_code(&self, code_hash: &str) -> Result<Option<Uuid>> {
+        let user_id = sqlx::query_scalar(
+            "DELETE FROM oauth_login_codes
+             WHERE code_hash = $1 AND expires_at > NOW()
+             RETURNING user_id",
+        )
+        .bind(code_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user_id)
+    }
+}
+
+#[derive(Clone)]
+pub struct LoginThrottleRepository {
+    pool: PgPool,
+}
+
+impl LoginThrottleRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_active_lock(&self, scope: LockoutScope, subject: &str) -> Result<Option<LoginThrottle>> {
+        let throttle = sqlx::query_as::<_, LoginThrottle>(
+            "SELECT * FROM login_throttles
+             WHERE scope = $1 AND subject = $2 AND locked_until > NOW()",
+        )
+        .bind(scope.to_string())
+        .bind(subject)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(throttle)
+    }
+
+    /// Count a failed login; the count restarts once the last failure is old enough.
+    pub async fn record_failure(&self, scope: LockoutScope, subject: &str) -> Result<LoginThrottle> {
+        let throttle = sqlx::query_as::<_, LoginThrottle>(
+            "INSERT INTO login_throttles (scope, subject, failed_attempts, last_failed_at)
+             VALUES ($1, $2, 1, NOW())
+             ON CONFLICT (scope, subject) DO UPDATE SET
+                failed_attempts = CASE
+                    WHEN login_throttles.last_failed_at < NOW() - make_interval(hours => $3) THEN 1
+                    ELSE login_throttles.failed_attempts + 1
+                END,
+                last_failed_at = NOW()
+             RETURNING *",
+        )
+        .bind(scope.to_string())
+        .bind(subject)
+        .bind(FAILED_ATTEMPT_WINDOW_HOURS as i32)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(throttle)
+    }
+
+    pub async fn lock(&self, id: Uuid, locked_until: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE login_throttles SET locked_until = $2 WHERE id = $1")
+            .bind(id)
+            .bind(locked_until)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn clear(&self, scope: LockoutScope, subject: &str) -> Result<Option<LoginThrottle>> {
+        let throttle = sqlx::query_as::<_, LoginThrottle>(
+            "DELETE FROM login_throttles WHERE scope = $1 AND subject = $2 RETURNING *",
+        )
+        .bind(scope.to_string())
+        .bind(subject)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(throttle)
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<Option<LoginThrottle>> {
+        let throttle = sqlx::query_as::<_, LoginThrottle>("DELETE FROM login_throttles WHERE id = $1 RETURNING *")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(throttle)
+    }
+
+    /// Current lockouts, latest ending first.
+    pub async fn find_locked(&self) -> Result<Vec<LoginThrottle>> {
+        let throttles = sqlx::query_as::<_, LoginThrottle>(
+            "SELECT * FROM login_throttles WHERE locked_until > NOW() ORDER BY locked_until DESC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(throttles)
+    }
+}
+
+#[derive(Clone)]
+pub struct JwtKeyRepository {
+    pool: PgPool,
+}
+
+impl JwtKeyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Keys that may still verify tokens, oldest first.
+    pub async fn find_unexpired(&self) -> Result<Vec<JwtSigningKey>> {
+        let keys = sqlx::query_as::<_, JwtSigningKey>(
+            "SELECT * FROM jwt_signing_keys WHERE expires_at > NOW() ORDER BY activates_at",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(keys)
+    }
+
+    /// Store `key` unless another instance already scheduled a successor
+    /// retiring after `needed_until`. Returns whether it was stored.
+    pub async fn create_if_needed(&self, key: &JwtSigningKey, needed_until: DateTime<Utc>) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('jwt_signing_keys'))")
+            .execute(&mut *tx)
+            .await?;
+
+        let latest_retirement: Option<DateTime<Utc>> =
+            sqlx::query_scalar("SELECT MAX(retires_at) FROM jwt_signing_keys")
+                .fetch_one(&mut *tx)
+                .await?;
+
+        if latest_retirement.is_some_and(|retires_at| retires_at > needed_until) {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            "INSERT INTO jwt_signing_keys
+                (kid, algorithm, private_key, public_jwk, activates_at, retires_at, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(&key.kid)
+        .bind(&key.algorithm)
+        .bind(&key.private_key)
+        .bind(&key.public_jwk)
+        .bind(key.activates_at)
+        .bind(key.retires_at)
+        .bind(key.expires_at)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM jwt_signing_keys WHERE expires_at <= NOW()")
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+}
+
+#[derive(Clone)]
+pub struct SecurityEventRepository {
+    pool: PgPool,
+}
+
+impl SecurityEventRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(
+        &self,
+        user_id: Option<Uuid>,
+        kind: SecurityEventKind,
+        details: Option<serde_json::Value>,
+        client: &ClientInfo,
+    ) -> Result<SecurityEvent> {
+        let event = sqlx::query_as::<_, SecurityEvent>(
+            "INSERT INTO security_events (user_id, event_type, details, ip_address, user_agent)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(kind.to_string())
+        .bind(details)
+        .bind(&client.ip_address)
+        .bind(&client.user_agent)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(event)
+    }
+}
+
+#[derive(Clone)]
+pub struct ImpersonationRepository {
+    pool: PgPool,
+}
+
+impl ImpersonationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        admin_id: Uuid,
+        user_id: Uuid,
+        organization_id: Uuid,
+        reason: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<ImpersonationSession> {
+        let session = sqlx::query_as::<_, ImpersonationSession>(
+            "INSERT INTO impersonation_sessions (admin_id, user_id, organization_id, reason, expires_at)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING *",
+        )
+        .bind(admin_id)
+        .bind(user_id)
+        .bind(organization_id)
+        .bind(reason)
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    /// The session if it has neither been ended nor run out.
+    pub async fn find_open(&self, id: Uuid) -> Result<Option<ImpersonationSession>> {
+        let session = sqlx::query_as::<_, ImpersonationSession>(
+            "SELECT * FROM impersonation_sessions
+             WHERE id = $1 AND ended_at IS NULL AND expires_at > NOW()",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    /// End an open session now; `None` if it already ended or ran out.
+    pub async fn end(&self, id: Uuid) -> Result<Option<ImpersonationSession>> {
+        let session = sqlx::query_as::<_, ImpersonationSession>(
+            "UPDATE impersonation_sessions SET ended_at = NOW()
+             WHERE id = $1 AND ended_at IS NULL AND expires_at > NOW()
+             RETURNING *",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    /// Sessions that ran out without being ended.
+    pub async fn find_expired_ids(&self) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM impersonation_sessions
+             WHERE ended_at IS NULL AND expires_at <= NOW()",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Close a session that ran out, as of its expiry; `None` if it has
+    /// been ended meanwhile.
+    pub async fn end_expired_with_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+    ) -> Result<Option<ImpersonationSession>> {
+        let session = sqlx::query_as::<_, ImpersonationSession>(
+            "UPDATE impersonation_sessions SET ended_at = expires_at
+             WHERE id = $1 AND ended_at IS NULL AND expires_at <= NOW()
+             RETURNING *",
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(session)
+    }
+}
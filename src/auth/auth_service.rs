@@ -1,31 +1,153 @@
+use crate::audit::{
+    audit_models::{AuditAction, AuditTarget, NewAuditEntry},
+    audit_service::AuditService,
+};
 use crate::db::DbPool;
 use crate::error::Result;
-use crate::auth::auth_repository::RefreshTokenRepository;
-use crate::auth::{create_access_token, create_refresh_token, verify_jwt, hash_password, verify_password};
+use crate::auth::auth_repository::{
+    EmailVerificationRepository, IdentityRepository, ImpersonationRepository, LoginThrottleRepository,
+    OAuthStateRepository, PasswordResetRepository, PersonalAccessTokenRepository, RefreshTokenRepository, SecurityEventRepository, TwoFactorRepository,
+};
+use crate::auth::{
+    create_access_token, create_refresh_token, verify_jwt, hash_password, needs_rehash, verify_password, JwtKeys,
+    TokenType,
+    jwt::{create_impersonation_token, IMPERSONATION_MINUTES, REFRESH_TOKEN_DAYS},
+};
+use crate::auth::{generate_token, hash_token, totp};
+use crate::auth::auth_models::{ImpersonationSession, LoginThrottle, PersonalAccessToken, RefreshToken, SecurityEventKind, UserIdentity};
+use crate::auth::lockout::{lockout_duration, LockoutScope};
+use crate::auth::password_policy::PasswordPolicy;
+use crate::auth::oauth::{is_allowed_redirect, with_query, ExternalProfile, OAuthProvider};
+use crate::auth::token_scope::TokenScope;
+use crate::mail::{MailMessage, Mailer};
+use crate::middleware::ClientInfo;
+use crate::organization::organization_service::OrganizationService;
 use crate::user::user_repository::UserRepository;
 use crate::user::user_models::User;
-use chrono::{Duration, Utc};
+use crate::state::Config;
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+use oauth2::PkceCodeChallenge;
+
+/// How long the second login step may take.
+const TWO_FACTOR_CHALLENGE_MINUTES: i64 = 5;
+/// Wrong codes allowed per challenge before the user has to log in again.
+const TWO_FACTOR_MAX_ATTEMPTS: i32 = 5;
+const RECOVERY_CODE_COUNT: usize = 10;
+/// How long a user has to finish signing in at an OAuth provider.
+const OAUTH_AUTHORIZATION_MINUTES: i64 = 10;
+/// How long the SPA has to exchange the code it got after an OAuth login.
+const OAUTH_LOGIN_CODE_SECONDS: i64 = 60;
+/// Marks personal access tokens so `auth_middleware` can tell them from JWTs.
+pub const PERSONAL_ACCESS_TOKEN_PREFIX: &str = "tmpat_";
+
+/// Result of the password (or Google) step of a login.
+pub enum LoginOutcome {
+    Authenticated(Box<User>, String, String),
+    /// The account has 2FA enabled; finish with `verify_two_factor_login`.
+    TwoFactorRequired {
+        challenge_token: String,
+        expires_at: DateTime<Utc>,
+    },
+}
+
+/// A started OAuth authorization.
+pub struct OAuthStart {
+    pub authorization_url: String,
+    /// Secret to keep in a cookie so the callback runs in the same browser
+    pub browser_binding: Option<String>,
+}
+
+/// Result of an OAuth callback.
+pub enum OAuthCompletion {
+    /// No post-login redirect was requested, so the callback answers itself
+    LoggedIn(LoginOutcome),
+    Linked(UserIdentity),
+    /// Send the browser back to the app, with a login `code`, `linked` or `error`
+    Redirect(String),
+}
+
+enum OAuthResult {
+    LoggedIn(User),
+    Linked(UserIdentity),
+}
 
 #[derive(Clone)]
 pub struct AuthService {
     db: DbPool,
     user_repo: UserRepository,
     refresh_token_repo: RefreshTokenRepository,
-    jwt_secret: String,
+    organization_service: OrganizationService,
+    audit_service: AuditService,
+    password_reset_repo: PasswordResetRepository,
+    email_verification_repo: EmailVerificationRepository,
+    two_factor_repo: TwoFactorRepository,
+    security_event_repo: SecurityEventRepository,
+    personal_access_token_repo: PersonalAccessTokenRepository,
+    identity_repo: IdentityRepository,
+    oauth_state_repo: OAuthStateRepository,
+    login_throttle_repo: LoginThrottleRepository,
+    impersonation_repo: ImpersonationRepository,
+    mailer: Mailer,
+    jwt_keys: JwtKeys,
+    totp_issuer: String,
+    password_policy: PasswordPolicy,
+    password_reset_url: String,
+    password_reset_ttl: Duration,
+    email_verification_url: String,
+    email_verification_ttl: Duration,
+    email_verification_resend_interval: Duration,
+    oauth_allowed_redirects: Vec<String>,
+    oauth_default_redirect_url: Option<String>,
 }
 
 impl AuthService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         db: DbPool,
         user_repo: UserRepository,
         refresh_token_repo: RefreshTokenRepository,
-        jwt_secret: String,
+        organization_service: OrganizationService,
+        audit_service: AuditService,
+        password_reset_repo: PasswordResetRepository,
+        email_verification_repo: EmailVerificationRepository,
+        two_factor_repo: TwoFactorRepository,
+        security_event_repo: SecurityEventRepository,
+        personal_access_token_repo: PersonalAccessTokenRepository,
+        identity_repo: IdentityRepository,
+        oauth_state_repo: OAuthStateRepository,
+        login_throttle_repo: LoginThrottleRepository,
+        impersonation_repo: ImpersonationRepository,
+        mailer: Mailer,
+        jwt_keys: JwtKeys,
+        config: &Config,
     ) -> Self {
         Self {
             db,
             user_repo,
             refresh_token_repo,
-            jwt_secret,
+            organization_service,
+            audit_service,
+            password_reset_repo,
+            email_verification_repo,
+            two_factor_repo,
+            security_event_repo,
+            personal_access_token_repo,
+            identity_repo,
+            oauth_state_repo,
+            login_throttle_repo,
+            impersonation_repo,
+            mailer,
+            jwt_keys,
+            totp_issuer: config.totp_issuer.clone(),
+            password_policy: config.password_policy.clone(),
+            password_reset_url: config.password_reset_url.clone(),
+            password_reset_ttl: Duration::minutes(config.password_reset_token_minutes),
+            email_verification_url: config.email_verification_url.clone(),
+            email_verification_ttl: Duration::hours(config.email_verification_token_hours),
+            email_verification_resend_interval: Duration::seconds(config.email_verification_resend_seconds),
+            oauth_allowed_redirects: config.oauth_allowed_redirects.clone(),
+            oauth_default_redirect_url: config.oauth_default_redirect_url.clone(),
         }
     }
 
@@ -34,66 +156,461 @@ impl AuthService {
         username: &str,
         email: &str,
         password: &str,
+        client: &ClientInfo,
     ) -> Result<(User, String, String)> {
+        self.password_policy.check(password, &[username, email])?;
         let password_hash = hash_password(password)?;
-        
+
+        // The account, its workspace and its first session exist together or not at all
         let mut tx = self.db.begin().await?;
-        
+
         let user = self.user_repo.create_with_tx(&mut tx, username, email, &password_hash).await?;
-        
-        let access_token = create_access_token(user.id, &user.email, &user.role, &self.jwt_secret)?;
-        let refresh_token = create_refresh_token(user.id, &user.email, &user.role, &self.jwt_secret)?;
-        
-        let expires_at = Utc::now() + Duration::days(7);
+        let organization = self.organization_service.create_personal_with_tx(&mut tx, &user).await?;
+
+        let family_id = Uuid::new_v4();
+        let (access_token, refresh_token) = self.sign_tokens(&user, family_id, organization.id)?;
         self.refresh_token_repo
-            .create_with_tx(&mut tx, user.id, &refresh_token, expires_at)
+            .create_with_tx(
+                &mut tx,
+                family_id,
+                user.id,
+                &hash_token(&refresh_token),
+                Utc::now() + Duration::days(REFRESH_TOKEN_DAYS),
+                client,
+            )
             .await?;
 
         tx.commit().await?;
 
+        if let Err(e) = self.send_email_verification(user.id).await {
+            tracing::error!("Failed to send verification email: {:?}", e);
+        }
+
         Ok((user, access_token, refresh_token))
     }
 
-    pub async fn login(&self, email: &str, password: &str) -> Result<(User, String, String)> {
-        let user = self
-            .user_repo
-            .find_by_email(email)
-            .await?
-            .ok_or_else(|| crate::error::AppError::Authentication("Invalid credentials".into()))?;
+    pub async fn login(&self, email: &str, password: &str, client: &ClientInfo) -> Result<LoginOutcome> {
+        self.check_login_lockout(email, client).await?;
+
+        let user = match self.user_repo.find_by_email(email).await? {
+            Some(user) => user,
+            None => {
+                self.record_login_failure(email, None, client).await?;
+                return Err(crate::error::AppError::Authentication("Invalid credentials".into()));
+            }
+        };
 
         if let Some(ref password_hash) = user.password_hash {
             if !verify_password(password, password_hash)? {
+                self.record_login_failure(email, Some(user.id), client).await?;
                 return Err(crate::error::AppError::Authentication("Invalid credentials".into()));
             }
         } else {
-            return Err(crate::error::AppError::Authentication("Please use Google login".into()));
+            return Err(crate::error::AppError::Authentication("Please sign in with your linked login provider".into()));
         }
 
-        let access_token = create_access_token(user.id, &user.email, &user.role, &self.jwt_secret)?;
-        let refresh_token = create_refresh_token(user.id, &user.email, &user.role, &self.jwt_secret)?;
+        self.login_throttle_repo.clear(LockoutScope::Account, &account_key(email)).await?;
+        self.upgrade_password_hash(&user, password).await;
+        self.complete_first_factor(user, client).await
+    }
 
-        let mut tx = self.db.begin().await?;
-        
-        let expires_at = Utc::now() + Duration::days(7);
+    /// Re-hash a verified password that still uses bcrypt or outdated Argon2 parameters.
+    async fn upgrade_password_hash(&self, user: &User, password: &str) {
+        if !user.password_hash.as_deref().is_some_and(needs_rehash) {
+            return;
+        }
+
+        let result = match hash_password(password) {
+            Ok(password_hash) => self.user_repo.update_password(user.id, &password_hash).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to upgrade password hash for user {}: {:?}", user.id, e);
+        }
+    }
+
+    /// Change the password of a signed-in user, who has to confirm the
+    /// current one. Every other session is signed out.
+    pub async fn change_password(
+        &self,
+        user_id: Uuid,
+        current_password: &str,
+        new_password: &str,
+        current_session: Option<Uuid>,
+        client: &ClientInfo,
+    ) -> Result<()> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("User not found".into()))?;
+
+        let current_hash = user.password_hash.as_deref().ok_or_else(|| {
+            crate::error::AppError::BadRequest("This account has no password yet; use password reset to set one".into())
+        })?;
+
+        // A stolen access token must not allow guessing the password
+        self.check_login_lockout(&user.email, client).await?;
+        if !verify_password(current_password, current_hash)? {
+            self.record_login_failure(&user.email, Some(user.id), client).await?;
+            return Err(crate::error::AppError::Authentication("Current password is incorrect".into()));
+        }
+
+        if current_password == new_password {
+            return Err(crate::error::AppError::Validation(
+                "New password must differ from the current one".into(),
+            ));
+        }
+        self.password_policy.check(new_password, &[&user.username, &user.email])?;
+
+        let password_hash = hash_password(new_password)?;
+        self.user_repo.update_password(user.id, &password_hash).await?;
+
+        if let Some(session_id) = current_session {
+            self.refresh_token_repo.delete_other_sessions(user.id, session_id).await?;
+        } else {
+            self.refresh_token_repo.delete_by_user(user.id).await?;
+        }
+
+        self.security_event_repo
+            .record(Some(user.id), SecurityEventKind::PasswordChanged, None, client)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Refuse to check credentials while the account or the caller's address is locked.
+    async fn check_login_lockout(&self, email: &str, client: &ClientInfo) -> Result<()> {
+        for (scope, subject) in login_subjects(email, client) {
+            if let Some(locked_until) = self
+                .login_throttle_repo
+                .find_active_lock(scope, &subject)
+                .await?
+                .and_then(|throttle| throttle.locked_until)
+            {
+                let seconds = (locked_until - Utc::now()).num_seconds().max(1);
+                return Err(crate::error::AppError::TooManyRequests(format!(
+                    "Too many failed login attempts, try again in {} seconds",
+                    seconds
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Count a failed login against the account and the caller's address,
+    /// locking either one that crossed its threshold.
+    async fn record_login_failure(&self, email: &str, user_id: Option<Uuid>, client: &ClientInfo) -> Result<()> {
+        for (scope, subject) in login_subjects(email, client) {
+            let throttle = self.login_throttle_repo.record_failure(scope, &subject).await?;
+
+            let Some(duration) = lockout_duration(throttle.failed_attempts, scope.threshold()) else {
+                continue;
+            };
+            let locked_until = Utc::now() + duration;
+            self.login_throttle_repo.lock(throttle.id, locked_until).await?;
+
+            tracing::warn!("Locked {} {} until {} after {} failed logins", scope, subject, locked_until, throttle.failed_attempts);
+
+            let details = serde_json::json!({
+                "scope": scope.to_string(),
+                "subject": subject,
+                "failed_attempts": throttle.failed_attempts,
+                "locked_until": locked_until,
+            });
+            let locked_user = user_id.filter(|_| scope == LockoutScope::Account);
+            self.security_event_repo
+                .record(locked_user, SecurityEventKind::LoginLockout, Some(details.clone()), client)
+                .await?;
+
+            let organization_id = match locked_user {
+                Some(user_id) => self.organization_service.find_default_for(user_id).await?,
+                None => None,
+            };
+            self.audit_service
+                .record(
+                    NewAuditEntry::automatic(organization_id, AuditAction::LockoutCreated, AuditTarget::Lockout, throttle.id)
+                        .with_after(details),
+                    client,
+                )
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Lockouts in force right now (admin only).
+    pub async fn list_lockouts(&self) -> Result<Vec<LoginThrottle>> {
+        self.login_throttle_repo.find_locked().await
+    }
+
+    /// Lift a lockout and forget its failed attempts (admin only). Returns
+    /// the lockout as it was.
+    pub async fn clear_lockout(&self, lockout_id: Uuid, client: &ClientInfo) -> Result<LoginThrottle> {
+        let throttle = self
+            .login_throttle_repo
+            .delete(lockout_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("Lockout not found".into()))?;
+
+        let user_id = match throttle.scope.as_str() {
+            "account" => self.user_repo.find_by_email(&throttle.subject).await?.map(|user| user.id),
+            _ => None,
+        };
+        self.record_lockout_cleared(user_id, &throttle, client).await?;
+        Ok(throttle)
+    }
+
+    /// Lift the lockout of a user's account (admin only). Returns the
+    /// lockout as it was.
+    pub async fn clear_user_lockout(&self, user_id: Uuid, client: &ClientInfo) -> Result<LoginThrottle> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("User not found".into()))?;
+
+        let throttle = self
+            .login_throttle_repo
+            .clear(LockoutScope::Account, &account_key(&user.email))
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("Lockout not found".into()))?;
+
+        self.record_lockout_cleared(Some(user.id), &throttle, client).await?;
+        Ok(throttle)
+    }
+
+    async fn record_lockout_cleared(
+        &self,
+        user_id: Option<Uuid>,
+        throttle: &LoginThrottle,
+        client: &ClientInfo,
+    ) -> Result<()> {
+        let details = serde_json::json!({
+            "scope": throttle.scope,
+            "subject": throttle.subject,
+            "failed_attempts": throttle.failed_attempts,
+        });
+        self.security_event_repo
+            .record(user_id, SecurityEventKind::LockoutCleared, Some(details), client)
+            .await?;
+        Ok(())
+    }
+
+    /// Issue tokens, or a 2FA challenge if the account requires a second factor.
+    async fn complete_first_factor(&self, user: User, client: &ClientInfo) -> Result<LoginOutcome> {
+        if user.two_factor_enabled {
+            let challenge_token = generate_token();
+            let challenge = self
+                .two_factor_repo
+                .create_challenge(
+                    user.id,
+                    &hash_token(&challenge_token),
+                    Utc::now() + Duration::minutes(TWO_FACTOR_CHALLENGE_MINUTES),
+                )
+                .await?;
+
+            return Ok(LoginOutcome::TwoFactorRequired {
+                challenge_token,
+                expires_at: challenge.expires_at,
+            });
+        }
+
+        let (access_token, refresh_token) = self.issue_tokens(&user, client).await?;
+        Ok(LoginOutcome::Authenticated(Box::new(user), access_token, refresh_token))
+    }
+
+    /// Start a new session (token family) for `user` and return its access and refresh tokens.
+    async fn issue_tokens(&self, user: &User, client: &ClientInfo) -> Result<(String, String)> {
+        let family_id = Uuid::new_v4();
+        let organization_id = self.organization_service.default_for(user).await?;
+        let (access_token, refresh_token) = self.sign_tokens(user, family_id, organization_id)?;
+
+        let expires_at = Utc::now() + Duration::days(REFRESH_TOKEN_DAYS);
         self.refresh_token_repo
-            .create_with_tx(&mut tx, user.id, &refresh_token, expires_at)
+            .create(family_id, user.id, &hash_token(&refresh_token), expires_at, client)
             .await?;
-            
-        tx.commit().await?;
 
+        Ok((access_token, refresh_token))
+    }
+
+    /// Access and refresh token for a new session `family_id`.
+    fn sign_tokens(&self, user: &User, family_id: Uuid, organization_id: Uuid) -> Result<(String, String)> {
+        let access_token = create_access_token(&self.jwt_keys, user.id, &user.email, family_id, organization_id)?;
+        let refresh_token = create_refresh_token(&self.jwt_keys, user.id, &user.email, family_id, organization_id)?;
+        Ok((access_token, refresh_token))
+    }
+
+    /// Second login step: trade a challenge and a TOTP or recovery code for tokens.
+    pub async fn verify_two_factor_login(
+        &self,
+        challenge_token: &str,
+        code: Option<&str>,
+        recovery_code: Option<&str>,
+        client: &ClientInfo,
+    ) -> Result<(User, String, String)> {
+        let challenge = self
+            .two_factor_repo
+            .attempt_challenge(&hash_token(challenge_token), TWO_FACTOR_MAX_ATTEMPTS)
+            .await?
+            .ok_or_else(|| crate::error::AppError::Authentication("Invalid or expired challenge, please log in again".into()))?;
+
+        let user = self
+            .user_repo
+            .find_by_id(challenge.user_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::Authentication("User not found".into()))?;
+
+        // Wrong codes count like wrong passwords, so new challenges don't reset the budget
+        self.check_login_lockout(&user.email, client).await?;
+
+        if !self.check_second_factor(challenge.user_id, code, recovery_code).await? {
+            self.record_login_failure(&user.email, Some(user.id), client).await?;
+            return Err(crate::error::AppError::Authentication("Invalid two-factor code".into()));
+        }
+
+        self.two_factor_repo.complete_challenge(challenge.id).await?;
+        self.login_throttle_repo.clear(LockoutScope::Account, &account_key(&user.email)).await?;
+
+        let (access_token, refresh_token) = self.issue_tokens(&user, client).await?;
         Ok((user, access_token, refresh_token))
     }
 
-    pub async fn refresh_access_token(&self, refresh_token: &str) -> Result<(String, String)> {
-        let claims = verify_jwt(refresh_token, &self.jwt_secret)?;
-        
-        let _stored_token = self
+    /// Check a TOTP code, or else a recovery code (which is used up).
+    async fn check_second_factor(
+        &self,
+        user_id: Uuid,
+        code: Option<&str>,
+        recovery_code: Option<&str>,
+    ) -> Result<bool> {
+        let two_factor = match self.two_factor_repo.find_by_user(user_id).await? {
+            Some(two_factor) if two_factor.confirmed_at.is_some() => two_factor,
+            _ => return Ok(false),
+        };
+
+        match (code, recovery_code) {
+            (Some(code), _) => match totp::verify_code(&two_factor.secret, code, Utc::now().timestamp()) {
+                Some(step) => self.two_factor_repo.record_used_step(user_id, step).await,
+                None => Ok(false),
+            },
+            (None, Some(recovery_code)) => {
+                let code_hash = hash_token(&totp::normalize_recovery_code(recovery_code));
+                self.two_factor_repo.consume_recovery_code(user_id, &code_hash).await
+            }
+            (None, None) => Err(crate::error::AppError::Validation(
+                "Either code or recovery_code is required".into(),
+            )),
+        }
+    }
+
+    /// Start 2FA enrollment with a new secret; nothing changes until it is confirmed.
+    pub async fn begin_two_factor_setup(&self, user_id: Uuid) -> Result<(String, String)> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("User not found".into()))?;
+
+        if user.two_factor_enabled {
+            return Err(crate::error::AppError::BadRequest("Two-factor authentication is already enabled".into()));
+        }
+
+        let secret = totp::generate_secret();
+        self.two_factor_repo.upsert_pending(user_id, &secret).await?;
+
+        let uri = totp::otpauth_uri(&self.totp_issuer, &user.email, &secret);
+        Ok((secret, uri))
+    }
+
+    /// Confirm enrollment with a first code; returns the recovery codes, shown only once.
+    pub async fn confirm_two_factor_setup(&self, user_id: Uuid, code: &str) -> Result<Vec<String>> {
+        let two_factor = self
+            .two_factor_repo
+            .find_by_user(user_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::BadRequest("Start two-factor setup first".into()))?;
+
+        if two_factor.confirmed_at.is_some() {
+            return Err(crate::error::AppError::BadRequest("Two-factor authentication is already enabled".into()));
+        }
+
+        let step = totp::verify_code(&two_factor.secret, code, Utc::now().timestamp())
+            .ok_or_else(|| crate::error::AppError::BadRequest("Invalid two-factor code".into()))?;
+        self.two_factor_repo.record_used_step(user_id, step).await?;
+
+        let recovery_codes = totp::generate_recovery_codes(RECOVERY_CODE_COUNT);
+        let hashes: Vec<String> = recovery_codes
+            .iter()
+            .map(|code| hash_token(&totp::normalize_recovery_code(code)))
+            .collect();
+        self.two_factor_repo.enable(user_id, &hashes).await?;
+
+        Ok(recovery_codes)
+    }
+
+    /// Turn 2FA off; requires a current TOTP or recovery code.
+    pub async fn disable_two_factor(
+        &self,
+        user_id: Uuid,
+        code: Option<&str>,
+        recovery_code: Option<&str>,
+    ) -> Result<()> {
+        if !self.check_second_factor(user_id, code, recovery_code).await? {
+            return Err(crate::error::AppError::BadRequest("Invalid two-factor code".into()));
+        }
+
+        self.two_factor_repo.disable(user_id).await
+    }
+
+    /// Admin reset for users who lost their authenticator and recovery codes.
+    pub async fn reset_two_factor(&self, user_id: Uuid) -> Result<()> {
+        self.user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("User not found".into()))?;
+
+        self.two_factor_repo.disable(user_id).await
+    }
+
+    /// Rotate a refresh token within its family.
+    ///
+    /// Presenting a token that was already rotated means it has been copied:
+    /// the whole family is revoked and a security event recorded.
+    pub async fn refresh_access_token(&self, refresh_token: &str, client: &ClientInfo) -> Result<(String, String)> {
+        self.rotate_refresh_token(refresh_token, None, client).await
+    }
+
+    /// Rotate a refresh token into tokens acting in another organization the
+    /// user belongs to.
+    pub async fn switch_organization(
+        &self,
+        refresh_token: &str,
+        organization_id: Uuid,
+        client: &ClientInfo,
+    ) -> Result<(String, String)> {
+        self.rotate_refresh_token(refresh_token, Some(organization_id), client).await
+    }
+
+    /// Rotate into the `switch_to` organization, or keep the token's own one
+    /// while the user still belongs to it.
+    async fn rotate_refresh_token(
+        &self,
+        refresh_token: &str,
+        switch_to: Option<Uuid>,
+        client: &ClientInfo,
+    ) -> Result<(String, String)> {
+        let claims = verify_jwt(&self.jwt_keys, refresh_token, TokenType::Refresh).await?;
+
+        let stored_token = self
             .refresh_token_repo
-            .find_by_token(refresh_token)
+            .find_by_hash(&hash_token(refresh_token))
             .await?
             .ok_or_else(|| crate::error::AppError::Authentication("Invalid refresh token".into()))?;
 
-        let user_id = uuid::Uuid::parse_str(&claims.sub)
+        if stored_token.revoked {
+            return Err(self.handle_refresh_token_reuse(&stored_token, client).await);
+        }
+
+        let user_id = Uuid::parse_str(&claims.sub)
             .map_err(|_| crate::error::AppError::Authentication("Invalid token claims".into()))?;
 
         let user = self
@@ -102,65 +619,660 @@ impl AuthService {
             .await?
             .ok_or_else(|| crate::error::AppError::Authentication("User not found".into()))?;
 
-        let new_access_token = create_access_token(user.id, &user.email, &user.role, &self.jwt_secret)?;
-        let new_refresh_token = create_refresh_token(user.id, &user.email, &user.role, &self.jwt_secret)?;
+        let organization_id = match (switch_to, claims.org) {
+            (Some(requested), _) => self.organization_service.resolve_active(user.id, Some(requested)).await?,
+            (None, Some(current)) if self.organization_service.is_member(current, user.id).await? => current,
+            (None, _) => self.organization_service.default_for(&user).await?,
+        };
 
-        let mut tx = self.db.begin().await?;
+        let family_id = stored_token.family_id;
+        let new_access_token = create_access_token(&self.jwt_keys, user.id, &user.email, family_id, organization_id)?;
+        let new_refresh_token = create_refresh_token(&self.jwt_keys, user.id, &user.email, family_id, organization_id)?;
 
-        self.refresh_token_repo
-            .delete_by_token(refresh_token) // Note: This uses pool, not tx. Should ideally use tx but delete_by_token doesn't support it yet.
+        let expires_at = Utc::now() + Duration::days(REFRESH_TOKEN_DAYS);
+        let rotated = self
+            .refresh_token_repo
+            .rotate(stored_token.id, &hash_token(&new_refresh_token), expires_at, client)
             .await?;
-        
-        // To be fully atomic, delete_by_token should also take tx. 
-        // For now, we'll just create the new one in tx.
-        // Actually, if we want strict correctness, we should update delete_by_token too.
-        // But let's stick to what we have for now to minimize changes.
-        
-        let expires_at = Utc::now() + Duration::days(7);
-        self.refresh_token_repo
-            .create_with_tx(&mut tx, user.id, &new_refresh_token, expires_at)
+
+        // Lost the race against another rotation of the same token
+        if rotated.is_none() {
+            return Err(self.handle_refresh_token_reuse(&stored_token, client).await);
+        }
+
+        Ok((new_access_token, new_refresh_token))
+    }
+
+    async fn handle_refresh_token_reuse(
+        &self,
+        reused: &RefreshToken,
+        client: &ClientInfo,
+    ) -> crate::error::AppError {
+        tracing::warn!(
+            "Refresh token reuse detected for user {}, revoking session {}",
+            reused.user_id,
+            reused.family_id
+        );
+
+        if let Err(e) = self.refresh_token_repo.revoke_family(reused.family_id).await {
+            return e;
+        }
+
+        let details = serde_json::json!({
+            "family_id": reused.family_id,
+            "token_id": reused.id,
+        });
+        if let Err(e) = self
+            .security_event_repo
+            .record(Some(reused.user_id), SecurityEventKind::RefreshTokenReuse, Some(details.clone()), client)
+            .await
+        {
+            tracing::error!("Failed to record security event: {:?}", e);
+        }
+
+        match self.organization_service.find_default_for(reused.user_id).await {
+            Ok(organization_id) => {
+                self.audit_service
+                    .record(
+                        NewAuditEntry::automatic(
+                            organization_id,
+                            AuditAction::SessionReuseRevoked,
+                            AuditTarget::User,
+                            reused.user_id,
+                        )
+                        .with_after(details),
+                        client,
+                    )
+                    .await
+            }
+            Err(e) => tracing::error!("Failed to audit refresh token reuse: {:?}", e),
+        }
+
+        crate::error::AppError::Authentication("Refresh token has already been used, please log in again".into())
+    }
+
+    /// Email a reset link if an active account uses `email`.
+    ///
+    /// Succeeds whether or not the address is known, and sends mail in the
+    /// background, so callers cannot probe which emails are registered.
+    pub async fn request_password_reset(&self, email: &str) -> Result<()> {
+        let user = match self.user_repo.find_by_email(email).await? {
+            Some(user) if user.is_active => user,
+            _ => return Ok(()),
+        };
+
+        let token = generate_token();
+        let expires_at = Utc::now() + self.password_reset_ttl;
+        self.password_reset_repo
+            .replace_for_user(user.id, &hash_token(&token), expires_at)
+            .await?;
+
+        self.mailer.send_in_background(MailMessage {
+            to: user.email.clone(),
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "Hi {},\n\nUse the link below to choose a new password. It expires in {} minutes and works once.\n\n{}?token={}\n\nIf you did not ask for this, you can ignore this email.",
+                user.username,
+                self.password_reset_ttl.num_minutes(),
+                self.password_reset_url,
+                token
+            ),
+        });
+
+        Ok(())
+    }
+
+    /// Set a new password with a reset token and sign out every session.
+    pub async fn reset_password(&self, token: &str, new_password: &str, client: &ClientInfo) -> Result<()> {
+        let mut tx = self.db.begin().await?;
+
+        let reset_token = self
+            .password_reset_repo
+            .consume_with_tx(&mut tx, &hash_token(token))
+            .await?
+            .ok_or_else(|| crate::error::AppError::BadRequest("Invalid or expired reset token".into()))?;
+
+        // Rejecting the password rolls back, so the token can be used again
+        let user = self
+            .user_repo
+            .find_by_id(reset_token.user_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::BadRequest("Invalid or expired reset token".into()))?;
+        self.password_policy.check(new_password, &[&user.username, &user.email])?;
+        let password_hash = hash_password(new_password)?;
+
+        self.user_repo
+            .update_password_with_tx(&mut tx, reset_token.user_id, &password_hash)
             .await?;
-            
+
         tx.commit().await?;
 
-        Ok((new_access_token, new_refresh_token))
+        self.refresh_token_repo.delete_by_user(reset_token.user_id).await?;
+
+        // Not signed in, so filed under the user's default organization
+        if let Some(organization_id) = self.organization_service.find_default_for(user.id).await? {
+            self.audit_service
+                .record(
+                    NewAuditEntry::new(organization_id, user.id, AuditAction::PasswordReset, AuditTarget::User, user.id),
+                    client,
+                )
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Send a fresh verification link to the user's current email, unless it is verified.
+    pub async fn send_email_verification(&self, user_id: Uuid) -> Result<()> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("User not found".into()))?;
+
+        if user.email_verified {
+            return Ok(());
+        }
+
+        let token = generate_token();
+        let expires_at = Utc::now() + self.email_verification_ttl;
+        self.email_verification_repo
+            .replace_for_user(user.id, &user.email, &hash_token(&token), expires_at)
+            .await?;
+
+        self.mailer.send_in_background(MailMessage {
+            to: user.email.clone(),
+            subject: "Verify your email address".to_string(),
+            body: format!(
+                "Hi {},\n\nPlease confirm your email address by opening the link below. It expires in {} hours.\n\n{}?token={}",
+                user.username,
+                self.email_verification_ttl.num_hours(),
+                self.email_verification_url,
+                token
+            ),
+        });
+
+        Ok(())
+    }
+
+    /// Resend the verification link, at most once per resend interval.
+    pub async fn resend_email_verification(&self, user_id: Uuid) -> Result<()> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("User not found".into()))?;
+
+        if user.email_verified {
+            return Err(crate::error::AppError::BadRequest("Email is already verified".into()));
+        }
+
+        if let Some(latest) = self.email_verification_repo.find_latest_for_user(user_id).await? {
+            let next_allowed = latest.created_at + self.email_verification_resend_interval;
+            if next_allowed > Utc::now() {
+                return Err(crate::error::AppError::TooManyRequests(format!(
+                    "Please wait {} seconds before requesting another email",
+                    (next_allowed - Utc::now()).num_seconds().max(1)
+                )));
+            }
+        }
+
+        self.send_email_verification(user_id).await
+    }
+
+    pub async fn verify_email(&self, token: &str) -> Result<()> {
+        self.email_verification_repo
+            .consume(&hash_token(token))
+            .await?
+            .ok_or_else(|| crate::error::AppError::BadRequest("Invalid or expired verification token".into()))?;
+
+        Ok(())
     }
 
     pub async fn logout(&self, refresh_token: &str) -> Result<()> {
         self.refresh_token_repo
-            .delete_by_token(refresh_token)
+            .delete_family_by_hash(&hash_token(refresh_token))
             .await
     }
+
+    pub async fn list_sessions(&self, user_id: Uuid) -> Result<Vec<RefreshToken>> {
+        self.refresh_token_repo.find_active_by_user(user_id).await
+    }
+
+    pub async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> Result<()> {
+        if self.refresh_token_repo.delete_session(user_id, session_id).await? == 0 {
+            return Err(crate::error::AppError::NotFound("Session not found".into()));
+        }
+        Ok(())
+    }
+
+    /// Sign out everywhere except `current_session`. Returns how many sessions ended.
+    pub async fn revoke_other_sessions(&self, user_id: Uuid, current_session: Uuid) -> Result<u64> {
+        self.refresh_token_repo.delete_other_sessions(user_id, current_session).await
+    }
+
+    /// Open an impersonation session of `admin_id` acting as `user` and
+    /// return its short-lived access token. Callers check that the admin may.
+    pub async fn start_impersonation(
+        &self,
+        admin_id: Uuid,
+        user: &User,
+        organization_id: Uuid,
+        reason: Option<&str>,
+    ) -> Result<(String, ImpersonationSession)> {
+        let expires_at = Utc::now() + Duration::minutes(IMPERSONATION_MINUTES);
+        let session = self
+            .impersonation_repo
+            .create(admin_id, user.id, organization_id, reason, expires_at)
+            .await?;
+
+        let token = create_impersonation_token(
+            &self.jwt_keys,
+            user.id,
+            &user.email,
+            session.id,
+            organization_id,
+            admin_id,
+        )?;
+
+        Ok((token, session))
+    }
+
+    /// The impersonation session if it is still open.
+    pub async fn find_open_impersonation(&self, session_id: Uuid) -> Result<Option<ImpersonationSession>> {
+        self.impersonation_repo.find_open(session_id).await
+    }
+
+    /// End an impersonation session early; its token stops working at once.
+    pub async fn end_impersonation(&self, session_id: Uuid) -> Result<ImpersonationSession> {
+        self.impersonation_repo
+            .end(session_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("Impersonation session not found".into()))
+    }
+
+    /// Close the impersonation sessions that ran out, for the scheduler.
+    /// Each session is ended together with its audit entry, so neither is
+    /// kept without the other; one that fails is logged and retried on the
+    /// next run.
+    pub async fn end_expired_impersonations(&self) -> Result<Vec<ImpersonationSession>> {
+        let mut ended = Vec::new();
+
+        for session_id in self.impersonation_repo.find_expired_ids().await? {
+            match self.end_expired_impersonation(session_id).await {
+                Ok(Some(session)) => ended.push(session),
+                Ok(None) => {}
+                Err(e) => tracing::error!("Failed to end expired impersonation session {}: {:?}", session_id, e),
+            }
+        }
+
+        Ok(ended)
+    }
+
+    async fn end_expired_impersonation(&self, session_id: Uuid) -> Result<Option<ImpersonationSession>> {
+        let mut tx = self.db.begin().await?;
+
+        let Some(session) = self.impersonation_repo.end_expired_with_tx(&mut tx, session_id).await? else {
+            return Ok(None);
+        };
+
+        self.audit_service
+            .record_with_tx(
+                &mut tx,
+                NewAuditEntry::new(
+                    session.organization_id,
+                    session.admin_id,
+                    AuditAction::ImpersonationEnded,
+                    AuditTarget::User,
+                    session.user_id,
+                )
+                .with_after(serde_json::json!({ "impersonation_id": session.id, "ended": "expired" })),
+                &ClientInfo::default(),
+            )
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(session))
+    }
+
+    /// Create a personal access token. The plain token is returned only here.
+    pub async fn create_personal_access_token(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(String, PersonalAccessToken)> {
+        let mut granted: Vec<String> = Vec::new();
+        for scope in scopes {
+            let scope = TokenScope::parse(scope)
+                .ok_or_else(|| crate::error::AppError::Validation(format!("Unknown scope: {}", scope)))?;
+            if !granted.iter().any(|s| s == scope.as_str()) {
+                granted.push(scope.to_string());
+            }
+        }
+        if granted.is_empty() {
+            return Err(crate::error::AppError::Validation("At least one scope is required".into()));
+        }
+        if expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(crate::error::AppError::Validation("expires_at must be in the future".into()));
+        }
+
+        let token = format!("{}{}", PERSONAL_ACCESS_TOKEN_PREFIX, generate_token());
+        let token_prefix = &token[..PERSONAL_ACCESS_TOKEN_PREFIX.len() + 6];
+
+        let stored = self
+            .personal_access_token_repo
+            .create(user_id, name, &hash_token(&token), token_prefix, &granted, expires_at)
+            .await?;
+
+        Ok((token, stored))
+    }
+
+    pub async fn list_personal_access_tokens(&self, user_id: Uuid) -> Result<Vec<PersonalAccessToken>> {
+        self.personal_access_token_repo.find_by_user(user_id).await
+    }
+
+    pub async fn revoke_personal_access_token(&self, user_id: Uuid, token_id: Uuid) -> Result<()> {
+        if self.personal_access_token_repo.delete(token_id, user_id).await? == 0 {
+            return Err(crate::error::AppError::NotFound("Token not found".into()));
+        }
+        Ok(())
+    }
+
+    /// Resolve a personal access token, updating its last-used time.
+    pub async fn authenticate_personal_access_token(&self, token: &str) -> Result<Option<PersonalAccessToken>> {
+        self.personal_access_token_repo.touch_by_hash(&hash_token(token)).await
+    }
       pub async fn find_by_email(&self, email: &str) -> Result<Option<User>> {
         self.user_repo.find_by_email(email).await
     }
-   pub async fn find_by_id(&self, id: uuid::Uuid) -> Result<Option<User>> {
+   pub async fn find_by_id(&self, id: Uuid) -> Result<Option<User>> {
         self.user_repo.find_by_id(id).await
     }
-    pub async fn google_login_or_register(
+    /// Start an OAuth login, or a link when `link_user_id` is set.
+    ///
+    /// State and PKCE verifier are kept server-side until the callback.
+    /// Logins are also bound to the browser through `browser_binding`.
+    pub async fn begin_oauth(
         &self,
-        username: &str,
-        email: &str,
-        google_id: &str,
-        avatar_url: &str,
-    ) -> Result<(User, String, String)> {
+        provider: &OAuthProvider,
+        link_user_id: Option<Uuid>,
+        redirect_url: Option<&str>,
+    ) -> Result<OAuthStart> {
+        let redirect_url = match redirect_url {
+            Some(url) if is_allowed_redirect(url, &self.oauth_allowed_redirects) => Some(url.to_string()),
+            Some(_) => return Err(crate::error::AppError::BadRequest("redirect_uri is not allowed".into())),
+            None => self.oauth_default_redirect_url.clone(),
+        };
+
+        let state = generate_token();
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+        let browser_binding = link_user_id.is_none().then(generate_token);
+
+        self.oauth_state_repo
+            .create_authorization(
+                &hash_token(&state),
+                &provider.name,
+                pkce_verifier.secret(),
+                link_user_id,
+                redirect_url.as_deref(),
+                browser_binding.as_deref().map(hash_token).as_deref(),
+                Utc::now() + Duration::minutes(OAUTH_AUTHORIZATION_MINUTES),
+            )
+            .await?;
+
+        Ok(OAuthStart {
+            authorization_url: provider.authorize_url(state, pkce_challenge),
+            browser_binding,
+        })
+    }
+
+    /// Handle the provider's callback for a pending authorization.
+    ///
+    /// Once the state checks out, failures are reported to the app through
+    /// its redirect URL, if it gave one.
+    pub async fn complete_oauth(
+        &self,
+        provider: &OAuthProvider,
+        state: &str,
+        code: Option<&str>,
+        provider_error: Option<&str>,
+        browser_binding: Option<&str>,
+        client: &ClientInfo,
+    ) -> Result<OAuthCompletion> {
+        let authorization = self
+            .oauth_state_repo
+            .consume_authorization(&hash_token(state), &provider.name)
+            .await?
+            .ok_or_else(|| crate::error::AppError::BadRequest("Invalid or expired OAuth state".into()))?;
+
+        if let Some(expected) = &authorization.browser_binding_hash {
+            if browser_binding.map(hash_token).as_ref() != Some(expected) {
+                return Err(crate::error::AppError::BadRequest(
+                    "The login was started in a different browser".into(),
+                ));
+            }
+        }
+
+        let result = match (code, provider_error) {
+            (Some(code), None) => {
+                self.finish_oauth(provider, code, &authorization.pkce_verifier, authorization.link_user_id)
+                    .await
+            }
+            (_, error) => Err(crate::error::AppError::Authentication(format!(
+                "Login was not approved: {}",
+                error.unwrap_or("no authorization code")
+            ))),
+        };
+
+        let Some(redirect_url) = authorization.redirect_url else {
+            return Ok(match result? {
+                OAuthResult::LoggedIn(user) => OAuthCompletion::LoggedIn(self.complete_first_factor(user, client).await?),
+                OAuthResult::Linked(identity) => OAuthCompletion::Linked(identity),
+            });
+        };
+
+        // Tokens never travel in the URL: the app trades the code for them.
+        let redirect = match result {
+            Ok(OAuthResult::LoggedIn(user)) => {
+                let code = generate_token();
+                self.oauth_state_repo
+                    .create_login_code(
+                        &hash_token(&code),
+                        user.id,
+                        Utc::now() + Duration::seconds(OAUTH_LOGIN_CODE_SECONDS),
+                    )
+                    .await?;
+                with_query(&redirect_url, &[("code", &code)])?
+            }
+            Ok(OAuthResult::Linked(identity)) => with_query(&redirect_url, &[("linked", &identity.provider)])?,
+            Err(e) => with_query(&redirect_url, &[("error", &public_error_message(&e))])?,
+        };
+
+        Ok(OAuthCompletion::Redirect(redirect))
+    }
+
+    async fn finish_oauth(
+        &self,
+        provider: &OAuthProvider,
+        code: &str,
+        pkce_verifier: &str,
+        link_user_id: Option<Uuid>,
+    ) -> Result<OAuthResult> {
+        let profile = provider.exchange_code(code, pkce_verifier).await?;
+
+        match link_user_id {
+            Some(user_id) => Ok(OAuthResult::Linked(self.link_identity(user_id, &provider.name, &profile).await?)),
+            None => Ok(OAuthResult::LoggedIn(self.resolve_oauth_user(&provider.name, &profile).await?)),
+        }
+    }
+
+    /// Trade the code from an OAuth login redirect for tokens (or a 2FA challenge).
+    pub async fn exchange_oauth_login_code(&self, code: &str, client: &ClientInfo) -> Result<LoginOutcome> {
+        let user_id = self
+            .oauth_state_repo
+            .consume_login_code(&hash_token(code))
+            .await?
+            .ok_or_else(|| crate::error::AppError::BadRequest("Invalid or expired login code".into()))?;
+
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::Authentication("User not found".into()))?;
+
+        self.complete_first_factor(user, client).await
+    }
+
+    /// The account behind an external identity, created on first use.
+    ///
+    /// An unknown identity whose email already belongs to an account is not
+    /// merged automatically; the owner has to link it while signed in.
+    async fn resolve_oauth_user(&self, provider: &str, profile: &ExternalProfile) -> Result<User> {
+        if let Some(identity) = self.identity_repo.find_by_subject(provider, &profile.subject).await? {
+            self.identity_repo.record_login(identity.id, profile.email.as_deref()).await?;
+
+            return self
+                .user_repo
+                .find_by_id(identity.user_id)
+                .await?
+                .ok_or_else(|| crate::error::AppError::Authentication("User not found".into()));
+        }
+
+        let email = profile
+            .email
+            .as_deref()
+            .ok_or_else(|| crate::error::AppError::BadRequest("The login provider did not share an email address".into()))?;
+
+        if self.user_repo.find_by_email(email).await?.is_some() {
+            return Err(crate::error::AppError::BadRequest(
+                "An account with this email already exists. Sign in and link this provider from your account".into(),
+            ));
+        }
+
+        let username = self.available_username(profile).await?;
+
         let mut tx = self.db.begin().await?;
-        
+
         let user = self
             .user_repo
-            .upsert_google_user_with_tx(&mut tx, username, email, google_id, avatar_url)
+            .create_external_user_with_tx(&mut tx, &username, email, profile.avatar_url.as_deref(), profile.email_verified)
+            .await?;
+        self.identity_repo
+            .create_with_tx(&mut tx, user.id, provider, &profile.subject, Some(email))
             .await?;
 
-        let access_token = create_access_token(user.id, &user.email, &user.role, &self.jwt_secret)?;
-        let refresh_token = create_refresh_token(user.id, &user.email, &user.role, &self.jwt_secret)?;
+        tx.commit().await?;
 
-        let expires_at = Utc::now() + Duration::days(7);
-        self.refresh_token_repo
-            .create_with_tx(&mut tx, user.id, &refresh_token, expires_at)
+        if !user.email_verified {
+            if let Err(e) = self.send_email_verification(user.id).await {
+                tracing::error!("Failed to send verification email: {:?}", e);
+            }
+        }
+
+        Ok(user)
+    }
+
+    /// The provider's login name, or the part of the email before the `@`,
+    /// with a random suffix if it is taken.
+    async fn available_username(&self, profile: &ExternalProfile) -> Result<String> {
+        let base = profile
+            .username
+            .clone()
+            .or_else(|| profile.email.as_deref().and_then(|e| e.split('@').next()).map(str::to_string))
+            .or_else(|| profile.name.clone())
+            .unwrap_or_else(|| "user".to_string());
+
+        if !self.user_repo.username_exists(&base).await? {
+            return Ok(base);
+        }
+        Ok(format!("{}-{}", base, &generate_token()[..6]))
+    }
+
+    pub async fn link_identity(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        profile: &ExternalProfile,
+    ) -> Result<UserIdentity> {
+        if let Some(identity) = self.identity_repo.find_by_subject(provider, &profile.subject).await? {
+            if identity.user_id != user_id {
+                return Err(crate::error::AppError::BadRequest(
+                    "This login is already linked to another account".into(),
+                ));
+            }
+            return Ok(identity);
+        }
+
+        if self.identity_repo.find_by_user(user_id).await?.iter().any(|i| i.provider == provider) {
+            return Err(crate::error::AppError::BadRequest(
+                "Another login of this provider is already linked; unlink it first".into(),
+            ));
+        }
+
+        let mut tx = self.db.begin().await?;
+        let identity = self
+            .identity_repo
+            .create_with_tx(&mut tx, user_id, provider, &profile.subject, profile.email.as_deref())
             .await?;
-            
         tx.commit().await?;
 
-        Ok((user, access_token, refresh_token))
+        Ok(identity)
+    }
+
+    pub async fn list_identities(&self, user_id: Uuid) -> Result<Vec<UserIdentity>> {
+        self.identity_repo.find_by_user(user_id).await
+    }
+
+    /// Unlink an identity, unless it is the only way left to sign in.
+    pub async fn unlink_identity(&self, user_id: Uuid, identity_id: Uuid) -> Result<()> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("User not found".into()))?;
+        let identities = self.identity_repo.find_by_user(user_id).await?;
+
+        if !identities.iter().any(|i| i.id == identity_id) {
+            return Err(crate::error::AppError::NotFound("Identity not found".into()));
+        }
+        if user.password_hash.is_none() && identities.len() == 1 {
+            return Err(crate::error::AppError::BadRequest(
+                "Set a password or link another provider before unlinking your only login".into(),
+            ));
+        }
+
+        self.identity_repo.delete(identity_id, user_id).await?;
+        Ok(())
+    }
+}
+
+fn account_key(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// What a login attempt counts against: the account, and the caller's address if known.
+fn login_subjects(email: &str, client: &ClientInfo) -> Vec<(LockoutScope, String)> {
+    let mut subjects = vec![(LockoutScope::Account, account_key(email))];
+    if let Some(ip_address) = &client.ip_address {
+        subjects.push((LockoutScope::Ip, ip_address.clone()));
+    }
+    subjects
+}
+
+/// Message safe to hand to the browser in a redirect.
+fn public_error_message(error: &crate::error::AppError) -> String {
+    use crate::error::AppError;
+
+    match error {
+        AppError::Authentication(msg)
+        | AppError::Validation(msg)
+        | AppError::NotFound(msg)
+        | AppError::Unauthorized(msg)
+        | AppError::Forbidden(msg)
+        | AppError::BadRequest(msg)
+        | AppError::TooManyRequests(msg) => msg.clone(),
+        AppError::Database(_) | AppError::InternalError => "Login failed".to_string(),
     }
 }
@@ -0,0 +1,49 @@
+use axum::http::Method;
+
+/// Where an impersonation token ends its own session.
+pub const END_IMPERSONATION_PATH: &str = "/api/auth/impersonation/end";
+
+/// Whether an admin impersonating a user may call `method path` (the full
+/// `/api/...` path). Impersonation is for seeing what the user sees, so only
+/// reads are allowed, apart from ending the impersonation. Even reads of the
+/// user's sign-in settings, sessions and tokens are refused, as are the admin
+/// endpoints, so the user's own permissions are never borrowed, and the
+/// WebSocket, which would let the admin send messages as the user.
+pub fn allows_while_impersonating(method: &Method, path: &str) -> bool {
+    if path == END_IMPERSONATION_PATH {
+        return true;
+    }
+
+    let read = method == Method::GET || method == Method::HEAD;
+    let account_security = path.starts_with("/api/auth/");
+    let administration = path.starts_with("/api/admin/");
+    let websocket = path == "/api/ws";
+    read && !(account_security || administration || websocket)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_impersonation_only_allows_reads() {
+        assert!(allows_while_impersonating(&Method::GET, "/api/users/me"));
+        assert!(allows_while_impersonating(&Method::GET, "/api/tasks"));
+        assert!(allows_while_impersonating(&Method::GET, "/api/organizations/7/members"));
+        assert!(allows_while_impersonating(&Method::POST, END_IMPERSONATION_PATH));
+
+        assert!(!allows_while_impersonating(&Method::POST, "/api/tasks"));
+        assert!(!allows_while_impersonating(&Method::DELETE, "/api/tasks/1"));
+        assert!(!allows_while_impersonating(&Method::POST, "/api/tasks/1/transfer"));
+        assert!(!allows_while_impersonating(&Method::DELETE, "/api/organizations/7/members/3"));
+        assert!(!allows_while_impersonating(&Method::DELETE, "/api/tasks/1/public-links/2"));
+        assert!(!allows_while_impersonating(&Method::DELETE, "/api/tasks/1/join-links/2"));
+        assert!(!allows_while_impersonating(&Method::PUT, "/api/users/me"));
+        assert!(!allows_while_impersonating(&Method::DELETE, "/api/users/me"));
+        assert!(!allows_while_impersonating(&Method::POST, "/api/auth/change-password"));
+        assert!(!allows_while_impersonating(&Method::POST, "/api/auth/2fa/disable"));
+        assert!(!allows_while_impersonating(&Method::GET, "/api/auth/tokens"));
+        assert!(!allows_while_impersonating(&Method::GET, "/api/admin/users"));
+        assert!(!allows_while_impersonating(&Method::GET, "/api/ws"));
+    }
+}
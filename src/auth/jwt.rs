@@ -1,90 +1,199 @@
 use crate::error::{AppError, Result};
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::jwt_keys::JwtKeys;
+
+pub const ACCESS_TOKEN_MINUTES: i64 = 15;
+pub const REFRESH_TOKEN_DAYS: i64 = 7;
+pub const IMPERSONATION_MINUTES: i64 = 10;
+/// Tolerated clock difference for `iat`, in seconds.
+const CLOCK_SKEW_SECONDS: i64 = 60;
+
+/// What a token may be used for; each kind is only accepted where it belongs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenType {
+    Access,
+    Refresh,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String, // user_id
     pub email: String,
-    pub role: String,
+    pub typ: TokenType,
+    pub iss: String,
+    pub aud: String,
+    pub iat: i64,
     pub exp: i64,
+    /// Session (refresh token family) the token was issued for
+    pub sid: Uuid,
+    /// Unique per token, so two tokens issued in the same second never collide
+    pub jti: String,
+    /// Active organization; tokens issued before organizations existed have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org: Option<Uuid>,
+    /// Admin acting as `sub`; `sid` is then the impersonation session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Uuid>,
 }
 
-/// Create access token (short-lived, 15 minutes)
-pub fn create_access_token(user_id: Uuid, email: &str, role: &str, secret: &str) -> Result<String> {
-    let expiration = Utc::now()
-        .checked_add_signed(Duration::minutes(15))
+fn build_claims(
+    keys: &JwtKeys,
+    typ: TokenType,
+    lifetime: Duration,
+    user_id: Uuid,
+    email: &str,
+    session_id: Uuid,
+    organization_id: Uuid,
+) -> Result<Claims> {
+    let now = Utc::now();
+    let expiration = now
+        .checked_add_signed(lifetime)
         .ok_or(AppError::InternalError)?
         .timestamp();
 
     let claims = Claims {
         sub: user_id.to_string(),
         email: email.to_string(),
-        role: role.to_string(),
+        typ,
+        iss: keys.issuer().to_string(),
+        aud: keys.audience().to_string(),
+        iat: now.timestamp(),
         exp: expiration,
+        sid: session_id,
+        jti: Uuid::new_v4().to_string(),
+        org: Some(organization_id),
+        act: None,
     };
 
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
-    .map_err(|_| AppError::Authentication("Failed to create access token".to_string()))
+    Ok(claims)
+}
+
+/// Create access token (short-lived, 15 minutes)
+pub fn create_access_token(
+    keys: &JwtKeys,
+    user_id: Uuid,
+    email: &str,
+    session_id: Uuid,
+    organization_id: Uuid,
+) -> Result<String> {
+    keys.sign(&build_claims(
+        keys,
+        TokenType::Access,
+        Duration::minutes(ACCESS_TOKEN_MINUTES),
+        user_id,
+        email,
+        session_id,
+        organization_id,
+    )?)
 }
 
 /// Create refresh token (long-lived, 7 days)
-pub fn create_refresh_token(user_id: Uuid, email: &str, role: &str, secret: &str) -> Result<String> {
-    let expiration = Utc::now()
-        .checked_add_signed(Duration::days(7))
-        .ok_or(AppError::InternalError)?
-        .timestamp();
+pub fn create_refresh_token(
+    keys: &JwtKeys,
+    user_id: Uuid,
+    email: &str,
+    session_id: Uuid,
+    organization_id: Uuid,
+) -> Result<String> {
+    keys.sign(&build_claims(
+        keys,
+        TokenType::Refresh,
+        Duration::days(REFRESH_TOKEN_DAYS),
+        user_id,
+        email,
+        session_id,
+        organization_id,
+    )?)
+}
 
-    let claims = Claims {
-        sub: user_id.to_string(),
-        email: email.to_string(),
-        role: role.to_string(),
-        exp: expiration,
-    };
+/// Create an access token for `admin_id` acting as `user_id`, valid until
+/// the impersonation session `session_id` expires. No refresh token goes
+/// with it.
+pub fn create_impersonation_token(
+    keys: &JwtKeys,
+    user_id: Uuid,
+    email: &str,
+    session_id: Uuid,
+    organization_id: Uuid,
+    admin_id: Uuid,
+) -> Result<String> {
+    let mut claims = build_claims(
+        keys,
+        TokenType::Access,
+        Duration::minutes(IMPERSONATION_MINUTES),
+        user_id,
+        email,
+        session_id,
+        organization_id,
+    )?;
+    claims.act = Some(admin_id);
 
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
-    .map_err(|_| AppError::Authentication("Failed to create refresh token".to_string()))
+    keys.sign(&claims)
 }
 
-/// Verify JWT token and extract claims
-pub fn verify_jwt(token: &str, secret: &str) -> Result<Claims> {
-    decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(secret.as_bytes()),
-        &Validation::default(),
-    )
-  .map(|data| data.claims)
-    .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))
+/// Verify a JWT of the `expected` type and extract its claims.
+///
+/// Besides signature and expiry, issuer and audience must match ours and
+/// `iat` must not lie in the future.
+pub async fn verify_jwt(keys: &JwtKeys, token: &str, expected: TokenType) -> Result<Claims> {
+    let claims: Claims = keys.decode(token).await?;
+
+    if claims.typ != expected {
+        return Err(AppError::Unauthorized("Invalid token type".to_string()));
+    }
+    if claims.iat > Utc::now().timestamp() + CLOCK_SKEW_SECONDS || claims.jti.is_empty() {
+        return Err(AppError::Unauthorized("Invalid token".to_string()));
+    }
+
+    Ok(claims)
 }
 
-/// Legacy function for backward compatibility
-pub fn create_jwt(user_id: Uuid, email: &str, secret: &str, expiration_hours: i64) -> Result<String> {
-    let expiration = Utc::now()
-        .checked_add_signed(Duration::hours(expiration_hours))
-        .ok_or(AppError::InternalError)?
-        .timestamp();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::jwt_keys::SigningAlgorithm;
 
-    let claims = Claims {
-        sub: user_id.to_string(),
-        email: email.to_string(),
-        role: "user".to_string(),
-        exp: expiration,
-    };
+    #[tokio::test]
+    async fn test_refresh_token_is_not_an_access_token() {
+        let keys = JwtKeys::for_tests(SigningAlgorithm::EdDsa);
+        keys.install_generated(Utc::now() - Duration::minutes(1)).await;
+
+        let (user_id, session_id, org_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let access = create_access_token(&keys, user_id, "a@example.com", session_id, org_id).unwrap();
+        let refresh = create_refresh_token(&keys, user_id, "a@example.com", session_id, org_id).unwrap();
+
+        let claims = verify_jwt(&keys, &access, TokenType::Access).await.unwrap();
+        assert_eq!(claims.sub, user_id.to_string());
+        assert_eq!(claims.sid, session_id);
+        assert_eq!(claims.org, Some(org_id));
+
+        assert!(verify_jwt(&keys, &refresh, TokenType::Access).await.is_err());
+        assert!(verify_jwt(&keys, &access, TokenType::Refresh).await.is_err());
+        assert!(verify_jwt(&keys, &refresh, TokenType::Refresh).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rotation_keeps_old_tokens_valid() {
+        let keys = JwtKeys::for_tests(SigningAlgorithm::Rs256);
+        let old_kid = keys.install_generated(Utc::now() - Duration::days(2)).await;
+        let token = create_access_token(&keys, Uuid::new_v4(), "a@example.com", Uuid::new_v4(), Uuid::new_v4()).unwrap();
+
+        // The successor signs from now on, the old key still verifies
+        let new_kid = keys.install_generated(Utc::now() - Duration::seconds(1)).await;
+        let rotated = create_access_token(&keys, Uuid::new_v4(), "a@example.com", Uuid::new_v4(), Uuid::new_v4()).unwrap();
+
+        let kid = |token: &str| jsonwebtoken::decode_header(token).unwrap().kid.unwrap();
+        assert_eq!(kid(&token), old_kid);
+        assert_eq!(kid(&rotated), new_kid);
+        assert!(verify_jwt(&keys, &token, TokenType::Access).await.is_ok());
+        assert_eq!(keys.jwks().keys.len(), 2);
 
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
-    .map_err(|_| AppError::Authentication("Failed to create token".to_string()))
+        // A different audience is rejected even with a valid signature
+        let other = keys.with_audience("someone-else");
+        assert!(verify_jwt(&other, &rotated, TokenType::Access).await.is_err());
+    }
 }
@@ -0,0 +1,358 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::Instant,
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{
+    decode, decode_header, encode,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters,
+        OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+    Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use rsa::{pkcs1::EncodeRsaPrivateKey, traits::PublicKeyParts, RsaPrivateKey};
+use serde::{de::DeserializeOwned, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    error::{AppError, Result},
+    state::Config,
+};
+use super::{auth_models::JwtSigningKey, auth_repository::JwtKeyRepository};
+
+/// New keys are published this long before they start signing, so that
+/// verifiers caching the JWKS already know them.
+pub const KEY_PUBLISH_AHEAD_HOURS: i64 = 24;
+/// An unknown `kid` reloads the key set at most this often.
+const UNKNOWN_KID_RELOAD_SECONDS: u64 = 10;
+const RSA_KEY_BITS: usize = 2048;
+
+/// Algorithm for newly generated keys, set by `JWT_ALGORITHM`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningAlgorithm {
+    /// Ed25519 (`EdDSA`, the default)
+    EdDsa,
+    Rs256,
+}
+
+impl SigningAlgorithm {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "EdDSA" => Some(Self::EdDsa),
+            "RS256" => Some(Self::Rs256),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SigningAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SigningAlgorithm::EdDsa => write!(f, "EdDSA"),
+            SigningAlgorithm::Rs256 => write!(f, "RS256"),
+        }
+    }
+}
+
+struct LoadedKey {
+    kid: String,
+    algorithm: Algorithm,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    jwk: Jwk,
+    activates_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+impl LoadedKey {
+    fn from_row(row: JwtSigningKey) -> Result<Self> {
+        let jwk: Jwk = serde_json::from_value(row.public_jwk).map_err(|_| AppError::InternalError)?;
+        let decoding_key = DecodingKey::from_jwk(&jwk).map_err(|_| AppError::InternalError)?;
+
+        let (algorithm, encoding_key) = match SigningAlgorithm::parse(&row.algorithm) {
+            Some(SigningAlgorithm::EdDsa) => (Algorithm::EdDSA, EncodingKey::from_ed_der(&row.private_key)),
+            Some(SigningAlgorithm::Rs256) => (Algorithm::RS256, EncodingKey::from_rsa_der(&row.private_key)),
+            None => return Err(AppError::InternalError),
+        };
+
+        Ok(Self {
+            kid: row.kid,
+            algorithm,
+            encoding_key,
+            decoding_key,
+            jwk,
+            activates_at: row.activates_at,
+            expires_at: row.expires_at,
+        })
+    }
+}
+
+struct KeyCache {
+    keys: Vec<LoadedKey>,
+    loaded_at: Instant,
+}
+
+/// The rotating set of keys that signs and verifies our JWTs.
+///
+/// Keys live in the database so that every instance shares them. Each one
+/// signs for `JWT_KEY_ROTATION_DAYS` and stays valid for verification for
+/// `JWT_KEY_OVERLAP_DAYS` more, so tokens outlive the key that signed them.
+#[derive(Clone)]
+pub struct JwtKeys {
+    repo: JwtKeyRepository,
+    algorithm: SigningAlgorithm,
+    rotation: Duration,
+    overlap: Duration,
+    issuer: String,
+    audience: String,
+    cache: Arc<RwLock<KeyCache>>,
+}
+
+impl JwtKeys {
+    pub fn new(db: DbPool, config: &Config) -> Self {
+        Self {
+            repo: JwtKeyRepository::new(db),
+            algorithm: config.jwt_algorithm,
+            rotation: Duration::days(config.jwt_key_rotation_days),
+            overlap: Duration::days(config.jwt_key_overlap_days),
+            issuer: config.jwt_issuer.clone(),
+            audience: config.jwt_audience.clone(),
+            cache: Arc::new(RwLock::new(KeyCache {
+                keys: Vec::new(),
+                loaded_at: Instant::now(),
+            })),
+        }
+    }
+
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    pub fn audience(&self) -> &str {
+        &self.audience
+    }
+
+    /// Schedule the next key when the newest one retires soon, then reload
+    /// the key set. Runs at startup and every minute after.
+    pub async fn refresh(&self) -> Result<()> {
+        let mut rows = self.repo.find_unexpired().await?;
+
+        let now = Utc::now();
+        let needed_until = now + Duration::hours(KEY_PUBLISH_AHEAD_HOURS);
+        let latest_retirement = rows.iter().map(|key| key.retires_at).max();
+
+        if latest_retirement.is_none_or(|retires_at| retires_at <= needed_until) {
+            let activates_at = latest_retirement.map_or(now, |retires_at| retires_at.max(now));
+            let key = self.generate_key(activates_at).await?;
+
+            if self.repo.create_if_needed(&key, needed_until).await? {
+                tracing::info!("Scheduled JWT signing key {} from {}", key.kid, activates_at);
+            }
+            rows = self.repo.find_unexpired().await?;
+        }
+
+        self.install(rows);
+        Ok(())
+    }
+
+    async fn reload(&self) -> Result<()> {
+        let rows = self.repo.find_unexpired().await?;
+        self.install(rows);
+        Ok(())
+    }
+
+    fn install(&self, rows: Vec<JwtSigningKey>) {
+        let keys = rows
+            .into_iter()
+            .filter_map(|row| {
+                let kid = row.kid.clone();
+                LoadedKey::from_row(row)
+                    .map_err(|_| tracing::error!("Skipping unreadable JWT signing key {}", kid))
+                    .ok()
+            })
+            .collect();
+
+        *self.cache.write().expect("JWT key cache lock poisoned") = KeyCache {
+            keys,
+            loaded_at: Instant::now(),
+        };
+    }
+
+    async fn generate_key(&self, activates_at: DateTime<Utc>) -> Result<JwtSigningKey> {
+        let algorithm = self.algorithm;
+        let kid = Uuid::new_v4().simple().to_string();
+
+        // RSA key generation takes a while; keep it off the async workers
+        let jwk_kid = kid.clone();
+        let (private_key, public_jwk) = tokio::task::spawn_blocking(move || generate_key_material(algorithm, jwk_kid))
+            .await
+            .map_err(|_| AppError::InternalError)??;
+
+        let retires_at = activates_at + self.rotation;
+        Ok(JwtSigningKey {
+            kid,
+            algorithm: algorithm.to_string(),
+            private_key,
+            public_jwk: serde_json::to_value(&public_jwk).map_err(|_| AppError::InternalError)?,
+            activates_at,
+            retires_at,
+            expires_at: retires_at + self.overlap,
+        })
+    }
+
+    /// Sign `claims` with the newest key that has become active.
+    pub fn sign<T: Serialize>(&self, claims: &T) -> Result<String> {
+        let now = Utc::now();
+        let cache = self.cache.read().expect("JWT key cache lock poisoned");
+
+        let key = cache
+            .keys
+            .iter()
+            .filter(|key| key.activates_at <= now && key.expires_at > now)
+            .max_by_key(|key| key.activates_at)
+            .ok_or(AppError::InternalError)?;
+
+        let mut header = Header::new(key.algorithm);
+        header.kid = Some(key.kid.clone());
+
+        encode(&header, claims, &key.encoding_key).map_err(|_| AppError::InternalError)
+    }
+
+    /// Check signature, `exp`, `iss` and `aud` of `token` against the key named by its `kid`.
+    pub async fn decode<T: DeserializeOwned>(&self, token: &str) -> Result<T> {
+        let invalid = || AppError::Unauthorized("Invalid token".to_string());
+
+        let kid = decode_header(token).ok().and_then(|header| header.kid).ok_or_else(invalid)?;
+
+        // Another instance may have scheduled a key we have not loaded yet
+        if !self.knows(&kid) && self.may_reload() {
+            self.reload().await?;
+        }
+
+        let cache = self.cache.read().expect("JWT key cache lock poisoned");
+        let key = cache
+            .keys
+            .iter()
+            .find(|key| key.kid == kid && key.expires_at > Utc::now())
+            .ok_or_else(invalid)?;
+
+        let mut validation = Validation::new(key.algorithm);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+        validation.set_required_spec_claims(&["exp", "iat", "iss", "aud", "sub"]);
+
+        decode::<T>(token, &key.decoding_key, &validation)
+            .map(|data| data.claims)
+            .map_err(|_| invalid())
+    }
+
+    fn knows(&self, kid: &str) -> bool {
+        let cache = self.cache.read().expect("JWT key cache lock poisoned");
+        cache.keys.iter().any(|key| key.kid == kid)
+    }
+
+    fn may_reload(&self) -> bool {
+        let cache = self.cache.read().expect("JWT key cache lock poisoned");
+        cache.loaded_at.elapsed().as_secs() >= UNKNOWN_KID_RELOAD_SECONDS
+    }
+
+    /// Public halves of every key that is, or is about to be, in use.
+    pub fn jwks(&self) -> JwkSet {
+        let now = Utc::now();
+        let cache = self.cache.read().expect("JWT key cache lock poisoned");
+
+        JwkSet {
+            keys: cache
+                .keys
+                .iter()
+                .filter(|key| key.expires_at > now)
+                .map(|key| key.jwk.clone())
+                .collect(),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn for_tests(algorithm: SigningAlgorithm) -> Self {
+        let db = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+
+        Self {
+            repo: JwtKeyRepository::new(db),
+            algorithm,
+            rotation: Duration::days(30),
+            overlap: Duration::days(8),
+            issuer: "task-manager".to_string(),
+            audience: "task-manager-api".to_string(),
+            cache: Arc::new(RwLock::new(KeyCache {
+                keys: Vec::new(),
+                loaded_at: Instant::now(),
+            })),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_audience(&self, audience: &str) -> Self {
+        Self {
+            audience: audience.to_string(),
+            ..self.clone()
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) async fn install_generated(&self, activates_at: DateTime<Utc>) -> String {
+        let key = self.generate_key(activates_at).await.unwrap();
+        let kid = key.kid.clone();
+
+        self.cache.write().unwrap().keys.push(LoadedKey::from_row(key).unwrap());
+        kid
+    }
+}
+
+/// A fresh private key (DER) and the JWK of its public half.
+fn generate_key_material(algorithm: SigningAlgorithm, kid: String) -> Result<(Vec<u8>, Jwk)> {
+    let common = |key_algorithm| CommonParameters {
+        public_key_use: Some(PublicKeyUse::Signature),
+        key_algorithm: Some(key_algorithm),
+        key_id: Some(kid.clone()),
+        ..Default::default()
+    };
+
+    match algorithm {
+        SigningAlgorithm::EdDsa => {
+            let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new())
+                .map_err(|_| AppError::InternalError)?;
+            let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).map_err(|_| AppError::InternalError)?;
+
+            let jwk = Jwk {
+                common: common(KeyAlgorithm::EdDSA),
+                algorithm: AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                    key_type: OctetKeyPairType::OctetKeyPair,
+                    curve: EllipticCurve::Ed25519,
+                    x: URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref()),
+                }),
+            };
+            Ok((pkcs8.as_ref().to_vec(), jwk))
+        }
+        SigningAlgorithm::Rs256 => {
+            let private_key =
+                RsaPrivateKey::new(&mut rand::rngs::OsRng, RSA_KEY_BITS).map_err(|_| AppError::InternalError)?;
+            let der = private_key.to_pkcs1_der().map_err(|_| AppError::InternalError)?;
+
+            let jwk = Jwk {
+                common: common(KeyAlgorithm::RS256),
+                algorithm: AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: URL_SAFE_NO_PAD.encode(private_key.n().to_bytes_be()),
+                    e: URL_SAFE_NO_PAD.encode(private_key.e().to_bytes_be()),
+                }),
+            };
+            Ok((der.as_bytes().to_vec(), jwk))
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::Duration;
+
+/// Failed logins before an account is locked.
+pub const ACCOUNT_LOCKOUT_THRESHOLD: i32 = 5;
+/// Failed logins from one address, across all accounts, before it is locked.
+pub const IP_LOCKOUT_THRESHOLD: i32 = 20;
+/// Failures older than this are forgotten.
+pub const FAILED_ATTEMPT_WINDOW_HOURS: i64 = 24;
+const LOCKOUT_BASE_SECONDS: i64 = 60;
+const LOCKOUT_MAX_HOURS: i64 = 24;
+
+/// What failed logins are counted against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockoutScope {
+    /// Keyed by the lowercased email, so unknown addresses lock like real ones
+    Account,
+    /// Keyed by the client IP address
+    Ip,
+}
+
+impl LockoutScope {
+    pub fn threshold(&self) -> i32 {
+        match self {
+            LockoutScope::Account => ACCOUNT_LOCKOUT_THRESHOLD,
+            LockoutScope::Ip => IP_LOCKOUT_THRESHOLD,
+        }
+    }
+}
+
+impl std::fmt::Display for LockoutScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LockoutScope::Account => write!(f, "account"),
+            LockoutScope::Ip => write!(f, "ip"),
+        }
+    }
+}
+
+/// How long to lock after `failed_attempts` failures in a row, if at all.
+///
+/// The lock starts at a minute on reaching `threshold` and doubles with
+/// every further failure, up to a day.
+pub fn lockout_duration(failed_attempts: i32, threshold: i32) -> Option<Duration> {
+    if failed_attempts < threshold {
+        return None;
+    }
+
+    let doublings = (failed_attempts - threshold).min(16) as u32;
+    let seconds = (LOCKOUT_BASE_SECONDS << doublings).min(LOCKOUT_MAX_HOURS * 3600);
+    Some(Duration::seconds(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lockout_backs_off_exponentially() {
+        assert_eq!(lockout_duration(4, 5), None);
+        assert_eq!(lockout_duration(5, 5), Some(Duration::minutes(1)));
+        assert_eq!(lockout_duration(6, 5), Some(Duration::minutes(2)));
+        assert_eq!(lockout_duration(8, 5), Some(Duration::minutes(8)));
+        assert_eq!(lockout_duration(40, 5), Some(Duration::hours(24)));
+    }
+}
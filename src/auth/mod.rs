@@ -1,7 +1,15 @@
 // Declare existing modules
+pub mod impersonation;
 pub mod jwt;
+pub mod jwt_keys;
+pub mod lockout;
 pub mod oauth;
 pub mod password;
+pub mod password_policy;
+pub mod secure_token;
+pub mod token_scope;
+pub mod totp;
+pub mod verification_policy;
 
 // Declare submodules
 pub mod auth_models;
@@ -11,6 +19,8 @@ pub mod auth_handlers;
 pub mod auth_service;
 
 // Re-export public items
-pub use jwt::{create_access_token, create_refresh_token, verify_jwt};
-pub use oauth::create_oauth_client;
-pub use password::{hash_password, verify_password};
+pub use jwt::{create_access_token, create_refresh_token, verify_jwt, TokenType};
+pub use jwt_keys::JwtKeys;
+pub use oauth::OAuthProviderRegistry;
+pub use password::{hash_password, needs_rehash, verify_password};
+pub use secure_token::{generate_token, hash_token};
@@ -1,35 +1,469 @@
 use crate::error::{AppError, Result};
 use oauth2::{
-    basic::BasicClient, AuthUrl, ClientId, ClientSecret, RedirectUrl, TokenUrl,
+    basic::BasicClient, url::Url, AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken,
+    PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, Scope, TokenResponse, TokenUrl,
 };
+use serde::Deserialize;
+use std::collections::HashMap;
 
-pub fn create_oauth_client(
-    client_id: String,
-    client_secret: String,
-    redirect_uri: String,
-) -> Result<BasicClient> {
-    let google_client_id = ClientId::new(client_id);
-    let google_client_secret = ClientSecret::new(client_secret);
-    let auth_url = AuthUrl::new("https://accounts.google.com/o/oauth2/v2/auth".to_string())
-        .map_err(|_| AppError::InternalError)?;
-    let token_url = TokenUrl::new("https://oauth2.googleapis.com/token".to_string())
-        .map_err(|_| AppError::InternalError)?;
-    let redirect_url = RedirectUrl::new(redirect_uri)
-        .map_err(|_| AppError::InternalError)?;
-
-    Ok(BasicClient::new(
-        google_client_id,
-        Some(google_client_secret),
-        auth_url,
-        Some(token_url),
-    )
-    .set_redirect_uri(redirect_url))
-}
-
-#[derive(serde::Deserialize)]
-pub struct GoogleUserInfo {
-    pub id: String,
-    pub email: String,
+/// How a provider's profile is fetched and read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    /// OpenID Connect: the standard userinfo endpoint
+    Oidc,
+    /// GitHub's REST API, which is OAuth 2.0 only
+    GitHub,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderEndpoints {
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum EndpointSource {
+    Fixed(ProviderEndpoints),
+    /// Read from `{issuer}/.well-known/openid-configuration` at startup
+    Discovery { issuer: String },
+}
+
+/// One login provider, as configured in the environment.
+#[derive(Debug, Clone)]
+pub struct OAuthProviderConfig {
+    /// URL slug, e.g. `google` in `/api/auth/oauth/google`
     pub name: String,
-    pub picture: Option<String>,
+    pub display_name: String,
+    pub kind: ProviderKind,
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    pub endpoints: EndpointSource,
+}
+
+/// Providers enabled through the environment:
+///
+/// - Google, if `GOOGLE_CLIENT_ID` is set
+/// - GitHub, if `GITHUB_CLIENT_ID` is set
+/// - every name in `OIDC_PROVIDERS`, configured by `OIDC_<NAME>_ISSUER`,
+///   `_CLIENT_ID`, `_CLIENT_SECRET` and optionally `_REDIRECT_URI`,
+///   `_SCOPES` and `_DISPLAY_NAME`
+pub fn provider_configs_from_env() -> Vec<OAuthProviderConfig> {
+    let redirect_base = std::env::var("OAUTH_REDIRECT_BASE_URL")
+        .unwrap_or_else(|_| "http://localhost:3000".to_string());
+    let default_redirect = |name: &str| format!("{}/api/auth/oauth/{}/callback", redirect_base.trim_end_matches('/'), name);
+
+    let mut configs = Vec::new();
+
+    if let Ok(client_id) = std::env::var("GOOGLE_CLIENT_ID") {
+        configs.push(OAuthProviderConfig {
+            name: "google".to_string(),
+            display_name: "Google".to_string(),
+            kind: ProviderKind::Oidc,
+            client_id,
+            client_secret: std::env::var("GOOGLE_CLIENT_SECRET").expect("GOOGLE_CLIENT_SECRET must be set"),
+            redirect_uri: std::env::var("GOOGLE_REDIRECT_URI").unwrap_or_else(|_| default_redirect("google")),
+            scopes: vec!["openid".to_string(), "email".to_string(), "profile".to_string()],
+            endpoints: EndpointSource::Fixed(ProviderEndpoints {
+                authorization_endpoint: "https://accounts.google.com/o/oauth2/v2/auth".to_string(),
+                token_endpoint: "https://oauth2.googleapis.com/token".to_string(),
+                userinfo_endpoint: "https://openidconnect.googleapis.com/v1/userinfo".to_string(),
+            }),
+        });
+    }
+
+    if let Ok(client_id) = std::env::var("GITHUB_CLIENT_ID") {
+        configs.push(OAuthProviderConfig {
+            name: "github".to_string(),
+            display_name: "GitHub".to_string(),
+            kind: ProviderKind::GitHub,
+            client_id,
+            client_secret: std::env::var("GITHUB_CLIENT_SECRET").expect("GITHUB_CLIENT_SECRET must be set"),
+            redirect_uri: std::env::var("GITHUB_REDIRECT_URI").unwrap_or_else(|_| default_redirect("github")),
+            scopes: vec!["read:user".to_string(), "user:email".to_string()],
+            endpoints: EndpointSource::Fixed(ProviderEndpoints {
+                authorization_endpoint: "https://github.com/login/oauth/authorize".to_string(),
+                token_endpoint: "https://github.com/login/oauth/access_token".to_string(),
+                userinfo_endpoint: "https://api.github.com/user".to_string(),
+            }),
+        });
+    }
+
+    let oidc_names = std::env::var("OIDC_PROVIDERS").unwrap_or_default();
+    for name in oidc_names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let name = name.to_lowercase();
+        let var = |key: &str| std::env::var(format!("OIDC_{}_{}", name.to_uppercase().replace('-', "_"), key));
+
+        configs.push(OAuthProviderConfig {
+            display_name: var("DISPLAY_NAME").unwrap_or_else(|_| name.clone()),
+            kind: ProviderKind::Oidc,
+            client_id: var("CLIENT_ID").unwrap_or_else(|_| panic!("OIDC client id for {} must be set", name)),
+            client_secret: var("CLIENT_SECRET").unwrap_or_else(|_| panic!("OIDC client secret for {} must be set", name)),
+            redirect_uri: var("REDIRECT_URI").unwrap_or_else(|_| default_redirect(&name)),
+            scopes: var("SCOPES")
+                .unwrap_or_else(|_| "openid email profile".to_string())
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+            endpoints: EndpointSource::Discovery {
+                issuer: var("ISSUER").unwrap_or_else(|_| panic!("OIDC issuer for {} must be set", name)),
+            },
+            name,
+        });
+    }
+
+    configs
+}
+
+/// What we learn about a user from their provider.
+#[derive(Debug, Clone)]
+pub struct ExternalProfile {
+    /// Stable id of the user at the provider (`sub`)
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+    /// Login name at the provider, used to suggest a username
+    pub username: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+pub struct OAuthProvider {
+    pub name: String,
+    pub display_name: String,
+    /// Our callback URL registered at the provider
+    pub redirect_uri: String,
+    kind: ProviderKind,
+    client: BasicClient,
+    scopes: Vec<String>,
+    userinfo_endpoint: String,
+    http: reqwest::Client,
+}
+
+impl OAuthProvider {
+    fn new(config: &OAuthProviderConfig, endpoints: ProviderEndpoints, http: reqwest::Client) -> Result<Self> {
+        let client = BasicClient::new(
+            ClientId::new(config.client_id.clone()),
+            Some(ClientSecret::new(config.client_secret.clone())),
+            AuthUrl::new(endpoints.authorization_endpoint).map_err(|_| AppError::InternalError)?,
+            Some(TokenUrl::new(endpoints.token_endpoint).map_err(|_| AppError::InternalError)?),
+        )
+        .set_redirect_uri(RedirectUrl::new(config.redirect_uri.clone()).map_err(|_| AppError::InternalError)?);
+
+        Ok(Self {
+            name: config.name.clone(),
+            display_name: config.display_name.clone(),
+            redirect_uri: config.redirect_uri.clone(),
+            kind: config.kind,
+            client,
+            scopes: config.scopes.clone(),
+            userinfo_endpoint: endpoints.userinfo_endpoint,
+            http,
+        })
+    }
+
+    /// Where to send the browser to sign in; `state` comes back on the callback.
+    pub fn authorize_url(&self, state: String, pkce_challenge: PkceCodeChallenge) -> String {
+        let (url, _) = self
+            .client
+            .authorize_url(|| CsrfToken::new(state))
+            .add_scopes(self.scopes.iter().cloned().map(Scope::new))
+            .set_pkce_challenge(pkce_challenge)
+            .url();
+
+        url.to_string()
+    }
+
+    /// Trade an authorization code and the matching PKCE verifier for the user's profile.
+    pub async fn exchange_code(&self, code: &str, pkce_verifier: &str) -> Result<ExternalProfile> {
+        let token = self
+            .client
+            .exchange_code(AuthorizationCode::new(code.to_string()))
+            .set_pkce_verifier(PkceCodeVerifier::new(pkce_verifier.to_string()))
+            .request_async(oauth2::reqwest::async_http_client)
+            .await
+            .map_err(|_| AppError::Authentication("Failed to exchange code".to_string()))?;
+        let access_token = token.access_token().secret();
+
+        match self.kind {
+            ProviderKind::Oidc => self.oidc_profile(access_token).await,
+            ProviderKind::GitHub => self.github_profile(access_token).await,
+        }
+    }
+
+    async fn oidc_profile(&self, access_token: &str) -> Result<ExternalProfile> {
+        #[derive(Deserialize)]
+        struct UserInfo {
+            sub: String,
+            email: Option<String>,
+            #[serde(default)]
+            email_verified: bool,
+            name: Option<String>,
+            preferred_username: Option<String>,
+            picture: Option<String>,
+        }
+
+        let info: UserInfo = self.get_json(&self.userinfo_endpoint, access_token).await?;
+
+        Ok(ExternalProfile {
+            subject: info.sub,
+            email: info.email,
+            email_verified: info.email_verified,
+            name: info.name,
+            username: info.preferred_username,
+            avatar_url: info.picture,
+        })
+    }
+
+    async fn github_profile(&self, access_token: &str) -> Result<ExternalProfile> {
+        #[derive(Deserialize)]
+        struct GitHubUser {
+            id: i64,
+            login: String,
+            name: Option<String>,
+            avatar_url: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct GitHubEmail {
+            email: String,
+            primary: bool,
+            verified: bool,
+        }
+
+        let user: GitHubUser = self.get_json(&self.userinfo_endpoint, access_token).await?;
+        let emails: Vec<GitHubEmail> = self
+            .get_json(&format!("{}/emails", self.userinfo_endpoint), access_token)
+            .await?;
+        let primary = emails.into_iter().find(|e| e.primary);
+
+        Ok(ExternalProfile {
+            subject: user.id.to_string(),
+            email_verified: primary.as_ref().is_some_and(|e| e.verified),
+            email: primary.map(|e| e.email),
+            name: user.name,
+            username: Some(user.login),
+            avatar_url: user.avatar_url,
+        })
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, url: &str, access_token: &str) -> Result<T> {
+        self.http
+            .get(url)
+            .bearer_auth(access_token)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|_| AppError::Authentication("Failed to get user info".to_string()))?
+            .json()
+            .await
+            .map_err(|_| AppError::Authentication("Failed to parse user info".to_string()))
+    }
+}
+
+/// The login providers this server offers, by name.
+pub struct OAuthProviderRegistry {
+    providers: HashMap<String, OAuthProvider>,
+}
+
+impl OAuthProviderRegistry {
+    /// Build every configured provider, running OIDC discovery where needed.
+    ///
+    /// A provider whose discovery fails is logged and left out, so one
+    /// unreachable issuer does not keep the server from starting.
+    pub async fn from_configs(configs: &[OAuthProviderConfig]) -> Self {
+        let http = reqwest::Client::builder()
+            .user_agent("task-manager")
+            .build()
+            .expect("HTTP client must build");
+
+        let mut providers = HashMap::new();
+        for config in configs {
+            let endpoints = match &config.endpoints {
+                EndpointSource::Fixed(endpoints) => Ok(endpoints.clone()),
+                EndpointSource::Discovery { issuer } => discover(&http, issuer).await,
+            };
+
+            match endpoints.and_then(|endpoints| OAuthProvider::new(config, endpoints, http.clone())) {
+                Ok(provider) => {
+                    tracing::info!("Login provider {} enabled", config.name);
+                    providers.insert(config.name.clone(), provider);
+                }
+                Err(e) => tracing::error!("Login provider {} disabled: {:?}", config.name, e),
+            }
+        }
+
+        Self { providers }
+    }
+
+    pub fn get(&self, name: &str) -> Result<&OAuthProvider> {
+        self.providers
+            .get(name)
+            .ok_or_else(|| AppError::NotFound("Login provider not found".to_string()))
+    }
+
+    /// Enabled providers, sorted by name.
+    pub fn list(&self) -> Vec<&OAuthProvider> {
+        let mut providers: Vec<&OAuthProvider> = self.providers.values().collect();
+        providers.sort_by(|a, b| a.name.cmp(&b.name));
+        providers
+    }
+}
+
+async fn discover(http: &reqwest::Client, issuer: &str) -> Result<ProviderEndpoints> {
+    #[derive(Deserialize)]
+    struct Discovery {
+        issuer: String,
+        #[serde(flatten)]
+        endpoints: ProviderEndpoints,
+    }
+
+    let issuer = issuer.trim_end_matches('/');
+    let discovery: Discovery = http
+        .get(format!("{}/.well-known/openid-configuration", issuer))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| AppError::BadRequest(format!("OIDC discovery failed: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid OIDC discovery document: {}", e)))?;
+
+    if discovery.issuer.trim_end_matches('/') != issuer {
+        return Err(AppError::BadRequest(format!(
+            "OIDC discovery issuer {} does not match {}",
+            discovery.issuer, issuer
+        )));
+    }
+
+    Ok(discovery.endpoints)
+}
+
+/// Whether `candidate` may be used as a post-login redirect.
+///
+/// An entry of the allow-list matches URLs with the same scheme, host and
+/// port whose path is the entry's path or lies below it.
+pub fn is_allowed_redirect(candidate: &str, allowed: &[String]) -> bool {
+    let Ok(candidate) = Url::parse(candidate) else {
+        return false;
+    };
+
+    allowed.iter().filter_map(|entry| Url::parse(entry).ok()).any(|entry| {
+        let prefix = entry.path().trim_end_matches('/');
+        candidate.scheme() == entry.scheme()
+            && candidate.host_str() == entry.host_str()
+            && candidate.port_or_known_default() == entry.port_or_known_default()
+            && (candidate.path() == prefix
+                || candidate.path().strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/')))
+    })
+}
+
+/// `url` with `params` added to its query string.
+pub fn with_query(url: &str, params: &[(&str, &str)]) -> Result<String> {
+    let mut url = Url::parse(url).map_err(|_| AppError::InternalError)?;
+    url.query_pairs_mut().extend_pairs(params);
+    Ok(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::{get, post}, Json, Router};
+
+    /// Minimal OIDC provider: discovery, token and userinfo endpoints.
+    async fn start_mock_oidc_provider() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+        let discovery = serde_json::json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{}/authorize", issuer),
+            "token_endpoint": format!("{}/token", issuer),
+            "userinfo_endpoint": format!("{}/userinfo", issuer),
+        });
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(move || async move { Json(discovery) }))
+            .route(
+                "/token",
+                post(|| async {
+                    Json(serde_json::json!({
+                        "access_token": "mock-access-token",
+                        "token_type": "Bearer",
+                        "expires_in": 3600,
+                    }))
+                }),
+            )
+            .route(
+                "/userinfo",
+                get(|| async {
+                    Json(serde_json::json!({
+                        "sub": "mock-user-1",
+                        "email": "alice@example.com",
+                        "email_verified": true,
+                        "name": "Alice",
+                        "preferred_username": "alice",
+                    }))
+                }),
+            );
+
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        issuer
+    }
+
+    fn oidc_config(name: &str, issuer: &str) -> OAuthProviderConfig {
+        OAuthProviderConfig {
+            name: name.to_string(),
+            display_name: name.to_string(),
+            kind: ProviderKind::Oidc,
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_uri: format!("http://localhost:3000/api/auth/oauth/{}/callback", name),
+            scopes: vec!["openid".to_string(), "email".to_string()],
+            endpoints: EndpointSource::Discovery { issuer: issuer.to_string() },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_discovers_and_logs_in_against_mock_provider() {
+        let issuer = start_mock_oidc_provider().await;
+        let registry = OAuthProviderRegistry::from_configs(&[oidc_config("mock", &issuer)]).await;
+        let provider = registry.get("mock").unwrap();
+
+        let (challenge, verifier) = PkceCodeChallenge::new_random_sha256();
+        let url = provider.authorize_url("abc".to_string(), challenge);
+        assert!(url.starts_with(&format!("{}/authorize?", issuer)));
+        assert!(url.contains("state=abc"));
+        assert!(url.contains("client_id=client"));
+        assert!(url.contains("code_challenge_method=S256"));
+
+        let profile = provider.exchange_code("code", verifier.secret()).await.unwrap();
+        assert_eq!(profile.subject, "mock-user-1");
+        assert_eq!(profile.email.as_deref(), Some("alice@example.com"));
+        assert!(profile.email_verified);
+        assert_eq!(profile.username.as_deref(), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn test_skips_provider_whose_discovery_fails() {
+        let registry = OAuthProviderRegistry::from_configs(&[oidc_config("broken", "http://127.0.0.1:9")]).await;
+        assert!(registry.get("broken").is_err());
+        assert!(registry.list().is_empty());
+    }
+
+    #[test]
+    fn test_redirect_allow_list() {
+        let allowed = vec!["https://app.example.com/auth".to_string(), "http://localhost:5173".to_string()];
+
+        assert!(is_allowed_redirect("https://app.example.com/auth", &allowed));
+        assert!(is_allowed_redirect("https://app.example.com/auth/done?x=1", &allowed));
+        assert!(is_allowed_redirect("http://localhost:5173/anything", &allowed));
+        assert!(!is_allowed_redirect("https://app.example.com/authx", &allowed));
+        assert!(!is_allowed_redirect("https://app.example.com.evil.com/auth", &allowed));
+        assert!(!is_allowed_redirect("http://app.example.com/auth", &allowed));
+        assert!(!is_allowed_redirect("http://localhost:8080/", &allowed));
+        assert!(!is_allowed_redirect("/relative", &allowed));
+    }
 }
@@ -1,11 +1,68 @@
 use crate::error::{AppError, Result};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Algorithm, Argon2, Params, Version,
+};
+
+/// Argon2id with the OWASP recommended parameters (19 MiB, 2 passes).
+fn argon2() -> Argon2<'static> {
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, Params::default())
+}
 
 pub fn hash_password(password: &str) -> Result<String> {
-    bcrypt::hash(password, bcrypt::DEFAULT_COST)
+    let salt = SaltString::generate(&mut OsRng);
+    argon2()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
         .map_err(|_| AppError::InternalError)
 }
 
+/// Check `password` against an Argon2 hash, or a bcrypt hash from before the switch.
 pub fn verify_password(password: &str, hash: &str) -> Result<bool> {
+    if hash.starts_with("$argon2") {
+        let parsed = PasswordHash::new(hash).map_err(|_| AppError::Authentication("Invalid password".to_string()))?;
+        return Ok(argon2().verify_password(password.as_bytes(), &parsed).is_ok());
+    }
+
     bcrypt::verify(password, hash)
         .map_err(|_| AppError::Authentication("Invalid password".to_string()))
 }
+
+/// Whether `hash` should be replaced on the next successful login: it is
+/// bcrypt, or Argon2 with other than the current algorithm and parameters.
+pub fn needs_rehash(hash: &str) -> bool {
+    let Ok(parsed) = PasswordHash::new(hash) else {
+        return true;
+    };
+
+    let current = Params::default();
+    parsed.algorithm != Algorithm::Argon2id.ident()
+        || parsed.version != Some(Version::V0x13.into())
+        || !Params::try_from(&parsed).is_ok_and(|params| {
+            (params.m_cost(), params.t_cost(), params.p_cost()) == (current.m_cost(), current.t_cost(), current.p_cost())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_argon2id_round_trip() {
+        let hash = hash_password("correct horse battery staple").unwrap();
+
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify_password("correct horse battery staple", &hash).unwrap());
+        assert!(!verify_password("Tr0ub4dor&3", &hash).unwrap());
+        assert!(!needs_rehash(&hash));
+    }
+
+    #[test]
+    fn test_bcrypt_hashes_still_verify_and_get_upgraded() {
+        let legacy = bcrypt::hash("hunter22", 4).unwrap();
+
+        assert!(verify_password("hunter22", &legacy).unwrap());
+        assert!(!verify_password("hunter23", &legacy).unwrap());
+        assert!(needs_rehash(&legacy));
+    }
+}
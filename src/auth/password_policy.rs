@@ -0,0 +1,148 @@
+use std::{collections::HashSet, sync::Arc};
+
+use crate::error::{AppError, Result};
+
+/// Longest accepted password, to bound hashing work.
+pub const MAX_PASSWORD_LENGTH: usize = 128;
+
+const COMMON_PASSWORDS: &str = include_str!("common_passwords.txt");
+
+/// Rules for new passwords, set by the `PASSWORD_*` variables.
+///
+/// Existing passwords are not re-checked; the policy applies whenever a
+/// password is chosen (registration, reset and change).
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_lowercase: bool,
+    pub require_uppercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    /// Lowercased passwords that are too common or known from breaches
+    blocklist: Arc<HashSet<String>>,
+}
+
+fn env_flag(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|value| value == "true" || value == "1")
+}
+
+fn parse_blocklist(contents: &str) -> impl Iterator<Item = String> + '_ {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_lowercase)
+}
+
+impl PasswordPolicy {
+    pub fn new(min_length: usize, extra_blocklist: Option<&str>) -> Self {
+        let blocklist = parse_blocklist(COMMON_PASSWORDS)
+            .chain(extra_blocklist.into_iter().flat_map(parse_blocklist))
+            .collect();
+
+        Self {
+            min_length,
+            require_lowercase: false,
+            require_uppercase: false,
+            require_digit: false,
+            require_symbol: false,
+            blocklist: Arc::new(blocklist),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let min_length = std::env::var("PASSWORD_MIN_LENGTH")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .ok()
+            .filter(|length| (1..=MAX_PASSWORD_LENGTH).contains(length))
+            .expect("PASSWORD_MIN_LENGTH must be a number between 1 and 128");
+
+        // e.g. a list of breached passwords, one per line
+        let extra_blocklist = std::env::var("PASSWORD_BLOCKLIST_FILE").ok().map(|path| {
+            std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("PASSWORD_BLOCKLIST_FILE {} is unreadable: {}", path, e))
+        });
+
+        Self {
+            require_lowercase: env_flag("PASSWORD_REQUIRE_LOWERCASE"),
+            require_uppercase: env_flag("PASSWORD_REQUIRE_UPPERCASE"),
+            require_digit: env_flag("PASSWORD_REQUIRE_DIGIT"),
+            require_symbol: env_flag("PASSWORD_REQUIRE_SYMBOL"),
+            ..Self::new(min_length, extra_blocklist.as_deref())
+        }
+    }
+
+    /// Check a new password; `personal` holds the user's name and email,
+    /// which the password must not contain.
+    pub fn check(&self, password: &str, personal: &[&str]) -> Result<()> {
+        let mut problems = Vec::new();
+        let length = password.chars().count();
+
+        if length < self.min_length {
+            problems.push(format!("Password must be at least {} characters long", self.min_length));
+        }
+        if length > MAX_PASSWORD_LENGTH {
+            problems.push(format!("Password must be at most {} characters long", MAX_PASSWORD_LENGTH));
+        }
+        if self.require_lowercase && !password.chars().any(char::is_lowercase) {
+            problems.push("Password must contain a lowercase letter".to_string());
+        }
+        if self.require_uppercase && !password.chars().any(char::is_uppercase) {
+            problems.push("Password must contain an uppercase letter".to_string());
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            problems.push("Password must contain a digit".to_string());
+        }
+        if self.require_symbol && password.chars().all(char::is_alphanumeric) {
+            problems.push("Password must contain a symbol".to_string());
+        }
+
+        let lowercase = password.to_lowercase();
+        if self.blocklist.contains(&lowercase) {
+            problems.push("Password is too common or has appeared in a data breach".to_string());
+        }
+
+        let contains_personal = personal
+            .iter()
+            .map(|value| value.split('@').next().unwrap_or(value).to_lowercase())
+            .any(|value| value.chars().count() >= 3 && lowercase.contains(&value));
+        if contains_personal {
+            problems.push("Password must not contain your username or email".to_string());
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(problems.join("; ")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_short_common_and_personal_passwords() {
+        let policy = PasswordPolicy::new(10, Some("correcthorse2024\n"));
+
+        assert!(policy.check("Password123", &[]).is_err());
+        assert!(policy.check("short", &[]).is_err());
+        assert!(policy.check("CorrectHorse2024", &[]).is_err());
+        assert!(policy.check("ada-lovelace-1815", &["ada", "ada.lovelace@example.com"]).is_err());
+        assert!(policy.check("violet staple orbit", &["ada", "ada@example.com"]).is_ok());
+    }
+
+    #[test]
+    fn test_optional_character_classes() {
+        let policy = PasswordPolicy {
+            require_uppercase: true,
+            require_digit: true,
+            require_symbol: true,
+            ..PasswordPolicy::new(8, None)
+        };
+
+        assert!(policy.check("violet staple orbit", &[]).is_err());
+        assert!(policy.check("Violet staple orbit 7", &[]).is_ok());
+    }
+}
@@ -0,0 +1,28 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Random, URL-safe secret for one-time links such as password resets.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// SHA-256 of a token; only this digest is ever stored.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_are_unique_and_hashed_deterministically() {
+        let token = generate_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, generate_token());
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_ne!(hash_token(&token), token);
+    }
+}
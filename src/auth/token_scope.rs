@@ -0,0 +1,79 @@
+use axum::http::Method;
+
+/// What a personal access token may be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenScope {
+    TasksRead,
+    TasksWrite,
+    MessagesRead,
+    MessagesWrite,
+}
+
+impl TokenScope {
+    pub const ALL: [TokenScope; 4] = [
+        TokenScope::TasksRead,
+        TokenScope::TasksWrite,
+        TokenScope::MessagesRead,
+        TokenScope::MessagesWrite,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|scope| scope.as_str() == value)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TasksRead => "tasks:read",
+            Self::TasksWrite => "tasks:write",
+            Self::MessagesRead => "messages:read",
+            Self::MessagesWrite => "messages:write",
+        }
+    }
+
+    /// Scope a token needs to call `method path` (the full `/api/...` path).
+    ///
+    /// `None` means the endpoint is closed to personal access tokens, which
+    /// covers account, token and admin management.
+    pub fn required_for(method: &Method, path: &str) -> Option<Self> {
+        let read = method == Method::GET || method == Method::HEAD;
+
+        if path == "/api/tasks" || path.starts_with("/api/tasks/") {
+            Some(if read { Self::TasksRead } else { Self::TasksWrite })
+        } else if path == "/api/messages" || path.starts_with("/api/messages/") {
+            Some(if read { Self::MessagesRead } else { Self::MessagesWrite })
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for TokenScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_round_trips() {
+        for scope in TokenScope::ALL {
+            assert_eq!(TokenScope::parse(scope.as_str()), Some(scope));
+        }
+        assert_eq!(TokenScope::parse("admin"), None);
+    }
+
+    #[test]
+    fn test_required_scope_follows_method_and_path() {
+        let task = "/api/tasks/0b5e7c9e-2f0a-4a4e-9d59-0a9c1f1f1f1f";
+
+        assert_eq!(TokenScope::required_for(&Method::GET, "/api/tasks"), Some(TokenScope::TasksRead));
+        assert_eq!(TokenScope::required_for(&Method::PATCH, &format!("{}/status", task)), Some(TokenScope::TasksWrite));
+        assert_eq!(TokenScope::required_for(&Method::POST, "/api/messages"), Some(TokenScope::MessagesWrite));
+        assert_eq!(TokenScope::required_for(&Method::GET, "/api/users/me"), None);
+        assert_eq!(TokenScope::required_for(&Method::POST, "/api/auth/tokens"), None);
+        assert_eq!(TokenScope::required_for(&Method::GET, "/api/tasksearch"), None);
+    }
+}
@@ -0,0 +1,106 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+
+/// Seconds per TOTP time step (RFC 6238 default).
+const STEP_SECONDS: i64 = 30;
+const DIGITS: u32 = 6;
+/// Steps before and after the current one that are still accepted, for clock drift.
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+
+/// New random 160-bit secret, base32 encoded as authenticator apps expect.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 20];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    BASE32_NOPAD.encode(&bytes)
+}
+
+/// `otpauth://` URI to render as a QR code for authenticator apps.
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={DIGITS}&period={STEP_SECONDS}",
+        issuer = urlencoding::encode(issuer),
+        account = urlencoding::encode(account),
+        secret = secret,
+    )
+}
+
+fn code_at_step(secret: &[u8], step: i64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // Dynamic truncation, RFC 4226 section 5.3
+    let offset = (digest[19] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]])
+        & 0x7fff_ffff;
+    binary % 10u32.pow(DIGITS)
+}
+
+/// Check `code` against the time steps around `unix_time`.
+///
+/// Returns the matching step so callers can refuse steps at or before the
+/// last one used, which stops a code from being replayed.
+pub fn verify_code(secret: &str, code: &str, unix_time: i64) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let code: u32 = code.parse().ok()?;
+    let secret = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+
+    let current = unix_time.div_euclid(STEP_SECONDS);
+    (current - ALLOWED_DRIFT_STEPS..=current + ALLOWED_DRIFT_STEPS).find(|step| code_at_step(&secret, *step) == code)
+}
+
+/// One-time recovery codes such as `3f9a1-0c2d7`.
+pub fn generate_recovery_codes(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let mut bytes = [0u8; 5];
+            rand::thread_rng().fill_bytes(&mut bytes);
+            let code = hex::encode(bytes);
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+/// Recovery codes are compared without the dash and case-insensitively.
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.trim().replace('-', "").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B secret "12345678901234567890"
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn test_matches_rfc_6238_vectors() {
+        assert_eq!(verify_code(RFC_SECRET, "287082", 59), Some(1));
+        assert_eq!(verify_code(RFC_SECRET, "081804", 1111111109), Some(37037036));
+        assert_eq!(verify_code(RFC_SECRET, "005924", 1234567890), Some(41152263));
+    }
+
+    #[test]
+    fn test_rejects_wrong_or_malformed_codes() {
+        assert_eq!(verify_code(RFC_SECRET, "287083", 59), None);
+        assert_eq!(verify_code(RFC_SECRET, "28708", 59), None);
+        assert_eq!(verify_code(RFC_SECRET, "abcdef", 59), None);
+        // Two steps later is outside the allowed drift
+        assert_eq!(verify_code(RFC_SECRET, "287082", 59 + 2 * STEP_SECONDS), None);
+    }
+
+    #[test]
+    fn test_otpauth_uri_and_recovery_codes() {
+        let uri = otpauth_uri("Task Manager", "ada@example.com", RFC_SECRET);
+        assert!(uri.starts_with("otpauth://totp/Task%20Manager:ada%40example.com?secret="));
+
+        let codes = generate_recovery_codes(10);
+        assert_eq!(codes.len(), 10);
+        assert_eq!(normalize_recovery_code(&codes[0].to_uppercase()).len(), 10);
+    }
+}
@@ -0,0 +1,82 @@
+use axum::http::Method;
+
+/// What users with an unverified email address may do, set by `UNVERIFIED_USER_POLICY`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnverifiedUserPolicy {
+    /// No restrictions
+    Full,
+    /// Everything except sharing tasks and messaging (`restricted`, the default)
+    Restricted,
+    /// Reads only, plus editing the own profile to fix the address
+    ReadOnly,
+}
+
+impl UnverifiedUserPolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "full" => Some(Self::Full),
+            "restricted" => Some(Self::Restricted),
+            "read_only" => Some(Self::ReadOnly),
+            _ => None,
+        }
+    }
+
+    /// Whether an unverified user may call `method path` (the full `/api/...` path).
+    pub fn allows(&self, method: &Method, path: &str) -> bool {
+        // Resending the verification mail must always work.
+        if path == "/api/auth/verify-email/resend" {
+            return true;
+        }
+
+        match self {
+            Self::Full => true,
+            Self::Restricted => !is_sharing_or_messaging(method, path),
+            Self::ReadOnly => {
+                (method == Method::GET && path != "/api/ws") || (method == Method::PUT && path == "/api/users/me")
+            }
+        }
+    }
+}
+
+fn is_sharing_or_messaging(method: &Method, path: &str) -> bool {
+    let sharing = method == Method::POST
+        && path.starts_with("/api/tasks/")
+        && (path.ends_with("/share")
+            || path.ends_with("/invitations")
+            || path.ends_with("/join-links")
+            || path.ends_with("/public-links"));
+    let messaging = path == "/api/ws" || (method != Method::GET && path.starts_with("/api/messages"));
+    sharing || messaging
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restricted_blocks_sharing_and_messaging() {
+        let policy = UnverifiedUserPolicy::Restricted;
+        let task_id = "/api/tasks/0b5e7c9e-2f0a-4a4e-9d59-0a9c1f1f1f1f";
+
+        assert!(policy.allows(&Method::POST, "/api/tasks"));
+        assert!(policy.allows(&Method::GET, "/api/messages/conversations"));
+        assert!(!policy.allows(&Method::POST, &format!("{}/share", task_id)));
+        assert!(!policy.allows(&Method::POST, &format!("{}/invitations", task_id)));
+        assert!(!policy.allows(&Method::POST, &format!("{}/join-links", task_id)));
+        assert!(!policy.allows(&Method::POST, &format!("{}/public-links", task_id)));
+        assert!(policy.allows(&Method::POST, "/api/invitations/join"));
+        assert!(!policy.allows(&Method::POST, "/api/messages"));
+        assert!(!policy.allows(&Method::GET, "/api/ws"));
+    }
+
+    #[test]
+    fn test_read_only_allows_reads_and_profile() {
+        let policy = UnverifiedUserPolicy::ReadOnly;
+
+        assert!(policy.allows(&Method::GET, "/api/tasks"));
+        assert!(policy.allows(&Method::PUT, "/api/users/me"));
+        assert!(policy.allows(&Method::POST, "/api/auth/verify-email/resend"));
+        assert!(!policy.allows(&Method::POST, "/api/tasks"));
+        assert!(!policy.allows(&Method::GET, "/api/ws"));
+    }
+}
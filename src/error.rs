@@ -31,6 +31,9 @@ pub enum AppError {
 
     #[error("Bad request: {0}")]
     BadRequest(String),
+
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
 }
 
 impl From<validator::ValidationErrors> for AppError {
@@ -55,6 +58,7 @@ AppError::Unauthorized(ref msg) => (StatusCode::UNAUTHORIZED, msg.as_str()),
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
             }
             AppError::BadRequest(ref msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
+            AppError::TooManyRequests(ref msg) => (StatusCode::TOO_MANY_REQUESTS, msg.as_str()),
         };
 
         let body = Json(json!({
@@ -0,0 +1,25 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpsertEscalationPolicyRequest {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Notify the task owner as soon as the task is overdue
+    #[serde(default = "default_true")]
+    pub notify_owner: bool,
+    /// Raise the priority one level once overdue for this many hours
+    #[validate(range(min = 1, max = 8760))]
+    pub bump_priority_after_hours: Option<i32>,
+    /// Escalate once overdue for this many hours
+    #[validate(range(min = 1, max = 8760))]
+    pub escalate_after_hours: Option<i32>,
+    /// Who to escalate to; every task member when omitted
+    pub escalation_user_id: Option<Uuid>,
+}
+
+fn default_true() -> bool {
+    true
+}
@@ -0,0 +1,165 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::{AppError, Result},
+    middleware::CurrentOrganization,
+    state::AppState,
+};
+use super::{
+    escalation_dto::UpsertEscalationPolicyRequest,
+    escalation_models::EscalationPolicy,
+};
+
+/// Get the current user's default escalation policy
+#[utoipa::path(
+    get,
+    path = "/api/users/me/escalation-policy",
+    responses(
+        (status = 200, description = "Default escalation policy", body = EscalationPolicy),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "No policy set")
+    ),
+    tag = "escalations",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_default_policy(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<EscalationPolicy>> {
+    let policy = state.escalation_service.get_default_policy(user_id).await?;
+    Ok(Json(policy))
+}
+
+/// Create or replace the current user's default escalation policy
+#[utoipa::path(
+    put,
+    path = "/api/users/me/escalation-policy",
+    request_body = UpsertEscalationPolicyRequest,
+    responses(
+        (status = 200, description = "Default escalation policy saved", body = EscalationPolicy),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "escalations",
+    security(("bearer_auth" = []))
+)]
+pub async fn set_default_policy(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<UpsertEscalationPolicyRequest>,
+) -> Result<Json<EscalationPolicy>> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let policy = state.escalation_service.set_default_policy(user_id, payload).await?;
+    Ok(Json(policy))
+}
+
+/// Remove the current user's default escalation policy
+#[utoipa::path(
+    delete,
+    path = "/api/users/me/escalation-policy",
+    responses(
+        (status = 204, description = "Default escalation policy removed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "No policy set")
+    ),
+    tag = "escalations",
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_default_policy(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<StatusCode> {
+    state.escalation_service.delete_default_policy(user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get a task's escalation policy override
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{task_id}/escalation-policy",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 200, description = "Task escalation policy", body = EscalationPolicy),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Access denied"),
+        (status = 404, description = "No policy set")
+    ),
+    tag = "escalations",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_task_policy(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path(task_id): Path<Uuid>,
+) -> Result<Json<EscalationPolicy>> {
+    let policy = state.escalation_service.get_task_policy(organization_id, task_id, user_id).await?;
+    Ok(Json(policy))
+}
+
+/// Create or replace a task's escalation policy override
+#[utoipa::path(
+    put,
+    path = "/api/tasks/{task_id}/escalation-policy",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID")
+    ),
+    request_body = UpsertEscalationPolicyRequest,
+    responses(
+        (status = 200, description = "Task escalation policy saved", body = EscalationPolicy),
+        (status = 400, description = "Validation error or escalation user is not a member"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only task owner")
+    ),
+    tag = "escalations",
+    security(("bearer_auth" = []))
+)]
+pub async fn set_task_policy(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path(task_id): Path<Uuid>,
+    Json(payload): Json<UpsertEscalationPolicyRequest>,
+) -> Result<Json<EscalationPolicy>> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let policy = state.escalation_service.set_task_policy(organization_id, task_id, user_id, payload).await?;
+    Ok(Json(policy))
+}
+
+/// Remove a task's escalation policy override
+#[utoipa::path(
+    delete,
+    path = "/api/tasks/{task_id}/escalation-policy",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 204, description = "Task escalation policy removed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only task owner"),
+        (status = 404, description = "No policy set")
+    ),
+    tag = "escalations",
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_task_policy(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path(task_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    state.escalation_service.delete_task_policy(organization_id, task_id, user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct EscalationPolicy {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Set for a per-task override, `None` for the user's default policy
+    pub task_id: Option<Uuid>,
+    pub enabled: bool,
+    pub notify_owner: bool,
+    pub bump_priority_after_hours: Option<i32>,
+    pub escalate_after_hours: Option<i32>,
+    pub escalation_user_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An overdue, unfinished task together with the policy that applies to it.
+#[derive(Debug, Clone, FromRow)]
+pub struct OverdueTask {
+    pub task_id: Uuid,
+    pub organization_id: Uuid,
+    pub owner_id: Uuid,
+    pub title: String,
+    pub priority: String,
+    pub due_date: DateTime<Utc>,
+    pub policy_id: Uuid,
+    pub notify_owner: bool,
+    pub bump_priority_after_hours: Option<i32>,
+    pub escalate_after_hours: Option<i32>,
+    pub escalation_user_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscalationStep {
+    /// Tell the owner the task is overdue
+    Overdue,
+    /// Raise the task's priority by one level
+    PriorityBumped,
+    /// Tell the escalation user, or every member
+    Escalated,
+}
+
+impl std::fmt::Display for EscalationStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EscalationStep::Overdue => write!(f, "overdue"),
+            EscalationStep::PriorityBumped => write!(f, "priority_bumped"),
+            EscalationStep::Escalated => write!(f, "escalated"),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use super::{
+    escalation_dto::UpsertEscalationPolicyRequest,
+    escalation_models::{EscalationPolicy, EscalationStep, OverdueTask},
+};
+
+#[derive(Clone)]
+pub struct EscalationRepository {
+    pool: PgPool,
+}
+
+impl EscalationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_default_policy(&self, user_id: Uuid) -> Result<Option<EscalationPolicy>> {
+        let policy = sqlx::query_as::<_, EscalationPolicy>(
+            "SELECT * FROM escalation_policies WHERE user_id = $1 AND task_id IS NULL"
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(policy)
+    }
+
+    pub async fn find_task_policy(&self, task_id: Uuid) -> Result<Option<EscalationPolicy>> {
+        let policy = sqlx::query_as::<_, EscalationPolicy>(
+            "SELECT * FROM escalation_policies WHERE task_id = $1"
+        )
+        .bind(task_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(policy)
+    }
+
+    pub async fn upsert_default_policy(
+        &self,
+        user_id: Uuid,
+        policy: &UpsertEscalationPolicyRequest,
+    ) -> Result<EscalationPolicy> {
+        let policy = sqlx::query_as::<_, EscalationPolicy>(
+            "INSERT INTO escalation_policies
+                (user_id, enabled, notify_owner, bump_priority_after_hours, escalate_after_hours, escalation_user_id)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (user_id) WHERE task_id IS NULL DO UPDATE SET
+                enabled = EXCLUDED.enabled,
+                notify_owner = EXCLUDED.notify_owner,
+                bump_priority_after_hours = EXCLUDED.bump_priority_after_hours,
+                escalate_after_hours = EXCLUDED.escalate_after_hours,
+                escalation_user_id = EXCLUDED.escalation_user_id
+             RETURNING *"
+        )
+        .bind(user_id)
+        .bind(policy.enabled)
+        .bind(policy.notify_owner)
+        .bind(policy.bump_priority_after_hours)
+        .bind(policy.escalate_after_hours)
+        .bind(policy.escalation_user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(policy)
+    }
+
+    pub async fn upsert_task_policy(
+        &self,
+        task_id: Uuid,
+        owner_id: Uuid,
+        policy: &UpsertEscalationPolicyRequest,
+    ) -> Result<EscalationPolicy> {
+        let policy = sqlx::query_as::<_, EscalationPolicy>(
+            "INSERT INTO escalation_policies
+                (user_id, task_id, enabled, notify_owner, bump_priority_after_hours, escalate_after_hours, escalation_user_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (task_id) WHERE task_id IS NOT NULL DO UPDATE SET
+                enabled = EXCLUDED.enabled,
+                notify_owner = EXCLUDED.notify_owner,
+                bump_priority_after_hours = EXCLUDED.bump_priority_after_hours,
+                escalate_after_hours = EXCLUDED.escalate_after_hours,
+                escalation_user_id = EXCLUDED.escalation_user_id
+             RETURNING *"
+        )
+        .bind(owner_id)
+        .bind(task_id)
+        .bind(policy.enabled)
+        .bind(policy.notify_owner)
+        .bind(policy.bump_priority_after_hours)
+        .bind(policy.escalate_after_hours)
+        .bind(policy.escalation_user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(policy)
+    }
+
+    pub async fn delete_default_policy(&self, user_id: Uuid) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM escalation_policies WHERE user_id = $1 AND task_id IS NULL")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_task_policy(&self, task_id: Uuid) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM escalation_policies WHERE task_id = $1")
+            .bind(task_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Unfinished tasks past their due date, each paired with its task policy
+    /// if it has one, otherwise with the owner's default policy.
+    pub async fn find_overdue_tasks(&self, now: DateTime<Utc>) -> Result<Vec<OverdueTask>> {
+        let tasks = sqlx::query_as::<_, OverdueTask>(
+            "SELECT t.id AS task_id, t.organization_id, t.user_id AS owner_id, t.title, t.priority, t.due_date,
+                    p.id AS policy_id, p.notify_owner, p.bump_priority_after_hours,
+                    p.escalate_after_hours, p.escalation_user_id
+             FROM tasks t
+             JOIN LATERAL (
+                 SELECT * FROM escalation_policies ep
+                 WHERE ep.task_id = t.id OR (ep.task_id IS NULL AND ep.user_id = t.user_id)
+                 ORDER BY ep.task_id IS NULL
+                 LIMIT 1
+             ) p ON true
+             WHERE p.enabled
+               AND t.due_date IS NOT NULL
+               AND t.due_date < $1
+               AND t.status NOT IN ('Completed', 'Archived')"
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tasks)
+    }
+
+    /// Record an escalation step for the task's current due date.
+    ///
+    /// Returns `false` when the step was already recorded, so each step fires
+    /// once per due date; moving the due date re-arms the policy.
+    pub async fn record_step(&self, task: &OverdueTask, step: EscalationStep) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO task_activity (task_id, user_id, action, details)
+             SELECT $1, NULL, 'escalated',
+                    jsonb_build_object('step', $2::text, 'policy_id', $3::uuid, 'due_date', $4::timestamptz)
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM task_activity
+                 WHERE task_id = $1
+                   AND action = 'escalated'
+                   AND details->>'step' = $2::text
+                   AND (details->>'due_date')::timestamptz = $4::timestamptz
+             )"
+        )
+        .bind(task.task_id)
+        .bind(step.to_string())
+        .bind(task.policy_id)
+        .bind(task.due_date)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,258 @@
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, Result},
+    task::{task_models::Task, task_repository::TaskRepository},
+};
+use super::{
+    escalation_dto::UpsertEscalationPolicyRequest,
+    escalation_models::{EscalationPolicy, EscalationStep, OverdueTask},
+    escalation_repository::EscalationRepository,
+};
+
+/// A step that fired during an evaluation pass, for the caller to announce.
+#[derive(Debug, Clone)]
+pub struct EscalationOutcome {
+    pub task: OverdueTask,
+    pub step: EscalationStep,
+    pub recipients: Vec<Uuid>,
+    /// The task after a priority bump
+    pub updated_task: Option<Task>,
+}
+
+#[derive(Clone)]
+pub struct EscalationService {
+    repo: EscalationRepository,
+    task_repo: TaskRepository,
+}
+
+impl EscalationService {
+    pub fn new(repo: EscalationRepository, task_repo: TaskRepository) -> Self {
+        Self { repo, task_repo }
+    }
+
+    pub async fn get_default_policy(&self, user_id: Uuid) -> Result<EscalationPolicy> {
+        self.repo
+            .find_default_policy(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Escalation policy not found".into()))
+    }
+
+    pub async fn set_default_policy(
+        &self,
+        user_id: Uuid,
+        payload: UpsertEscalationPolicyRequest,
+    ) -> Result<EscalationPolicy> {
+        Self::check_policy(&payload)?;
+        self.repo.upsert_default_policy(user_id, &payload).await
+    }
+
+    pub async fn delete_default_policy(&self, user_id: Uuid) -> Result<()> {
+        if self.repo.delete_default_policy(user_id).await? == 0 {
+            return Err(AppError::NotFound("Escalation policy not found".into()));
+        }
+        Ok(())
+    }
+
+    pub async fn get_task_policy(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        requesting_user: Uuid,
+    ) -> Result<EscalationPolicy> {
+        if !self.task_repo.has_task_access(organization_id, task_id, requesting_user).await? {
+            return Err(AppError::Forbidden("Access denied".to_string()));
+        }
+
+        self.repo
+            .find_task_policy(task_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Escalation policy not found".into()))
+    }
+
+    pub async fn set_task_policy(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        requesting_user: Uuid,
+        payload: UpsertEscalationPolicyRequest,
+    ) -> Result<EscalationPolicy> {
+        Self::check_policy(&payload)?;
+
+        if !self.task_repo.is_task_owner(organization_id, task_id, requesting_user).await? {
+            return Err(AppError::Forbidden("Only task owner can set an escalation policy".to_string()));
+        }
+        if let Some(escalation_user_id) = payload.escalation_user_id {
+            if !self.task_repo.has_task_access(organization_id, task_id, escalation_user_id).await? {
+                return Err(AppError::BadRequest("Escalation user is not a task member".to_string()));
+            }
+        }
+
+        let policy = self.repo.upsert_task_policy(task_id, requesting_user, &payload).await?;
+
+        let _ = self.task_repo.log_task_activity(
+            organization_id,
+            task_id,
+            requesting_user,
+            "escalation_policy_set",
+            Some(serde_json::json!({
+                "policy_id": policy.id,
+                "enabled": policy.enabled,
+                "bump_priority_after_hours": policy.bump_priority_after_hours,
+                "escalate_after_hours": policy.escalate_after_hours,
+                "escalation_user_id": policy.escalation_user_id,
+            })),
+        ).await;
+
+        Ok(policy)
+    }
+
+    pub async fn delete_task_policy(&self, organization_id: Uuid, task_id: Uuid, requesting_user: Uuid) -> Result<()> {
+        if !self.task_repo.is_task_owner(organization_id, task_id, requesting_user).await? {
+            return Err(AppError::Forbidden("Only task owner can remove an escalation policy".to_string()));
+        }
+        if self.repo.delete_task_policy(task_id).await? == 0 {
+            return Err(AppError::NotFound("Escalation policy not found".into()));
+        }
+        Ok(())
+    }
+
+    fn check_policy(payload: &UpsertEscalationPolicyRequest) -> Result<()> {
+        if let (Some(bump), Some(escalate)) = (payload.bump_priority_after_hours, payload.escalate_after_hours) {
+            if escalate < bump {
+                return Err(AppError::Validation(
+                    "escalate_after_hours must not be earlier than bump_priority_after_hours".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fire every escalation step that is due and has not fired yet.
+    pub async fn evaluate_overdue(&self, now: DateTime<Utc>) -> Result<Vec<EscalationOutcome>> {
+        let mut outcomes = Vec::new();
+
+        for task in self.repo.find_overdue_tasks(now).await? {
+            for step in due_steps(&task, now - task.due_date) {
+                if !self.repo.record_step(&task, step).await? {
+                    continue;
+                }
+
+                let (recipients, updated_task) = match step {
+                    EscalationStep::Overdue => (vec![task.owner_id], None),
+                    EscalationStep::PriorityBumped => {
+                        let updated = match next_priority(&task.priority) {
+                            Some(priority) => Some(
+                                self.task_repo
+                                    .update(task.organization_id, task.task_id, None, None, None, Some(priority), None)
+                                    .await?,
+                            ),
+                            None => None,
+                        };
+                        (vec![task.owner_id], updated)
+                    }
+                    EscalationStep::Escalated => (self.escalation_recipients(&task).await?, None),
+                };
+
+                outcomes.push(EscalationOutcome {
+                    task: task.clone(),
+                    step,
+                    recipients,
+                    updated_task,
+                });
+            }
+        }
+
+        Ok(outcomes)
+    }
+
+    /// The designated escalation user while they can still see the task,
+    /// otherwise the owner and every member.
+    async fn escalation_recipients(&self, task: &OverdueTask) -> Result<Vec<Uuid>> {
+        if let Some(escalation_user_id) = task.escalation_user_id {
+            if self.task_repo.has_task_access(task.organization_id, task.task_id, escalation_user_id).await? {
+                return Ok(vec![escalation_user_id]);
+            }
+        }
+
+        let mut recipients = vec![task.owner_id];
+        for member in self.task_repo.get_task_members(task.organization_id, task.task_id).await? {
+            if !recipients.contains(&member.user_id) {
+                recipients.push(member.user_id);
+            }
+        }
+        Ok(recipients)
+    }
+}
+
+/// Steps of `task`'s policy that apply after being overdue for `overdue_for`.
+pub fn due_steps(task: &OverdueTask, overdue_for: Duration) -> Vec<EscalationStep> {
+    let passed = |hours: Option<i32>| hours.is_some_and(|h| overdue_for >= Duration::hours(h as i64));
+
+    let mut steps = Vec::new();
+    if task.notify_owner {
+        steps.push(EscalationStep::Overdue);
+    }
+    if passed(task.bump_priority_after_hours) {
+        steps.push(EscalationStep::PriorityBumped);
+    }
+    if passed(task.escalate_after_hours) {
+        steps.push(EscalationStep::Escalated);
+    }
+    steps
+}
+
+/// One priority level up, or `None` if already at the top.
+pub fn next_priority(priority: &str) -> Option<&'static str> {
+    match priority {
+        "Low" => Some("Medium"),
+        "Medium" => Some("High"),
+        "High" => Some("Urgent"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overdue_task(bump: Option<i32>, escalate: Option<i32>) -> OverdueTask {
+        OverdueTask {
+            task_id: Uuid::new_v4(),
+            organization_id: Uuid::new_v4(),
+            owner_id: Uuid::new_v4(),
+            title: "Task".to_string(),
+            priority: "Low".to_string(),
+            due_date: Utc::now(),
+            policy_id: Uuid::new_v4(),
+            notify_owner: true,
+            bump_priority_after_hours: bump,
+            escalate_after_hours: escalate,
+            escalation_user_id: None,
+        }
+    }
+
+    #[test]
+    fn test_due_steps_follow_thresholds() {
+        let task = overdue_task(Some(4), Some(24));
+
+        assert_eq!(due_steps(&task, Duration::minutes(5)), vec![EscalationStep::Overdue]);
+        assert_eq!(
+            due_steps(&task, Duration::hours(4)),
+            vec![EscalationStep::Overdue, EscalationStep::PriorityBumped]
+        );
+        assert_eq!(
+            due_steps(&task, Duration::hours(30)),
+            vec![EscalationStep::Overdue, EscalationStep::PriorityBumped, EscalationStep::Escalated]
+        );
+        assert!(due_steps(&OverdueTask { notify_owner: false, ..overdue_task(None, None) }, Duration::hours(100)).is_empty());
+    }
+
+    #[test]
+    fn test_next_priority() {
+        assert_eq!(next_priority("Low"), Some("Medium"));
+        assert_eq!(next_priority("High"), Some("Urgent"));
+        assert_eq!(next_priority("Urgent"), None);
+    }
+}
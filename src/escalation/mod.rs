@@ -0,0 +1,6 @@
+// Declare submodules
+pub mod escalation_models;
+pub mod escalation_dto;
+pub mod escalation_repository;
+pub mod escalation_handlers;
+pub mod escalation_service;
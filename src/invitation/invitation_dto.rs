@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{organization::organization_models::OrgRole, task::task_models::TaskRole};
+use super::invitation_models::TaskJoinLink;
+
+/// Name exactly one of `username` or `email`.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateInvitationRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub username: Option<String>,
+    /// May belong to someone who has not signed up yet
+    #[validate(email)]
+    pub email: Option<String>,
+    /// `viewer` or `editor` (default)
+    pub role: Option<TaskRole>,
+}
+
+/// Name exactly one of `username` or `email`.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateOrganizationInvitationRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub username: Option<String>,
+    /// Whether or not it has an account yet; the reply is the same either way
+    #[validate(email)]
+    pub email: Option<String>,
+    /// `member` (default), `admin` or `owner`
+    pub role: Option<OrgRole>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateJoinLinkRequest {
+    /// `viewer` (default) or `editor`
+    pub role: Option<TaskRole>,
+    /// Defaults to 72 hours
+    #[validate(range(min = 1, max = 720))]
+    pub expires_in_hours: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedJoinLinkResponse {
+    #[serde(flatten)]
+    pub link: TaskJoinLink,
+    /// Shown only once
+    pub token: String,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct JoinTaskRequest {
+    #[validate(length(min = 1))]
+    pub token: String,
+}
@@ -0,0 +1,637 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    audit::audit_models::{AuditAction, AuditTarget, NewAuditEntry},
+    error::{AppError, Result},
+    middleware::{AuthUser, ClientInfo, CurrentOrganization},
+    organization::organization_models::OrganizationMembership,
+    state::AppState,
+    task::task_models::Task,
+    websocket::types::{TaskInvitationAcceptedPayload, TaskInvitationPayload, WsMessage},
+};
+use super::{
+    invitation_dto::{
+        CreateInvitationRequest, CreateJoinLinkRequest, CreateOrganizationInvitationRequest, JoinTaskRequest,
+    },
+    invitation_models::{
+        OrganizationInvitation, ReceivedInvitation, ReceivedOrganizationInvitation, TaskInvitation, TaskJoinLink,
+    },
+    invitation_service::SentInvitation,
+};
+
+/// Invite a user to a task by username or email address (owner only)
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{task_id}/invitations",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID")
+    ),
+    request_body = CreateInvitationRequest,
+    responses(
+        (status = 201, description = "Invitation sent", body = TaskInvitation),
+        (status = 400, description = "Validation error, not in the organization, already a member or already invited"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only task owner can invite, and only organization admins can invite outsiders"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn create_invitation(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path(task_id): Path<Uuid>,
+    Json(payload): Json<CreateInvitationRequest>,
+) -> Result<impl IntoResponse> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let sent = state.invitation_service.invite(organization_id, task_id, user_id, payload).await?;
+    notify_invitee(&state, &sent).await?;
+
+    Ok((StatusCode::CREATED, Json(sent.invitation)))
+}
+
+/// List a task's pending invitations (owner only)
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{task_id}/invitations",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 200, description = "Pending invitations", body = Vec<TaskInvitation>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only task owner can invite")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_task_invitations(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path(task_id): Path<Uuid>,
+) -> Result<Json<Vec<TaskInvitation>>> {
+    let invitations = state.invitation_service.list_task_invitations(organization_id, task_id, user_id).await?;
+    Ok(Json(invitations))
+}
+
+/// Withdraw a pending invitation (owner only)
+#[utoipa::path(
+    delete,
+    path = "/api/tasks/{task_id}/invitations/{invitation_id}",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ("invitation_id" = Uuid, Path, description = "Invitation ID")
+    ),
+    responses(
+        (status = 204, description = "Invitation revoked"),
+        (status = 400, description = "Invitation is no longer pending"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only task owner can invite"),
+        (status = 404, description = "Invitation not found")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_invitation(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path((task_id, invitation_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    state.invitation_service.revoke_invitation(organization_id, task_id, invitation_id, user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Create a shareable join link granting a role (owner only)
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{task_id}/join-links",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID")
+    ),
+    request_body = CreateJoinLinkRequest,
+    responses(
+        (status = 201, description = "Join link created; the token is only shown once", body = CreatedJoinLinkResponse),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only task owner can invite")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn create_join_link(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path(task_id): Path<Uuid>,
+    Json(payload): Json<CreateJoinLinkRequest>,
+) -> Result<impl IntoResponse> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let link = state.invitation_service.create_join_link(organization_id, task_id, user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(link)))
+}
+
+/// List a task's active join links (owner only)
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{task_id}/join-links",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 200, description = "Active join links", body = Vec<TaskJoinLink>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only task owner can invite")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_join_links(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path(task_id): Path<Uuid>,
+) -> Result<Json<Vec<TaskJoinLink>>> {
+    let links = state.invitation_service.list_join_links(organization_id, task_id, user_id).await?;
+    Ok(Json(links))
+}
+
+/// Revoke a join link (owner only)
+#[utoipa::path(
+    delete,
+    path = "/api/tasks/{task_id}/join-links/{link_id}",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ("link_id" = Uuid, Path, description = "Join link ID")
+    ),
+    responses(
+        (status = 204, description = "Join link revoked"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only task owner can invite"),
+        (status = 404, description = "Join link not found")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_join_link(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path((task_id, link_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    state.invitation_service.revoke_join_link(organization_id, task_id, link_id, user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List invitations waiting for the current user
+#[utoipa::path(
+    get,
+    path = "/api/invitations",
+    responses(
+        (status = 200, description = "Pending invitations", body = Vec<ReceivedInvitation>),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "invitations",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_my_invitations(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<Vec<ReceivedInvitation>>> {
+    let invitations = state.invitation_service.list_received(user_id).await?;
+    Ok(Json(invitations))
+}
+
+/// Accept an invitation and join the task
+#[utoipa::path(
+    post,
+    path = "/api/invitations/{invitation_id}/accept",
+    params(
+        ("invitation_id" = Uuid, Path, description = "Invitation ID")
+    ),
+    responses(
+        (status = 200, description = "Joined the task", body = Task),
+        (status = 400, description = "Invitation expired or no longer pending"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Email address not verified or no longer in the task's organization"),
+        (status = 404, description = "Invitation not found")
+    ),
+    tag = "invitations",
+    security(("bearer_auth" = []))
+)]
+pub async fn accept_invitation(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(invitation_id): Path<Uuid>,
+) -> Result<Json<Task>> {
+    let accepted = state.invitation_service.accept(invitation_id, user_id).await?;
+
+    let mut recipients = vec![accepted.task.user_id];
+    if let Some(inviter) = accepted.invitation.invited_by {
+        recipients.push(inviter);
+    }
+    notify_joined(
+        &state,
+        &accepted.task,
+        user_id,
+        Some(accepted.invitation.id),
+        &accepted.invitation.role,
+        recipients,
+    )
+    .await?;
+
+    Ok(Json(accepted.task))
+}
+
+/// Decline an invitation
+#[utoipa::path(
+    post,
+    path = "/api/invitations/{invitation_id}/decline",
+    params(
+        ("invitation_id" = Uuid, Path, description = "Invitation ID")
+    ),
+    responses(
+        (status = 204, description = "Invitation declined"),
+        (status = 400, description = "Invitation expired or no longer pending"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Invitation not found")
+    ),
+    tag = "invitations",
+    security(("bearer_auth" = []))
+)]
+pub async fn decline_invitation(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(invitation_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    state.invitation_service.decline(invitation_id, user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Join a task through a join link
+#[utoipa::path(
+    post,
+    path = "/api/invitations/join",
+    request_body = JoinTaskRequest,
+    responses(
+        (status = 200, description = "Joined the task", body = Task),
+        (status = 400, description = "Invalid or expired link, or already a member"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Not a member of the task's organization")
+    ),
+    tag = "invitations",
+    security(("bearer_auth" = []))
+)]
+pub async fn join_task(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Json(payload): Json<JoinTaskRequest>,
+) -> Result<Json<Task>> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let (link, task) = state.invitation_service.join_with_link(&payload.token, user_id).await?;
+    notify_joined(&state, &task, user_id, None, &link.role, vec![task.user_id]).await?;
+
+    Ok(Json(task))
+}
+
+/// Invite someone into an organization by username or email address (admins and owners)
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{organization_id}/invitations",
+    params(
+        ("organization_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = CreateOrganizationInvitationRequest,
+    responses(
+        (status = 201, description = "Invitation sent", body = OrganizationInvitation),
+        (status = 400, description = "Validation error, already a member or already invited"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only organization admins can manage members"),
+        (status = 404, description = "Organization or username not found")
+    ),
+    tag = "organizations",
+    security(("bearer_auth" = []))
+)]
+pub async fn create_organization_invitation(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    client: ClientInfo,
+    Path(organization_id): Path<Uuid>,
+    Json(payload): Json<CreateOrganizationInvitationRequest>,
+) -> Result<impl IntoResponse> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let invitation = state
+        .invitation_service
+        .invite_to_organization(organization_id, user_id, payload)
+        .await?;
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                organization_id,
+                user_id,
+                AuditAction::OrganizationMemberInvited,
+                AuditTarget::Invitation,
+                invitation.id,
+            )
+            .with_after(json!({
+                "invitee_user_id": invitation.invitee_user_id,
+                "invitee_email": invitation.invitee_email,
+                "role": invitation.role,
+            })),
+            &client,
+        )
+        .await;
+    notify_organization_invitee(&state, &invitation, user_id).await?;
+
+    Ok((StatusCode::CREATED, Json(invitation)))
+}
+
+/// List an organization's pending invitations (admins and owners)
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{organization_id}/invitations",
+    params(
+        ("organization_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Pending invitations", body = Vec<OrganizationInvitation>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only organization admins can manage members"),
+        (status = 404, description = "Organization not found")
+    ),
+    tag = "organizations",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_organization_invitations(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<Vec<OrganizationInvitation>>> {
+    let invitations = state
+        .invitation_service
+        .list_organization_invitations(organization_id, user_id)
+        .await?;
+    Ok(Json(invitations))
+}
+
+/// Withdraw a pending organization invitation (admins and owners; only owners for owner invitations)
+#[utoipa::path(
+    delete,
+    path = "/api/organizations/{organization_id}/invitations/{invitation_id}",
+    params(
+        ("organization_id" = Uuid, Path, description = "Organization ID"),
+        ("invitation_id" = Uuid, Path, description = "Invitation ID")
+    ),
+    responses(
+        (status = 204, description = "Invitation revoked"),
+        (status = 400, description = "Invitation is no longer pending"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only organization admins can manage members"),
+        (status = 404, description = "Organization or invitation not found")
+    ),
+    tag = "organizations",
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_organization_invitation(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    client: ClientInfo,
+    Path((organization_id, invitation_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    let invitation = state
+        .invitation_service
+        .revoke_organization_invitation(organization_id, invitation_id, user_id)
+        .await?;
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                organization_id,
+                user_id,
+                AuditAction::OrganizationInvitationRevoked,
+                AuditTarget::Invitation,
+                invitation.id,
+            )
+            .with_before(json!({ "status": "pending", "role": invitation.role })),
+            &client,
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List organization invitations waiting for the current user
+#[utoipa::path(
+    get,
+    path = "/api/invitations/organizations",
+    responses(
+        (status = 200, description = "Pending organization invitations", body = Vec<ReceivedOrganizationInvitation>),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "invitations",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_my_organization_invitations(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<Vec<ReceivedOrganizationInvitation>>> {
+    let invitations = state
+        .invitation_service
+        .list_received_organization_invitations(user_id)
+        .await?;
+    Ok(Json(invitations))
+}
+
+/// Accept an organization invitation and become a member
+#[utoipa::path(
+    post,
+    path = "/api/invitations/organizations/{invitation_id}/accept",
+    params(
+        ("invitation_id" = Uuid, Path, description = "Invitation ID")
+    ),
+    responses(
+        (status = 200, description = "Joined the organization", body = OrganizationMembership),
+        (status = 400, description = "Invitation expired or no longer pending, or already a member"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Email address not verified, or the inviter can no longer grant the role"),
+        (status = 404, description = "Invitation not found")
+    ),
+    tag = "invitations",
+    security(("bearer_auth" = []))
+)]
+pub async fn accept_organization_invitation(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    client: ClientInfo,
+    Path(invitation_id): Path<Uuid>,
+) -> Result<Json<OrganizationMembership>> {
+    let invitation = state
+        .invitation_service
+        .accept_organization_invitation(invitation_id, user_id)
+        .await?;
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                invitation.organization_id,
+                user_id,
+                AuditAction::OrganizationMemberAdded,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_after(json!({
+                "role": invitation.role,
+                "invitation_id": invitation.id,
+                "invited_by": invitation.invited_by,
+            })),
+            &client,
+        )
+        .await;
+
+    let membership = state
+        .organization_service
+        .get_membership(invitation.organization_id, user_id)
+        .await?;
+    Ok(Json(membership))
+}
+
+/// Decline an organization invitation
+#[utoipa::path(
+    post,
+    path = "/api/invitations/organizations/{invitation_id}/decline",
+    params(
+        ("invitation_id" = Uuid, Path, description = "Invitation ID")
+    ),
+    responses(
+        (status = 204, description = "Invitation declined"),
+        (status = 400, description = "Invitation expired or no longer pending"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Invitation not found")
+    ),
+    tag = "invitations",
+    security(("bearer_auth" = []))
+)]
+pub async fn decline_organization_invitation(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(invitation_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    state
+        .invitation_service
+        .decline_organization_invitation(invitation_id, user_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Tell a registered invitee about a new invitation. Email-only
+/// invitations were already mailed by the service.
+pub(crate) async fn notify_invitee(state: &AppState, sent: &SentInvitation) -> Result<()> {
+    let Some(invitee) = sent.invitation.invitee_user_id else {
+        return Ok(());
+    };
+    let Some(inviter) = sent.invitation.invited_by else {
+        return Ok(());
+    };
+
+    let inviter_username = state.user_repository.find_by_id(inviter).await?
+        .map(|u| u.username)
+        .unwrap_or_else(|| "Unknown".to_string());
+    let message = format!(
+        "{} invited you to \"{}\" as {}",
+        inviter_username, sent.task.title, sent.invitation.role
+    );
+    state.notification_repository.create(invitee, Some(sent.task.id), &message).await?;
+    let _ = state.notification_tx.send(format!("{}:{}", invitee, message));
+
+    let ws_message = WsMessage::TaskInvitation(TaskInvitationPayload {
+        invitation_id: sent.invitation.id,
+        task_id: sent.task.id,
+        task_title: sent.task.title.clone(),
+        invited_by: inviter,
+        invited_by_username: inviter_username,
+        role: sent.invitation.role.clone(),
+    });
+    state.ws_connections.send_to_user(&invitee, ws_message);
+
+    Ok(())
+}
+
+async fn notify_joined(
+    state: &AppState,
+    task: &Task,
+    user_id: Uuid,
+    invitation_id: Option<Uuid>,
+    role: &str,
+    mut recipients: Vec<Uuid>,
+) -> Result<()> {
+    recipients.sort();
+    recipients.dedup();
+
+    let username = state.user_repository.find_by_id(user_id).await?
+        .map(|u| u.username)
+        .unwrap_or_else(|| "Unknown".to_string());
+    let message = format!("{} joined \"{}\" as {}", username, task.title, role);
+    let ws_message = WsMessage::TaskInvitationAccepted(TaskInvitationAcceptedPayload {
+        invitation_id,
+        task_id: task.id,
+        task_title: task.title.clone(),
+        user_id,
+        username,
+        role: role.to_string(),
+    });
+
+    for recipient in recipients {
+        state.notification_repository.create(recipient, Some(task.id), &message).await?;
+        let _ = state.notification_tx.send(format!("{}:{}", recipient, message));
+        state.ws_connections.send_to_user(&recipient, ws_message.clone());
+    }
+
+    Ok(())
+}
+
+/// Tell a user invited by username about an organization invitation.
+/// Email invitations were already mailed by the service.
+async fn notify_organization_invitee(
+    state: &AppState,
+    invitation: &OrganizationInvitation,
+    inviter: Uuid,
+) -> Result<()> {
+    let Some(invitee) = invitation.invitee_user_id else {
+        return Ok(());
+    };
+
+    let organization = state
+        .organization_service
+        .get_membership(invitation.organization_id, inviter)
+        .await?;
+    let inviter_username = state.user_repository.find_by_id(inviter).await?
+        .map(|u| u.username)
+        .unwrap_or_else(|| "Unknown".to_string());
+    let message = format!(
+        "{} invited you to join \"{}\" as {}",
+        inviter_username, organization.name, invitation.role
+    );
+    state.notification_repository.create(invitee, None, &message).await?;
+    let _ = state.notification_tx.send(format!("{}:{}", invitee, message));
+
+    Ok(())
+}
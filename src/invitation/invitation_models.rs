@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvitationStatus {
+    Pending,
+    Accepted,
+    Declined,
+    /// Withdrawn by the task owner
+    Revoked,
+}
+
+impl std::fmt::Display for InvitationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvitationStatus::Pending => write!(f, "pending"),
+            InvitationStatus::Accepted => write!(f, "accepted"),
+            InvitationStatus::Declined => write!(f, "declined"),
+            InvitationStatus::Revoked => write!(f, "revoked"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TaskInvitation {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub invited_by: Option<Uuid>,
+    /// Set when the invitee is registered, or once they accept
+    pub invitee_user_id: Option<Uuid>,
+    /// Set when inviting by email address
+    pub invitee_email: Option<String>,
+    pub role: String,
+    pub status: String,
+    pub expires_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A pending invitation as the invitee sees it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ReceivedInvitation {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    /// Switch to this organization to work on the task once accepted
+    pub organization_id: Uuid,
+    pub organization_name: String,
+    pub invited_by: Option<Uuid>,
+    pub invited_by_username: Option<String>,
+    pub role: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TaskJoinLink {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub role: String,
+    /// How many people joined through the link
+    pub use_count: i32,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct OrganizationInvitation {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub invited_by: Option<Uuid>,
+    /// Set when inviting by username, or once an email invitation is accepted
+    pub invitee_user_id: Option<Uuid>,
+    /// Set when inviting by email address
+    pub invitee_email: Option<String>,
+    /// `member`, `admin` or `owner`
+    pub role: String,
+    pub status: String,
+    pub expires_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A pending organization invitation as the invitee sees it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ReceivedOrganizationInvitation {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub organization_name: String,
+    pub invited_by: Option<Uuid>,
+    pub invited_by_username: Option<String>,
+    pub role: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,465 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{error::Result, organization::organization_models::OrgRole, task::task_models::TaskRole};
+use super::invitation_models::{
+    InvitationStatus, OrganizationInvitation, ReceivedInvitation, ReceivedOrganizationInvitation, TaskInvitation,
+    TaskJoinLink,
+};
+
+#[derive(Clone)]
+pub struct InvitationRepository {
+    pool: PgPool,
+}
+
+impl InvitationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        task_id: Uuid,
+        invited_by: Uuid,
+        invitee_user_id: Option<Uuid>,
+        invitee_email: Option<&str>,
+        role: TaskRole,
+        expires_at: DateTime<Utc>,
+    ) -> Result<TaskInvitation> {
+        let invitation = sqlx::query_as::<_, TaskInvitation>(
+            "INSERT INTO task_invitations (task_id, invited_by, invitee_user_id, invitee_email, role, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING *"
+        )
+        .bind(task_id)
+        .bind(invited_by)
+        .bind(invitee_user_id)
+        .bind(invitee_email)
+        .bind(role.to_string())
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(invitation)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<TaskInvitation>> {
+        let invitation = sqlx::query_as::<_, TaskInvitation>("SELECT * FROM task_invitations WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(invitation)
+    }
+
+    /// Whether the user or address already has an open invitation to the task.
+    pub async fn has_pending(
+        &self,
+        task_id: Uuid,
+        invitee_user_id: Option<Uuid>,
+        invitee_email: Option<&str>,
+    ) -> Result<bool> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM task_invitations
+             WHERE task_id = $1 AND status = 'pending' AND expires_at > NOW()
+               AND (invitee_user_id = $2 OR invitee_email = $3)"
+        )
+        .bind(task_id)
+        .bind(invitee_user_id)
+        .bind(invitee_email)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count > 0)
+    }
+
+    pub async fn find_pending_by_task(&self, task_id: Uuid) -> Result<Vec<TaskInvitation>> {
+        let invitations = sqlx::query_as::<_, TaskInvitation>(
+            "SELECT * FROM task_invitations
+             WHERE task_id = $1 AND status = 'pending' AND expires_at > NOW()
+             ORDER BY created_at DESC"
+        )
+        .bind(task_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(invitations)
+    }
+
+    /// Open invitations addressed to the user, or to their email address,
+    /// across every organization.
+    pub async fn find_pending_for_user(&self, user_id: Uuid, email: &str) -> Result<Vec<ReceivedInvitation>> {
+        let invitations = sqlx::query_as::<_, ReceivedInvitation>(
+            "SELECT i.id, i.task_id, t.title AS task_title, t.organization_id, o.name AS organization_name,
+                    i.invited_by, u.username AS invited_by_username, i.role, i.expires_at, i.created_at
+             FROM task_invitations i
+             JOIN tasks t ON t.id = i.task_id
+             JOIN organizations o ON o.id = t.organization_id
+             LEFT JOIN users u ON u.id = i.invited_by
+             WHERE i.status = 'pending' AND i.expires_at > NOW()
+               AND (i.invitee_user_id = $1 OR (i.invitee_user_id IS NULL AND i.invitee_email = LOWER($2)))
+             ORDER BY i.created_at DESC"
+        )
+        .bind(user_id)
+        .bind(email)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(invitations)
+    }
+
+    /// Mark a pending invitation declined or revoked; `None` if it was no longer pending.
+    pub async fn close(
+        &self,
+        id: Uuid,
+        status: InvitationStatus,
+        responded_by: Option<Uuid>,
+    ) -> Result<Option<TaskInvitation>> {
+        let invitation = sqlx::query_as::<_, TaskInvitation>(
+            "UPDATE task_invitations
+             SET status = $2, responded_at = NOW(), invitee_user_id = COALESCE(invitee_user_id, $3)
+             WHERE id = $1 AND status = 'pending'
+             RETURNING *"
+        )
+        .bind(id)
+        .bind(status.to_string())
+        .bind(responded_by)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(invitation)
+    }
+
+    /// The organization a task belongs to; invitees act across organizations,
+    /// so this is looked up by task alone.
+    pub async fn find_task_organization(&self, task_id: Uuid) -> Result<Option<Uuid>> {
+        let organization_id = sqlx::query_scalar("SELECT organization_id FROM tasks WHERE id = $1")
+            .bind(task_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(organization_id)
+    }
+
+    /// Accept an invitation and add the user to the task in one transaction.
+    /// Invitations sent to an email address also bring the user into the
+    /// task's organization.
+    pub async fn accept(&self, id: Uuid, user_id: Uuid) -> Result<Option<TaskInvitation>> {
+        let mut tx = self.pool.begin().await?;
+
+        let invitation = sqlx::query_as::<_, TaskInvitation>(
+            "UPDATE task_invitations
+             SET status = $3, responded_at = NOW(), invitee_user_id = $2
+             WHERE id = $1 AND status = 'pending' AND expires_at > NOW()
+             RETURNING *"
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(InvitationStatus::Accepted.to_string())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(invitation) = invitation else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            "INSERT INTO organization_members (organization_id, user_id, role, added_by)
+             SELECT t.organization_id, $2, 'member', $3
+             FROM tasks t
+             WHERE t.id = $1 AND $4
+             ON CONFLICT (organization_id, user_id) DO NOTHING"
+        )
+        .bind(invitation.task_id)
+        .bind(user_id)
+        .bind(invitation.invited_by)
+        .bind(invitation.invitee_email.is_some())
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO task_members (task_id, user_id, role, added_by)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (task_id, user_id) DO NOTHING"
+        )
+        .bind(invitation.task_id)
+        .bind(user_id)
+        .bind(&invitation.role)
+        .bind(invitation.invited_by)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO task_activity (task_id, user_id, action, details)
+             VALUES ($1, $2, 'invitation_accepted', $3)"
+        )
+        .bind(invitation.task_id)
+        .bind(user_id)
+        .bind(serde_json::json!({"invitation_id": invitation.id, "role": invitation.role}))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(invitation))
+    }
+
+    pub async fn create_join_link(
+        &self,
+        task_id: Uuid,
+        created_by: Uuid,
+        token_hash: &str,
+        role: TaskRole,
+        expires_at: DateTime<Utc>,
+    ) -> Result<TaskJoinLink> {
+        let link = sqlx::query_as::<_, TaskJoinLink>(
+            "INSERT INTO task_join_links (task_id, created_by, token_hash, role, expires_at)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING *"
+        )
+        .bind(task_id)
+        .bind(created_by)
+        .bind(token_hash)
+        .bind(role.to_string())
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(link)
+    }
+
+    pub async fn find_join_links_by_task(&self, task_id: Uuid) -> Result<Vec<TaskJoinLink>> {
+        let links = sqlx::query_as::<_, TaskJoinLink>(
+            "SELECT * FROM task_join_links
+             WHERE task_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+             ORDER BY created_at DESC"
+        )
+        .bind(task_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(links)
+    }
+
+    pub async fn revoke_join_link(&self, task_id: Uuid, link_id: Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE task_join_links SET revoked_at = NOW()
+             WHERE id = $1 AND task_id = $2 AND revoked_at IS NULL"
+        )
+        .bind(link_id)
+        .bind(task_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find_active_join_link(&self, token_hash: &str) -> Result<Option<TaskJoinLink>> {
+        let link = sqlx::query_as::<_, TaskJoinLink>(
+            "SELECT * FROM task_join_links
+             WHERE token_hash = $1 AND revoked_at IS NULL AND expires_at > NOW()"
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(link)
+    }
+
+    /// Add the user to the link's task and count the use, in one transaction.
+    pub async fn join_with_link(&self, link: &TaskJoinLink, user_id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO task_members (task_id, user_id, role, added_by)
+             VALUES ($1, $2, $3, $4)"
+        )
+        .bind(link.task_id)
+        .bind(user_id)
+        .bind(&link.role)
+        .bind(link.created_by)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE task_join_links SET use_count = use_count + 1 WHERE id = $1")
+            .bind(link.id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO task_activity (task_id, user_id, action, details)
+             VALUES ($1, $2, 'joined_by_link', $3)"
+        )
+        .bind(link.task_id)
+        .bind(user_id)
+        .bind(serde_json::json!({"join_link_id": link.id, "role": link.role}))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn create_organization_invitation(
+        &self,
+        organization_id: Uuid,
+        invited_by: Uuid,
+        invitee_user_id: Option<Uuid>,
+        invitee_email: Option<&str>,
+        role: OrgRole,
+        expires_at: DateTime<Utc>,
+    ) -> Result<OrganizationInvitation> {
+        let invitation = sqlx::query_as::<_, OrganizationInvitation>(
+            "INSERT INTO organization_invitations
+                (organization_id, invited_by, invitee_user_id, invitee_email, role, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING *"
+        )
+        .bind(organization_id)
+        .bind(invited_by)
+        .bind(invitee_user_id)
+        .bind(invitee_email)
+        .bind(role.to_string())
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(invitation)
+    }
+
+    pub async fn find_organization_invitation(&self, id: Uuid) -> Result<Option<OrganizationInvitation>> {
+        let invitation = sqlx::query_as::<_, OrganizationInvitation>(
+            "SELECT * FROM organization_invitations WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(invitation)
+    }
+
+    /// Whether the user or address already has an open invitation to the organization.
+    pub async fn has_pending_organization_invitation(
+        &self,
+        organization_id: Uuid,
+        invitee_user_id: Option<Uuid>,
+        invitee_email: Option<&str>,
+    ) -> Result<bool> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM organization_invitations
+             WHERE organization_id = $1 AND status = 'pending' AND expires_at > NOW()
+               AND (invitee_user_id = $2 OR invitee_email = $3)"
+        )
+        .bind(organization_id)
+        .bind(invitee_user_id)
+        .bind(invitee_email)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count > 0)
+    }
+
+    pub async fn find_pending_by_organization(&self, organization_id: Uuid) -> Result<Vec<OrganizationInvitation>> {
+        let invitations = sqlx::query_as::<_, OrganizationInvitation>(
+            "SELECT * FROM organization_invitations
+             WHERE organization_id = $1 AND status = 'pending' AND expires_at > NOW()
+             ORDER BY created_at DESC"
+        )
+        .bind(organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(invitations)
+    }
+
+    /// Open organization invitations addressed to the user, or to their email address.
+    pub async fn find_pending_organization_invitations_for_user(
+        &self,
+        user_id: Uuid,
+        email: &str,
+    ) -> Result<Vec<ReceivedOrganizationInvitation>> {
+        let invitations = sqlx::query_as::<_, ReceivedOrganizationInvitation>(
+            "SELECT i.id, i.organization_id, o.name AS organization_name,
+                    i.invited_by, u.username AS invited_by_username, i.role, i.expires_at, i.created_at
+             FROM organization_invitations i
+             JOIN organizations o ON o.id = i.organization_id
+             LEFT JOIN users u ON u.id = i.invited_by
+             WHERE i.status = 'pending' AND i.expires_at > NOW()
+               AND (i.invitee_user_id = $1 OR (i.invitee_user_id IS NULL AND i.invitee_email = LOWER($2)))
+             ORDER BY i.created_at DESC"
+        )
+        .bind(user_id)
+        .bind(email)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(invitations)
+    }
+
+    /// Mark a pending organization invitation declined or revoked; `None` if
+    /// it was no longer pending.
+    pub async fn close_organization_invitation(
+        &self,
+        id: Uuid,
+        status: InvitationStatus,
+        responded_by: Option<Uuid>,
+    ) -> Result<Option<OrganizationInvitation>> {
+        let invitation = sqlx::query_as::<_, OrganizationInvitation>(
+            "UPDATE organization_invitations
+             SET status = $2, responded_at = NOW(), invitee_user_id = COALESCE(invitee_user_id, $3)
+             WHERE id = $1 AND status = 'pending'
+             RETURNING *"
+        )
+        .bind(id)
+        .bind(status.to_string())
+        .bind(responded_by)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(invitation)
+    }
+
+    /// Accept an organization invitation and add the user as a member with
+    /// its role, in one transaction.
+    pub async fn accept_organization_invitation(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<OrganizationInvitation>> {
+        let mut tx = self.pool.begin().await?;
+
+        let invitation = sqlx::query_as::<_, OrganizationInvitation>(
+            "UPDATE organization_invitations
+             SET status = $3, responded_at = NOW(), invitee_user_id = $2
+             WHERE id = $1 AND status = 'pending' AND expires_at > NOW()
+             RETURNING *"
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(InvitationStatus::Accepted.to_string())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(invitation) = invitation else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            "INSERT INTO organization_members (organization_id, user_id, role, added_by)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (organization_id, user_id) DO NOTHING"
+        )
+        .bind(invitation.organization_id)
+        .bind(user_id)
+        .bind(&invitation.role)
+        .bind(invitation.invited_by)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(invitation))
+    }
+}
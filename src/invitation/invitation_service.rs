@@ -0,0 +1,684 @@
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    auth::secure_token::{generate_token, hash_token},
+    error::{AppError, Result},
+    mail::{MailMessage, Mailer},
+    organization::{organization_models::OrgRole, organization_service::OrganizationService},
+    state::Config,
+    task::{task_models::{Task, TaskRole}, task_repository::TaskRepository},
+    user::{user_models::User, user_repository::UserRepository},
+};
+use super::{
+    invitation_dto::{
+        CreateInvitationRequest, CreateJoinLinkRequest, CreateOrganizationInvitationRequest, CreatedJoinLinkResponse,
+    },
+    invitation_models::{
+        InvitationStatus, OrganizationInvitation, ReceivedInvitation, ReceivedOrganizationInvitation, TaskInvitation,
+        TaskJoinLink,
+    },
+    invitation_repository::InvitationRepository,
+};
+
+/// How long an invitation can be accepted.
+pub const INVITATION_EXPIRY_DAYS: i64 = 14;
+const DEFAULT_JOIN_LINK_HOURS: i64 = 72;
+
+/// An invitation together with what the caller needs to announce it.
+pub struct SentInvitation {
+    pub invitation: TaskInvitation,
+    pub task: Task,
+}
+
+#[derive(Clone)]
+pub struct InvitationService {
+    repo: InvitationRepository,
+    task_repo: TaskRepository,
+    user_repo: UserRepository,
+    organization_service: OrganizationService,
+    mailer: Mailer,
+    invitation_url: String,
+    join_url: String,
+}
+
+impl InvitationService {
+    pub fn new(
+        repo: InvitationRepository,
+        task_repo: TaskRepository,
+        user_repo: UserRepository,
+        organization_service: OrganizationService,
+        mailer: Mailer,
+        config: &Config,
+    ) -> Self {
+        Self {
+            repo,
+            task_repo,
+            user_repo,
+            organization_service,
+            mailer,
+            invitation_url: config.task_invitation_url.clone(),
+            join_url: config.task_join_url.clone(),
+        }
+    }
+
+    /// Invite a user by username or email. Addresses nobody has signed up
+    /// with yet get an email and see the invitation once registered; only
+    /// organization admins may invite them, as accepting also joins the
+    /// organization.
+    pub async fn invite(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        inviter_id: Uuid,
+        payload: CreateInvitationRequest,
+    ) -> Result<SentInvitation> {
+        let role = Self::invited_role(payload.role)?;
+
+        let (invitee, email) = match (payload.username, payload.email) {
+            (Some(username), None) => {
+                let user = self
+                    .user_repo
+                    .find_by_username(username.trim())
+                    .await?
+                    .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+                (Some(user), None)
+            }
+            (None, Some(email)) => {
+                let email = email.trim();
+                match self.user_repo.find_by_email(email).await? {
+                    Some(user) => (Some(user), None),
+                    None => (None, Some(email.to_lowercase())),
+                }
+            }
+            _ => {
+                return Err(AppError::Validation(
+                    "Give either a username or an email address".to_string(),
+                ))
+            }
+        };
+
+        let sent = match invitee {
+            Some(user) => self.invite_user(organization_id, task_id, inviter_id, &user, role).await?,
+            None => {
+                if self.organization_service.require_role(organization_id, inviter_id).await? < OrgRole::Admin {
+                    return Err(AppError::Forbidden(
+                        "Only organization admins can invite people from outside the organization".to_string(),
+                    ));
+                }
+                let email = email.unwrap_or_default();
+                let sent = self
+                    .create_invitation(organization_id, task_id, inviter_id, None, Some(&email), role)
+                    .await?;
+                self.send_invitation_email(&sent, inviter_id, &email).await?;
+                sent
+            }
+        };
+
+        Ok(sent)
+    }
+
+    /// Invite several registered users at once by id.
+    pub async fn invite_user_ids(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        inviter_id: Uuid,
+        user_ids: Vec<Uuid>,
+        role: Option<TaskRole>,
+    ) -> Result<Vec<SentInvitation>> {
+        let role = Self::invited_role(role)?;
+        let mut sent = Vec::with_capacity(user_ids.len());
+
+        for user_id in user_ids {
+            let user = self
+                .user_repo
+                .find_by_id(user_id)
+                .await?
+                .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+            sent.push(self.invite_user(organization_id, task_id, inviter_id, &user, role).await?);
+        }
+
+        Ok(sent)
+    }
+
+    async fn invite_user(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        inviter_id: Uuid,
+        user: &User,
+        role: TaskRole,
+    ) -> Result<SentInvitation> {
+        if user.id == inviter_id {
+            return Err(AppError::BadRequest("You cannot invite yourself".to_string()));
+        }
+        if !self.organization_service.is_member(organization_id, user.id).await? {
+            return Err(AppError::BadRequest(format!("{} is not a member of this organization", user.username)));
+        }
+        if self.task_repo.has_task_access(organization_id, task_id, user.id).await? {
+            return Err(AppError::BadRequest(format!("{} is already a task member", user.username)));
+        }
+
+        let email = user.email.to_lowercase();
+        self.create_invitation(organization_id, task_id, inviter_id, Some(user.id), Some(&email), role).await
+    }
+
+    async fn create_invitation(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        inviter_id: Uuid,
+        invitee_user_id: Option<Uuid>,
+        invitee_email: Option<&str>,
+        role: TaskRole,
+    ) -> Result<SentInvitation> {
+        let task = self.owned_task(organization_id, task_id, inviter_id).await?;
+
+        if self.repo.has_pending(task_id, invitee_user_id, invitee_email).await? {
+            return Err(AppError::BadRequest("An invitation is already pending".to_string()));
+        }
+
+        // Registered invitees are matched by id, so the address is only kept for the others
+        let stored_email = if invitee_user_id.is_some() { None } else { invitee_email };
+        let expires_at = Utc::now() + Duration::days(INVITATION_EXPIRY_DAYS);
+        let invitation = self
+            .repo
+            .create(task_id, inviter_id, invitee_user_id, stored_email, role, expires_at)
+            .await?;
+
+        let _ = self.task_repo.log_task_activity(
+            organization_id,
+            task_id,
+            inviter_id,
+            "invitation_sent",
+            Some(serde_json::json!({
+                "invitation_id": invitation.id,
+                "invitee_user_id": invitee_user_id,
+                "invitee_email": stored_email,
+                "role": role,
+            })),
+        ).await;
+
+        Ok(SentInvitation { invitation, task })
+    }
+
+    async fn send_invitation_email(&self, sent: &SentInvitation, inviter_id: Uuid, email: &str) -> Result<()> {
+        let inviter = self
+            .user_repo
+            .find_by_id(inviter_id)
+            .await?
+            .map(|user| user.username)
+            .unwrap_or_else(|| "Someone".to_string());
+
+        self.mailer.send_in_background(MailMessage {
+            to: email.to_string(),
+            subject: format!("{} invited you to a task", inviter),
+            body: format!(
+                "Hi,\n\n{} invited you to collaborate on \"{}\" as {}.\n\nSign up with this email address, verify it and open the link below to accept. The invitation expires in {} days.\n\n{}\n",
+                inviter,
+                sent.task.title,
+                sent.invitation.role,
+                INVITATION_EXPIRY_DAYS,
+                self.invitation_url
+            ),
+        });
+
+        Ok(())
+    }
+
+    pub async fn list_task_invitations(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        requesting_user: Uuid,
+    ) -> Result<Vec<TaskInvitation>> {
+        self.owned_task(organization_id, task_id, requesting_user).await?;
+        self.repo.find_pending_by_task(task_id).await
+    }
+
+    pub async fn revoke_invitation(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        invitation_id: Uuid,
+        requesting_user: Uuid,
+    ) -> Result<()> {
+        self.owned_task(organization_id, task_id, requesting_user).await?;
+
+        match self.repo.find_by_id(invitation_id).await? {
+            Some(invitation) if invitation.task_id == task_id => {}
+            _ => return Err(AppError::NotFound("Invitation not found".into())),
+        }
+
+        self.repo
+            .close(invitation_id, InvitationStatus::Revoked, None)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Invitation is no longer pending".to_string()))?;
+        Ok(())
+    }
+
+    pub async fn list_received(&self, user_id: Uuid) -> Result<Vec<ReceivedInvitation>> {
+        let user = self.find_user(user_id).await?;
+        self.repo.find_pending_for_user(user.id, &user.email).await
+    }
+
+    /// Join the task the invitation is for, whichever organization it is in.
+    pub async fn accept(&self, invitation_id: Uuid, user_id: Uuid) -> Result<SentInvitation> {
+        let user = self.find_user(user_id).await?;
+        let invitation = self.received_invitation(invitation_id, &user).await?;
+        let organization_id = self.task_organization(invitation.task_id).await?;
+
+        if invitation.invitee_user_id.is_none() {
+            // Anyone could sign up with an address they do not own
+            if !user.email_verified {
+                return Err(AppError::Forbidden(
+                    "Verify your email address to accept this invitation".to_string(),
+                ));
+            }
+        } else if !self.organization_service.is_member(organization_id, user.id).await? {
+            return Err(AppError::Forbidden(
+                "You are no longer a member of the task's organization".to_string(),
+            ));
+        }
+
+        let invitation = self
+            .repo
+            .accept(invitation.id, user.id)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Invitation is no longer pending".to_string()))?;
+        let task = self
+            .task_repo
+            .find_by_id_any(organization_id, invitation.task_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Task not found".into()))?;
+
+        Ok(SentInvitation { invitation, task })
+    }
+
+    pub async fn decline(&self, invitation_id: Uuid, user_id: Uuid) -> Result<()> {
+        let user = self.find_user(user_id).await?;
+        let invitation = self.received_invitation(invitation_id, &user).await?;
+
+        self.repo
+            .close(invitation.id, InvitationStatus::Declined, Some(user.id))
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Invitation is no longer pending".to_string()))?;
+        Ok(())
+    }
+
+    pub async fn create_join_link(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        requesting_user: Uuid,
+        payload: CreateJoinLinkRequest,
+    ) -> Result<CreatedJoinLinkResponse> {
+        self.owned_task(organization_id, task_id, requesting_user).await?;
+        let role = match payload.role {
+            Some(role) => Self::invited_role(Some(role))?,
+            None => TaskRole::Viewer,
+        };
+
+        let token = generate_token();
+        let expires_at = Utc::now() + Duration::hours(payload.expires_in_hours.unwrap_or(DEFAULT_JOIN_LINK_HOURS));
+        let link = self
+            .repo
+            .create_join_link(task_id, requesting_user, &hash_token(&token), role, expires_at)
+            .await?;
+
+        let _ = self.task_repo.log_task_activity(
+            organization_id,
+            task_id,
+            requesting_user,
+            "join_link_created",
+            Some(serde_json::json!({"join_link_id": link.id, "role": role, "expires_at": expires_at})),
+        ).await;
+
+        Ok(CreatedJoinLinkResponse {
+            url: format!("{}?token={}", self.join_url, token),
+            link,
+            token,
+        })
+    }
+
+    pub async fn list_join_links(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        requesting_user: Uuid,
+    ) -> Result<Vec<TaskJoinLink>> {
+        self.owned_task(organization_id, task_id, requesting_user).await?;
+        self.repo.find_join_links_by_task(task_id).await
+    }
+
+    pub async fn revoke_join_link(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        link_id: Uuid,
+        requesting_user: Uuid,
+    ) -> Result<()> {
+        self.owned_task(organization_id, task_id, requesting_user).await?;
+
+        if self.repo.revoke_join_link(task_id, link_id).await? == 0 {
+            return Err(AppError::NotFound("Join link not found".into()));
+        }
+        Ok(())
+    }
+
+    /// Become a member of the link's task with the link's role. Links only
+    /// work for members of the task's organization.
+    pub async fn join_with_link(&self, token: &str, user_id: Uuid) -> Result<(TaskJoinLink, Task)> {
+        let link = self
+            .repo
+            .find_active_join_link(&hash_token(token))
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Invalid or expired join link".to_string()))?;
+
+        let organization_id = self.task_organization(link.task_id).await?;
+        if !self.organization_service.is_member(organization_id, user_id).await? {
+            return Err(AppError::Forbidden(
+                "Join links only work for members of the task's organization".to_string(),
+            ));
+        }
+        if self.task_repo.has_task_access(organization_id, link.task_id, user_id).await? {
+            return Err(AppError::BadRequest("You are already a member of this task".to_string()));
+        }
+
+        self.repo.join_with_link(&link, user_id).await?;
+        let task = self
+            .task_repo
+            .find_by_id_any(organization_id, link.task_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Task not found".into()))?;
+
+        Ok((link, task))
+    }
+
+    /// Invite someone into the organization by username or email. An
+    /// address gets an email whether or not it has an account, so the reply
+    /// does not tell which addresses are registered; only a registered user
+    /// invited by username is named on the invitation.
+    pub async fn invite_to_organization(
+        &self,
+        organization_id: Uuid,
+        inviter_id: Uuid,
+        payload: CreateOrganizationInvitationRequest,
+    ) -> Result<OrganizationInvitation> {
+        let role = payload.role.unwrap_or(OrgRole::Member);
+        self.organization_service.check_can_invite(organization_id, inviter_id, role).await?;
+
+        let (invitee_user_id, email) = match (payload.username, payload.email) {
+            (Some(username), None) => {
+                let user = self
+                    .user_repo
+                    .find_by_username(username.trim())
+                    .await?
+                    .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+                if self.organization_service.is_member(organization_id, user.id).await? {
+                    return Err(AppError::BadRequest(format!("{} is already a member", user.username)));
+                }
+                (Some(user.id), None)
+            }
+            (None, Some(email)) => (None, Some(email.trim().to_lowercase())),
+            _ => {
+                return Err(AppError::Validation(
+                    "Give either a username or an email address".to_string(),
+                ))
+            }
+        };
+
+        if self
+            .repo
+            .has_pending_organization_invitation(organization_id, invitee_user_id, email.as_deref())
+            .await?
+        {
+            return Err(AppError::BadRequest("An invitation is already pending".to_string()));
+        }
+
+        let expires_at = Utc::now() + Duration::days(INVITATION_EXPIRY_DAYS);
+        let invitation = self
+            .repo
+            .create_organization_invitation(
+                organization_id,
+                inviter_id,
+                invitee_user_id,
+                email.as_deref(),
+                role,
+                expires_at,
+            )
+            .await?;
+
+        if let Some(email) = email {
+            self.send_organization_invitation_email(&invitation, inviter_id, &email).await?;
+        }
+
+        Ok(invitation)
+    }
+
+    async fn send_organization_invitation_email(
+        &self,
+        invitation: &OrganizationInvitation,
+        inviter_id: Uuid,
+        email: &str,
+    ) -> Result<()> {
+        let inviter = self
+            .user_repo
+            .find_by_id(inviter_id)
+            .await?
+            .map(|user| user.username)
+            .unwrap_or_else(|| "Someone".to_string());
+        let organization = self
+            .organization_service
+            .get_membership(invitation.organization_id, inviter_id)
+            .await?;
+
+        self.mailer.send_in_background(MailMessage {
+            to: email.to_string(),
+            subject: format!("{} invited you to {}", inviter, organization.name),
+            body: format!(
+                "Hi,\n\n{} invited you to join \"{}\" as {}.\n\nSign in, or sign up with this email address, verify it and open the link below to accept. The invitation expires in {} days.\n\n{}\n",
+                inviter,
+                organization.name,
+                invitation.role,
+                INVITATION_EXPIRY_DAYS,
+                self.invitation_url
+            ),
+        });
+
+        Ok(())
+    }
+
+    pub async fn list_organization_invitations(
+        &self,
+        organization_id: Uuid,
+        requesting_user: Uuid,
+    ) -> Result<Vec<OrganizationInvitation>> {
+        self.organization_service
+            .check_can_invite(organization_id, requesting_user, OrgRole::Member)
+            .await?;
+        self.repo.find_pending_by_organization(organization_id).await
+    }
+
+    /// Withdraw a pending invitation; only owners withdraw invitations to
+    /// become an owner.
+    pub async fn revoke_organization_invitation(
+        &self,
+        organization_id: Uuid,
+        invitation_id: Uuid,
+        requesting_user: Uuid,
+    ) -> Result<OrganizationInvitation> {
+        let invitation = self
+            .repo
+            .find_organization_invitation(invitation_id)
+            .await?
+            .filter(|invitation| invitation.organization_id == organization_id)
+            .ok_or_else(|| AppError::NotFound("Invitation not found".into()))?;
+        let role = OrgRole::parse(&invitation.role).unwrap_or(OrgRole::Owner);
+        self.organization_service
+            .check_can_invite(organization_id, requesting_user, role)
+            .await?;
+
+        self.repo
+            .close_organization_invitation(invitation.id, InvitationStatus::Revoked, None)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Invitation is no longer pending".to_string()))
+    }
+
+    pub async fn list_received_organization_invitations(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<ReceivedOrganizationInvitation>> {
+        let user = self.find_user(user_id).await?;
+        self.repo
+            .find_pending_organization_invitations_for_user(user.id, &user.email)
+            .await
+    }
+
+    /// Join the organization with the invitation's role. The inviter must
+    /// still be allowed to grant that role.
+    pub async fn accept_organization_invitation(
+        &self,
+        invitation_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<OrganizationInvitation> {
+        let user = self.find_user(user_id).await?;
+        let invitation = self.received_organization_invitation(invitation_id, &user).await?;
+
+        // Anyone could sign up with an address they do not own
+        if invitation.invitee_user_id.is_none() && !user.email_verified {
+            return Err(AppError::Forbidden(
+                "Verify your email address to accept this invitation".to_string(),
+            ));
+        }
+        if self.organization_service.is_member(invitation.organization_id, user.id).await? {
+            return Err(AppError::BadRequest("You are already a member of this organization".to_string()));
+        }
+
+        let role = OrgRole::parse(&invitation.role).unwrap_or(OrgRole::Member);
+        let inviter_still_allowed = match invitation.invited_by {
+            Some(inviter) => self
+                .organization_service
+                .check_can_invite(invitation.organization_id, inviter, role)
+                .await
+                .is_ok(),
+            None => false,
+        };
+        if !inviter_still_allowed {
+            return Err(AppError::Forbidden(
+                "The person who invited you can no longer add members with this role".to_string(),
+            ));
+        }
+
+        self.repo
+            .accept_organization_invitation(invitation.id, user.id)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Invitation is no longer pending".to_string()))
+    }
+
+    pub async fn decline_organization_invitation(&self, invitation_id: Uuid, user_id: Uuid) -> Result<()> {
+        let user = self.find_user(user_id).await?;
+        let invitation = self.received_organization_invitation(invitation_id, &user).await?;
+
+        self.repo
+            .close_organization_invitation(invitation.id, InvitationStatus::Declined, Some(user.id))
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Invitation is no longer pending".to_string()))?;
+        Ok(())
+    }
+
+    /// A pending organization invitation addressed to `user`; others are
+    /// reported as not found.
+    async fn received_organization_invitation(
+        &self,
+        invitation_id: Uuid,
+        user: &User,
+    ) -> Result<OrganizationInvitation> {
+        let invitation = self
+            .repo
+            .find_organization_invitation(invitation_id)
+            .await?
+            .filter(|invitation| match invitation.invitee_user_id {
+                Some(invitee) => invitee == user.id,
+                None => invitation.invitee_email.as_deref() == Some(user.email.to_lowercase().as_str()),
+            })
+            .ok_or_else(|| AppError::NotFound("Invitation not found".into()))?;
+
+        if invitation.status != InvitationStatus::Pending.to_string() {
+            return Err(AppError::BadRequest("Invitation is no longer pending".to_string()));
+        }
+        if invitation.expires_at <= Utc::now() {
+            return Err(AppError::BadRequest("Invitation has expired".to_string()));
+        }
+
+        Ok(invitation)
+    }
+
+    /// A pending invitation addressed to `user`; others are reported as not found.
+    async fn received_invitation(&self, invitation_id: Uuid, user: &User) -> Result<TaskInvitation> {
+        let invitation = self
+            .repo
+            .find_by_id(invitation_id)
+            .await?
+            .filter(|invitation| match invitation.invitee_user_id {
+                Some(invitee) => invitee == user.id,
+                None => invitation.invitee_email.as_deref() == Some(user.email.to_lowercase().as_str()),
+            })
+            .ok_or_else(|| AppError::NotFound("Invitation not found".into()))?;
+
+        if invitation.status != InvitationStatus::Pending.to_string() {
+            return Err(AppError::BadRequest("Invitation is no longer pending".to_string()));
+        }
+        if invitation.expires_at <= Utc::now() {
+            return Err(AppError::BadRequest("Invitation has expired".to_string()));
+        }
+
+        Ok(invitation)
+    }
+
+    async fn owned_task(&self, organization_id: Uuid, task_id: Uuid, user_id: Uuid) -> Result<Task> {
+        self.task_repo
+            .find_by_id(organization_id, task_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::Forbidden("Only task owner can invite".to_string()))
+    }
+
+    async fn task_organization(&self, task_id: Uuid) -> Result<Uuid> {
+        self.repo
+            .find_task_organization(task_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Task not found".into()))
+    }
+
+    async fn find_user(&self, user_id: Uuid) -> Result<User> {
+        self.user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".into()))
+    }
+
+    fn invited_role(role: Option<TaskRole>) -> Result<TaskRole> {
+        match role.unwrap_or(TaskRole::Editor) {
+            TaskRole::Owner => Err(AppError::BadRequest(
+                "Transfer ownership to make someone the owner".to_string(),
+            )),
+            role => Ok(role),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invitations_cannot_grant_ownership() {
+        assert_eq!(InvitationService::invited_role(None).unwrap(), TaskRole::Editor);
+        assert_eq!(InvitationService::invited_role(Some(TaskRole::Viewer)).unwrap(), TaskRole::Viewer);
+        assert!(matches!(
+            InvitationService::invited_role(Some(TaskRole::Owner)),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+}
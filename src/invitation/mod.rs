@@ -0,0 +1,6 @@
+// Declare submodules
+pub mod invitation_models;
+pub mod invitation_dto;
+pub mod invitation_repository;
+pub mod invitation_handlers;
+pub mod invitation_service;
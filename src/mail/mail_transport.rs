@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use std::{path::PathBuf, sync::Arc};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+#[derive(Debug, Clone)]
+pub struct MailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Delivers outgoing mail. Implement this to plug in SMTP or a mail API.
+#[async_trait]
+pub trait MailTransport: Send + Sync {
+    async fn send(&self, from: &str, message: &MailMessage) -> Result<()>;
+}
+
+/// Writes every message to the application log. Meant for local development.
+pub struct LogTransport;
+
+#[async_trait]
+impl MailTransport for LogTransport {
+    async fn send(&self, from: &str, message: &MailMessage) -> Result<()> {
+        info!(
+            "Mail from {} to {}: {}\n{}",
+            from, message.to, message.subject, message.body
+        );
+        Ok(())
+    }
+}
+
+/// Stores every message as an `.eml` file in a directory, for local testing.
+pub struct FileTransport {
+    dir: PathBuf,
+}
+
+impl FileTransport {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[async_trait]
+impl MailTransport for FileTransport {
+    async fn send(&self, from: &str, message: &MailMessage) -> Result<()> {
+        let now = Utc::now();
+        let contents = format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\n\r\n{}\r\n",
+            from,
+            message.to,
+            message.subject,
+            now.to_rfc2822(),
+            message.body
+        );
+        let path = self.dir.join(format!("{}-{}.eml", now.format("%Y%m%dT%H%M%S"), Uuid::new_v4()));
+
+        tokio::fs::create_dir_all(&self.dir).await.map_err(|e| {
+            error!("Failed to create mail directory {:?}: {:?}", self.dir, e);
+            AppError::InternalError
+        })?;
+        tokio::fs::write(&path, contents).await.map_err(|e| {
+            error!("Failed to write mail to {:?}: {:?}", path, e);
+            AppError::InternalError
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Transport selected by `MAIL_TRANSPORT` (`log` or `file`).
+pub fn create_mail_transport(kind: &str, file_dir: &str) -> Option<Arc<dyn MailTransport>> {
+    match kind {
+        "log" => Some(Arc::new(LogTransport)),
+        "file" => Some(Arc::new(FileTransport::new(file_dir))),
+        _ => None,
+    }
+}
+
+#[derive(Clone)]
+pub struct Mailer {
+    transport: Arc<dyn MailTransport>,
+    from: String,
+}
+
+impl Mailer {
+    pub fn new(transport: Arc<dyn MailTransport>, from: String) -> Self {
+        Self { transport, from }
+    }
+
+    pub async fn send(&self, message: MailMessage) -> Result<()> {
+        self.transport.send(&self.from, &message).await
+    }
+
+    /// Send without waiting, so response times don't depend on delivery.
+    pub fn send_in_background(&self, message: MailMessage) {
+        let mailer = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = mailer.send(message).await {
+                error!("Failed to send mail: {:?}", e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_transport_writes_eml() {
+        let dir = std::env::temp_dir().join(format!("mail-test-{}", Uuid::new_v4()));
+        let mailer = Mailer::new(Arc::new(FileTransport::new(&dir)), "no-reply@example.com".to_string());
+
+        mailer
+            .send(MailMessage {
+                to: "user@example.com".to_string(),
+                subject: "Hello".to_string(),
+                body: "Body text".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let entry = std::fs::read_dir(&dir).unwrap().next().unwrap().unwrap();
+        let contents = std::fs::read_to_string(entry.path()).unwrap();
+        assert!(contents.contains("To: user@example.com"));
+        assert!(contents.contains("Body text"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,5 @@
+// Declare submodules
+pub mod mail_transport;
+
+// Re-export public items
+pub use mail_transport::{create_mail_transport, MailMessage, Mailer};
@@ -1,17 +1,24 @@
-mod admin;
+mod analytics;
+mod audit;
 mod auth;
 mod db;
 mod error;
+mod escalation;
+mod invitation;
+mod mail;
 mod message;
 mod middleware;
 mod notification;
+mod organization;
+mod public_link;
+mod role;
 mod routes;
 mod state;
 mod task;
 mod user;
 mod websocket;
 
-use auth::create_oauth_client;
+use auth::{JwtKeys, OAuthProviderRegistry};
 use db::{create_pool, run_migrations};
 use notification::start_notification_service;
 use routes::create_router;
@@ -48,12 +55,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Running migrations...");
     run_migrations(&db).await?;
 
-    // Create OAuth client
-    let oauth_client = create_oauth_client(
-        config.google_client_id.clone(),
-        config.google_client_secret.clone(),
-        config.google_redirect_uri.clone(),
-    )?;
+    // Load the JWT signing keys, creating the first one on a fresh database
+    let jwt_keys = JwtKeys::new(db.clone(), &config);
+    jwt_keys.refresh().await?;
+
+    // Set up login providers (runs OIDC discovery)
+    let oauth_providers = Arc::new(OAuthProviderRegistry::from_configs(&config.oauth_providers).await);
 
     // Create notification broadcaster
     let (notification_tx, _) = broadcast::channel(100);
@@ -70,26 +77,85 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let notification_repository = crate::notification::notification_repository::NotificationRepository::new(db.clone());
     let message_repository = crate::message::message_repository::MessageRepository::new(db.clone());
     let refresh_token_repository = crate::auth::auth_repository::RefreshTokenRepository::new(db.clone());
+    let password_reset_repository = crate::auth::auth_repository::PasswordResetRepository::new(db.clone());
+    let email_verification_repository = crate::auth::auth_repository::EmailVerificationRepository::new(db.clone());
+    let two_factor_repository = crate::auth::auth_repository::TwoFactorRepository::new(db.clone());
+    let security_event_repository = crate::auth::auth_repository::SecurityEventRepository::new(db.clone());
+    let personal_access_token_repository = crate::auth::auth_repository::PersonalAccessTokenRepository::new(db.clone());
+    let identity_repository = crate::auth::auth_repository::IdentityRepository::new(db.clone());
+    let oauth_state_repository = crate::auth::auth_repository::OAuthStateRepository::new(db.clone());
+    let login_throttle_repository = crate::auth::auth_repository::LoginThrottleRepository::new(db.clone());
+    let impersonation_repository = crate::auth::auth_repository::ImpersonationRepository::new(db.clone());
+    let escalation_repository = crate::escalation::escalation_repository::EscalationRepository::new(db.clone());
+    let role_repository = crate::role::role_repository::RoleRepository::new(db.clone());
+    let invitation_repository = crate::invitation::invitation_repository::InvitationRepository::new(db.clone());
+    let public_link_repository = crate::public_link::public_link_repository::PublicLinkRepository::new(db.clone());
+    let organization_repository = crate::organization::organization_repository::OrganizationRepository::new(db.clone());
+    let analytics_repository = crate::analytics::analytics_repository::AnalyticsRepository::new(db.clone());
+    let audit_repository = crate::audit::audit_repository::AuditRepository::new(db.clone());
+
+    // Create mailer
+    let mail_transport = crate::mail::create_mail_transport(&config.mail_transport, &config.mail_file_dir)
+        .ok_or("MAIL_TRANSPORT must be \"log\" or \"file\"")?;
+    let mailer = crate::mail::Mailer::new(mail_transport, config.mail_from.clone());
 
     // Create services
+    let organization_service = crate::organization::organization_service::OrganizationService::new(organization_repository);
     let user_service = crate::user::user_service::UserService::new(
         user_repository.clone(),
         task_repository.clone(),
     );
     let task_service = crate::task::task_service::TaskService::new(task_repository.clone());
+    let audit_service = crate::audit::audit_service::AuditService::new(audit_repository);
     let auth_service = crate::auth::auth_service::AuthService::new(
         db.clone(),
         user_repository.clone(),
         refresh_token_repository.clone(),
-        config.jwt_secret.clone(),
+        organization_service.clone(),
+        audit_service.clone(),
+        password_reset_repository,
+        email_verification_repository,
+        two_factor_repository,
+        security_event_repository,
+        personal_access_token_repository,
+        identity_repository,
+        oauth_state_repository,
+        login_throttle_repository,
+        impersonation_repository,
+        mailer.clone(),
+        jwt_keys.clone(),
+        &config,
     );
     let message_service = crate::message::message_service::MessageService::new(message_repository.clone());
+    let escalation_service = crate::escalation::escalation_service::EscalationService::new(
+        escalation_repository.clone(),
+        task_repository.clone(),
+    );
+    let role_service = crate::role::role_service::RoleService::new(role_repository, user_repository.clone());
+    let invitation_service = crate::invitation::invitation_service::InvitationService::new(
+        invitation_repository,
+        task_repository.clone(),
+        user_repository.clone(),
+        organization_service.clone(),
+        mailer,
+        &config,
+    );
+    let public_link_service = crate::public_link::public_link_service::PublicLinkService::new(
+        public_link_repository,
+        task_repository.clone(),
+        &config,
+    );
+    let analytics_service = crate::analytics::analytics_service::AnalyticsService::new(
+        analytics_repository,
+        user_repository.clone(),
+    );
 
     // Create application state
     let state = AppState {
         db: db.clone(),
         config: config.clone(),
-        oauth_client,
+        oauth_providers,
+        jwt_keys,
         notification_tx: notification_tx.clone(),
         task_tx: task_tx.clone(),
         ws_connections,
@@ -102,6 +168,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         task_service,
         auth_service,
         message_service,
+        escalation_service,
+        role_service,
+        invitation_service,
+        organization_service,
+        public_link_service,
+        analytics_service,
+        audit_service,
     };
 
     // Start notification service
@@ -124,7 +197,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Swagger UI available at http://{}/swagger-ui", addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    // Peer addresses are recorded on sessions when no proxy header is present
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await?;
 
     Ok(())
 }
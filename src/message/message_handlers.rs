@@ -10,7 +10,7 @@ use validator::Validate;
 
 use crate::{
     error::{AppError, Result},
-    middleware::AuthUser,
+    middleware::{AuthUser, CurrentOrganization},
     state::AppState,
     task::task_dto::PaginatedResponse,
     message::{
@@ -44,21 +44,22 @@ pub struct MessageQuery {
 pub async fn send_message(
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
     Json(payload): Json<SendMessageRequest>,
 ) -> Result<impl IntoResponse> {
     payload.validate()?;
 
-    // Verify receiver exists
+    // Only members of the organization can be messaged in it
     let _receiver = state
         .user_repository
-        .find_by_id(payload.receiver_id)
+        .find_by_id_in_organization(organization_id, payload.receiver_id)
         .await?
         .ok_or(AppError::NotFound("Receiver not found".to_string()))?;
 
     // Create message
     let message = state
         .message_service
-        .send_message(user_id, payload.clone())
+        .send_message(organization_id, user_id, payload.clone())
         .await?;
 
     // Broadcast message via WebSocket
@@ -107,6 +108,7 @@ pub async fn send_message(
 pub async fn get_conversation(
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
     Path(other_user_id): Path<Uuid>,
     Query(query): Query<MessageQuery>,
 ) -> Result<impl IntoResponse> {
@@ -116,13 +118,13 @@ pub async fn get_conversation(
 
     let messages = state
         .message_service
-        .get_conversation(user_id, other_user_id, limit as i64, offset)
+        .get_conversation(organization_id, user_id, other_user_id, limit as i64, offset)
         .await?;
 
     // Mark messages from other user as read
     let _ = state
         .message_service
-        .mark_conversation_as_read(user_id, other_user_id)
+        .mark_conversation_as_read(organization_id, user_id, other_user_id)
         .await;
 
     let message_responses: Vec<MessageResponse> = messages
@@ -160,10 +162,11 @@ pub async fn get_conversation(
 pub async fn get_conversations(
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
 ) -> Result<impl IntoResponse> {
     let conversations = state
         .message_service
-        .get_conversations(user_id)
+        .get_conversations(organization_id, user_id)
         .await?;
 
     Ok((StatusCode::OK, Json(conversations)))
@@ -189,11 +192,12 @@ pub async fn get_conversations(
 pub async fn mark_message_read(
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
     Path(message_id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
     state
         .message_service
-        .mark_read(user_id, message_id)
+        .mark_read(organization_id, user_id, message_id)
         .await?;
 
     Ok(StatusCode::OK)
@@ -7,6 +7,7 @@ use uuid::Uuid;
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Message {
     pub id: Uuid,
+    pub organization_id: Uuid,
     pub sender_id: Uuid,
     pub receiver_id: Uuid,
     pub content: String,
@@ -5,6 +5,8 @@ use crate::{
 use sqlx::PgPool;
 use uuid::Uuid;
 
+/// Conversations belong to an organization: the same two people talking in
+/// two organizations have two separate conversations.
 #[derive(Clone)]
 pub struct MessageRepository {
     pool: PgPool,
@@ -17,16 +19,18 @@ impl MessageRepository {
 
     pub async fn create(
         &self,
+        organization_id: Uuid,
         sender_id: Uuid,
         receiver_id: Uuid,
         content: &str,
         image_url: Option<&str>,
     ) -> Result<Message> {
         let message = sqlx::query_as::<_, Message>(
-            "INSERT INTO messages (sender_id, receiver_id, content, image_url)
-             VALUES ($1, $2, $3, $4)
+            "INSERT INTO messages (organization_id, sender_id, receiver_id, content, image_url)
+             VALUES ($1, $2, $3, $4, $5)
              RETURNING *",
         )
+        .bind(organization_id)
         .bind(sender_id)
         .bind(receiver_id)
         .bind(content)
@@ -39,6 +43,7 @@ impl MessageRepository {
 
     pub async fn find_conversation(
         &self,
+        organization_id: Uuid,
         user_id: Uuid,
         other_user_id: Uuid,
         limit: i64,
@@ -46,8 +51,9 @@ impl MessageRepository {
     ) -> Result<Vec<Message>> {
         let messages = sqlx::query_as::<_, Message>(
             "SELECT * FROM messages
-             WHERE (sender_id = $1 AND receiver_id = $2)
-                OR (sender_id = $2 AND receiver_id = $1)
+             WHERE organization_id = $5
+               AND ((sender_id = $1 AND receiver_id = $2)
+                OR (sender_id = $2 AND receiver_id = $1))
              ORDER BY created_at DESC
              LIMIT $3 OFFSET $4",
         )
@@ -55,13 +61,14 @@ impl MessageRepository {
         .bind(other_user_id)
         .bind(limit)
         .bind(offset)
+        .bind(organization_id)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(messages)
     }
 
-    pub async fn find_user_conversations(&self, user_id: Uuid) -> Result<Vec<ConversationUser>> {
+    pub async fn find_user_conversations(&self, organization_id: Uuid, user_id: Uuid) -> Result<Vec<ConversationUser>> {
         let conversations = sqlx::query_as::<_, ConversationUser>(
             "WITH latest_messages AS (
                 SELECT DISTINCT ON (
@@ -77,7 +84,7 @@ impl MessageRepository {
                 content AS last_message,
                 created_at AS last_message_time
                 FROM messages
-                WHERE sender_id = $1 OR receiver_id = $1
+                WHERE organization_id = $2 AND (sender_id = $1 OR receiver_id = $1)
                 ORDER BY
                     CASE
                         WHEN sender_id = $1 THEN receiver_id
@@ -88,7 +95,7 @@ impl MessageRepository {
             unread_counts AS (
                 SELECT sender_id AS user_id, COUNT(*) AS unread_count
                 FROM messages
-                WHERE receiver_id = $1 AND is_read = false
+                WHERE organization_id = $2 AND receiver_id = $1 AND is_read = false
                 GROUP BY sender_id
             )
             SELECT
@@ -104,20 +111,22 @@ impl MessageRepository {
             ORDER BY lm.last_message_time DESC",
         )
         .bind(user_id)
+        .bind(organization_id)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(conversations)
     }
 
-    pub async fn mark_as_read(&self, message_id: Uuid, user_id: Uuid) -> Result<()> {
+    pub async fn mark_as_read(&self, organization_id: Uuid, message_id: Uuid, user_id: Uuid) -> Result<()> {
         sqlx::query(
             "UPDATE messages
              SET is_read = true
-             WHERE id = $1 AND receiver_id = $2",
+             WHERE id = $1 AND receiver_id = $2 AND organization_id = $3",
         )
         .bind(message_id)
         .bind(user_id)
+        .bind(organization_id)
         .execute(&self.pool)
         .await?;
 
@@ -126,39 +135,45 @@ impl MessageRepository {
 
     pub async fn mark_conversation_as_read(
         &self,
+        organization_id: Uuid,
         user_id: Uuid,
         other_user_id: Uuid,
     ) -> Result<()> {
         sqlx::query(
             "UPDATE messages
              SET is_read = true
-             WHERE receiver_id = $1 AND sender_id = $2 AND is_read = false",
+             WHERE organization_id = $3 AND receiver_id = $1 AND sender_id = $2 AND is_read = false",
         )
         .bind(user_id)
         .bind(other_user_id)
+        .bind(organization_id)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    pub async fn count_unread(&self, user_id: Uuid) -> Result<i64> {
+    pub async fn count_unread(&self, organization_id: Uuid, user_id: Uuid) -> Result<i64> {
         let count: i64 = sqlx::query_scalar(
             "SELECT COUNT(*) FROM messages
-             WHERE receiver_id = $1 AND is_read = false",
+             WHERE organization_id = $2 AND receiver_id = $1 AND is_read = false",
         )
         .bind(user_id)
+        .bind(organization_id)
         .fetch_one(&self.pool)
         .await?;
 
         Ok(count)
     }
 
-    pub async fn find_by_id(&self, message_id: Uuid) -> Result<Option<Message>> {
-        let message = sqlx::query_as::<_, Message>("SELECT * FROM messages WHERE id = $1")
-            .bind(message_id)
-            .fetch_optional(&self.pool)
-            .await?;
+    pub async fn find_by_id(&self, organization_id: Uuid, message_id: Uuid) -> Result<Option<Message>> {
+        let message = sqlx::query_as::<_, Message>(
+            "SELECT * FROM messages WHERE id = $1 AND organization_id = $2"
+        )
+        .bind(message_id)
+        .bind(organization_id)
+        .fetch_optional(&self.pool)
+        .await?;
 
         Ok(message)
     }
@@ -16,33 +16,35 @@ impl MessageService {
 
     pub async fn send_message(
         &self,
+        organization_id: Uuid,
         sender_id: Uuid,
         payload: SendMessageRequest,
     ) -> Result<Message> {
         self.repo
-            .create(sender_id, payload.receiver_id, &payload.content, None)
+            .create(organization_id, sender_id, payload.receiver_id, &payload.content, None)
             .await
     }
 
     pub async fn get_conversation(
         &self,
+        organization_id: Uuid,
         user_id: Uuid,
         other_user_id: Uuid,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<Message>> {
-        self.repo.find_conversation(user_id, other_user_id, limit, offset).await
+        self.repo.find_conversation(organization_id, user_id, other_user_id, limit, offset).await
     }
 
-    pub async fn get_conversations(&self, user_id: Uuid) -> Result<Vec<crate::message::message_dto::ConversationUser>> {
-        self.repo.find_user_conversations(user_id).await
+    pub async fn get_conversations(&self, organization_id: Uuid, user_id: Uuid) -> Result<Vec<crate::message::message_dto::ConversationUser>> {
+        self.repo.find_user_conversations(organization_id, user_id).await
     }
 
-    pub async fn mark_read(&self, user_id: Uuid, message_id: Uuid) -> Result<()> {
-        self.repo.mark_as_read(message_id, user_id).await
+    pub async fn mark_read(&self, organization_id: Uuid, user_id: Uuid, message_id: Uuid) -> Result<()> {
+        self.repo.mark_as_read(organization_id, message_id, user_id).await
     }
 
-    pub async fn mark_conversation_as_read(&self, user_id: Uuid, other_user_id: Uuid) -> Result<()> {
-        self.repo.mark_conversation_as_read(user_id, other_user_id).await
+    pub async fn mark_conversation_as_read(&self, organization_id: Uuid, user_id: Uuid, other_user_id: Uuid) -> Result<()> {
+        self.repo.mark_conversation_as_read(organization_id, user_id, other_user_id).await
     }
 }
@@ -1,12 +1,20 @@
-use crate::{auth::verify_jwt, error::AppError, state::AppState};
+use crate::{
+    auth::{
+        auth_service::PERSONAL_ACCESS_TOKEN_PREFIX, impersonation::allows_while_impersonating,
+        token_scope::TokenScope, verify_jwt, TokenType,
+    },
+    error::AppError,
+    state::AppState,
+};
 use axum::{
     body::Body,
-    extract::{State, FromRequestParts},
+    extract::{State, FromRequestParts, OriginalUri},
     http::{Request, request::Parts},
     middleware::Next,
     response::Response,
     async_trait,
 };
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 pub async fn auth_middleware(
@@ -26,10 +34,75 @@ pub async fn auth_middleware(
        .ok_or(AppError::Unauthorized("Invalid credentials".to_string()))?;
 
 
-    let claims = verify_jwt(token, &state.config.jwt_secret)?;
-    
-   let user_id = Uuid::parse_str(&claims.sub)
-    .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+    // Nested routers see a shortened path; policies match on the full one
+    let path = req
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+
+    let (user_id, session_id, requested_organization, impersonation) = if token.starts_with(PERSONAL_ACCESS_TOKEN_PREFIX) {
+        let personal_token = state
+            .auth_service
+            .authenticate_personal_access_token(token)
+            .await?
+            .ok_or(AppError::Unauthorized("Invalid token".to_string()))?;
+
+        match TokenScope::required_for(req.method(), &path) {
+            None => {
+                return Err(AppError::Forbidden(
+                    "Personal access tokens cannot be used for this endpoint".to_string(),
+                ))
+            }
+            Some(scope) if !personal_token.scopes.iter().any(|s| s == scope.as_str()) => {
+                return Err(AppError::Forbidden(format!("Token lacks the {} scope", scope)));
+            }
+            Some(_) => {}
+        }
+
+        (personal_token.user_id, None, None, None)
+    } else {
+        let claims = verify_jwt(&state.jwt_keys, token, TokenType::Access).await?;
+
+        let user_id = Uuid::parse_str(&claims.sub)
+            .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+
+        if let Some(admin_id) = claims.act {
+            let session = state
+                .auth_service
+                .find_open_impersonation(claims.sid)
+                .await?
+                .filter(|session| session.admin_id == admin_id && session.user_id == user_id)
+                .ok_or(AppError::Unauthorized("Impersonation session has ended".to_string()))?;
+
+            let admin_active = state
+                .user_repository
+                .find_by_id(admin_id)
+                .await?
+                .is_some_and(|admin| admin.is_active);
+            if !admin_active {
+                return Err(AppError::Unauthorized("Impersonation session has ended".to_string()));
+            }
+
+            if !allows_while_impersonating(req.method(), &path) {
+                return Err(AppError::Forbidden("Not allowed while impersonating a user".to_string()));
+            }
+
+            let impersonation = Impersonation {
+                admin_id,
+                session_id: session.id,
+                expires_at: session.expires_at,
+            };
+            (user_id, None, claims.org, Some(impersonation))
+        } else {
+            // Access tokens die with the session they were issued for
+            if !state.refresh_token_repository.is_session_active(claims.sid).await? {
+                return Err(AppError::Unauthorized("Session has been revoked".to_string()));
+            }
+
+            (user_id, Some(claims.sid), claims.org, None)
+        }
+    };
 
     // Check if user is active
     let user = state
@@ -42,8 +115,21 @@ pub async fn auth_middleware(
         return Err(AppError::Forbidden("Account is deactivated".to_string()));
     }
 
+    if !user.email_verified && !state.config.unverified_user_policy.allows(req.method(), &path) {
+        return Err(AppError::Forbidden("Please verify your email address first".to_string()));
+    }
+
+    // Personal access tokens act in the organization the user joined first
+    let organization_id = state
+        .organization_service
+        .resolve_active(user_id, requested_organization)
+        .await?;
+
     req.extensions_mut().insert(user_id);
-    
+    req.extensions_mut().insert(CurrentSession(session_id));
+    req.extensions_mut().insert(CurrentOrganization(organization_id));
+    req.extensions_mut().insert(CurrentImpersonation(impersonation));
+
     Ok(next.run(req).await)
 }
 
@@ -69,3 +155,71 @@ where
     }
 }
 
+
+/// Session the access token was issued for; `None` for personal access tokens.
+#[derive(Clone, Copy)]
+pub struct CurrentSession(pub Option<Uuid>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for CurrentSession
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentSession>()
+            .copied()
+            .ok_or(AppError::Unauthorized("Invalid credentials".to_string()))
+    }
+}
+
+/// Organization the request acts in; every tenant-scoped query is filtered by it.
+#[derive(Clone, Copy)]
+pub struct CurrentOrganization(pub Uuid);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for CurrentOrganization
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentOrganization>()
+            .copied()
+            .ok_or(AppError::Unauthorized("Invalid credentials".to_string()))
+    }
+}
+
+/// An admin acting as the signed-in user through an impersonation token.
+#[derive(Clone, Copy)]
+pub struct Impersonation {
+    pub admin_id: Uuid,
+    pub session_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Set when the request comes from an impersonation token.
+#[derive(Clone, Copy)]
+pub struct CurrentImpersonation(pub Option<Impersonation>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for CurrentImpersonation
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentImpersonation>()
+            .copied()
+            .ok_or(AppError::Unauthorized("Invalid credentials".to_string()))
+    }
+}
@@ -0,0 +1,129 @@
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
+use ipnet::IpNet;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+use crate::error::AppError;
+use super::request_id::RequestId;
+
+/// Who is calling: recorded on sessions so users can recognise their devices,
+/// and on the audit log.
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    /// From the optional `X-Device-Name` header, e.g. "Ada's laptop"
+    pub device_name: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    /// Set by the `request_id` middleware
+    pub request_id: Option<String>,
+}
+
+/// Reverse proxies allowed to report the client address, from
+/// `Config::trusted_proxies`. Added to every request as an extension.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(pub Arc<Vec<IpNet>>);
+
+impl TrustedProxies {
+    fn contains(&self, ip: &IpAddr) -> bool {
+        self.0.iter().any(|proxy| proxy.contains(ip))
+    }
+}
+
+fn header(parts: &Parts, name: &str, max_len: usize) -> Option<String> {
+    parts
+        .headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().chars().take(max_len).collect::<String>())
+        .filter(|value| !value.is_empty())
+}
+
+/// The client address: the connecting peer, unless that is a trusted proxy.
+/// Proxies append to `X-Forwarded-For`, so it is read from the right and the
+/// first address that is not a trusted proxy wins; anything left of it may
+/// have been made up by the client.
+fn client_ip(
+    peer: Option<IpAddr>,
+    forwarded_for: Option<&str>,
+    real_ip: Option<&str>,
+    trusted: &TrustedProxies,
+) -> Option<IpAddr> {
+    let peer = peer?;
+    if !trusted.contains(&peer) {
+        return Some(peer);
+    }
+
+    match forwarded_for {
+        Some(forwarded_for) => {
+            let mut client = peer;
+            for hop in forwarded_for.rsplit(',') {
+                let Ok(hop) = hop.trim().parse::<IpAddr>() else { break };
+                client = hop;
+                if !trusted.contains(&hop) {
+                    break;
+                }
+            }
+            Some(client)
+        }
+        None => real_ip.and_then(|ip| ip.trim().parse().ok()).or(Some(peer)),
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        // Behind a proxy (e.g. Render) the forwarding headers carry the client address
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let trusted = parts.extensions.get::<TrustedProxies>().cloned().unwrap_or_default();
+        let ip_address = client_ip(
+            peer,
+            header(parts, "x-forwarded-for", 1024).as_deref(),
+            header(parts, "x-real-ip", 45).as_deref(),
+            &trusted,
+        )
+        .map(|ip| ip.to_string());
+
+        Ok(ClientInfo {
+            device_name: header(parts, "x-device-name", 100),
+            user_agent: header(parts, "user-agent", 512),
+            ip_address,
+            request_id: parts.extensions.get::<RequestId>().map(|RequestId(id)| id.clone()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwarding_headers_only_count_from_trusted_proxies() {
+        let trusted = TrustedProxies(Arc::new(vec!["10.0.0.0/8".parse().unwrap()]));
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+
+        // A direct client cannot pick its own address
+        assert_eq!(client_ip(Some(ip("203.0.113.7")), Some("1.2.3.4"), Some("1.2.3.4"), &trusted), Some(ip("203.0.113.7")));
+        // Behind the proxy, the entry the proxy appended wins over a forged one
+        assert_eq!(
+            client_ip(Some(ip("10.0.0.2")), Some("1.2.3.4, 198.51.100.9, 10.0.0.3"), None, &trusted),
+            Some(ip("198.51.100.9"))
+        );
+        assert_eq!(client_ip(Some(ip("10.0.0.2")), None, Some("198.51.100.9"), &trusted), Some(ip("198.51.100.9")));
+        assert_eq!(client_ip(Some(ip("10.0.0.2")), Some("garbage"), None, &trusted), Some(ip("10.0.0.2")));
+        assert_eq!(client_ip(None, Some("1.2.3.4"), None, &trusted), None);
+    }
+}
@@ -1,5 +1,11 @@
 pub mod auth;
-pub mod admin_middleware;
+pub mod client_info;
+pub mod permission;
+pub mod rate_limit;
+pub mod request_id;
 
-pub use auth::{auth_middleware, AuthUser};
-pub use admin_middleware::admin_middleware;
+pub use auth::{auth_middleware, AuthUser, CurrentImpersonation, CurrentOrganization, CurrentSession};
+pub use client_info::{ClientInfo, TrustedProxies};
+pub use permission::Authorized;
+pub use rate_limit::{rate_limit, RateLimiter};
+pub use request_id::request_id;
@@ -0,0 +1,55 @@
+use std::collections::HashSet;
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use uuid::Uuid;
+
+use crate::{error::AppError, role::permission::Permission, state::AppState};
+use super::auth::CurrentOrganization;
+
+/// The signed-in user together with the permissions their roles grant.
+///
+/// Needs `auth_middleware` in front. Permissions are read from the database
+/// on every request, so taking a role away applies immediately. What they
+/// unlock is limited to the active organization.
+pub struct Authorized {
+    pub user_id: Uuid,
+    pub organization_id: Uuid,
+    pub permissions: HashSet<Permission>,
+}
+
+impl Authorized {
+    pub fn has(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+
+    /// Fail with `403 Forbidden` unless the user holds `permission`.
+    pub fn require(&self, permission: Permission) -> Result<(), AppError> {
+        if self.has(permission) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden(format!("Missing the {} permission", permission)))
+        }
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for Authorized {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let user_id = parts
+            .extensions
+            .get::<Uuid>()
+            .copied()
+            .ok_or(AppError::Unauthorized("Invalid credentials".to_string()))?;
+        let CurrentOrganization(organization_id) = parts
+            .extensions
+            .get::<CurrentOrganization>()
+            .copied()
+            .ok_or(AppError::Unauthorized("Invalid credentials".to_string()))?;
+
+        let permissions = state.role_service.permissions_for_user(user_id).await?;
+
+        Ok(Authorized { user_id, organization_id, permissions })
+    }
+}
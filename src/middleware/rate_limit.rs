@@ -0,0 +1,101 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header::RETRY_AFTER, HeaderValue, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use dashmap::DashMap;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{error::AppError, middleware::ClientInfo};
+
+/// Windows kept before stale ones are swept out.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Fixed-window request limiter keyed by client IP address.
+///
+/// Counts live in memory, so with several instances each one enforces the
+/// limit on its own.
+#[derive(Clone)]
+pub struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    windows: Arc<DashMap<String, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            windows: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Count a request from `key`; once over the limit, returns how long until the window resets.
+    pub fn check(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        if self.windows.len() > MAX_TRACKED_CLIENTS {
+            self.windows.retain(|_, (started, _)| now.duration_since(*started) < self.window);
+        }
+
+        let mut entry = self.windows.entry(key.to_string()).or_insert((now, 0));
+        let (started, count) = entry.value_mut();
+
+        if now.duration_since(*started) >= self.window {
+            *started = now;
+            *count = 0;
+        }
+
+        if *count >= self.limit {
+            return Err(self.window - now.duration_since(*started));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+/// Answer `429 Too Many Requests` with `Retry-After` once a client exceeds the limiter.
+pub async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    client: ClientInfo,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let key = client.ip_address.unwrap_or_default();
+
+    match limiter.check(&key, Instant::now()) {
+        Ok(()) => next.run(req).await,
+        Err(retry_after) => {
+            let mut response = AppError::TooManyRequests("Too many requests, please slow down".to_string()).into_response();
+            let seconds = retry_after.as_secs().max(1);
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(seconds));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_each_client_per_window() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert!(limiter.check("10.0.0.1", start).is_ok());
+        assert!(limiter.check("10.0.0.1", start).is_ok());
+        assert_eq!(
+            limiter.check("10.0.0.1", start + Duration::from_secs(15)),
+            Err(Duration::from_secs(45))
+        );
+        assert!(limiter.check("10.0.0.2", start).is_ok());
+        assert!(limiter.check("10.0.0.1", start + Duration::from_secs(60)).is_ok());
+    }
+}
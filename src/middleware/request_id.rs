@@ -0,0 +1,43 @@
+use axum::{
+    body::Body,
+    http::{HeaderName, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest caller-supplied request id that is kept; longer ones are replaced.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Identifies one request across logs, the audit log and the response.
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Take the request id from the `X-Request-Id` header, set by a proxy or the
+/// client, or make one up, and echo it on the response.
+pub async fn request_id(mut req: Request<Body>, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| is_acceptable(value))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    req.extensions_mut().insert(RequestId(id.clone()));
+    let mut response = next.run(req).await;
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER.clone(), value);
+    }
+    response
+}
+
+fn is_acceptable(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LEN
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
@@ -1,4 +1,9 @@
-use crate::state::AppState;
+use crate::{
+    escalation::{escalation_models::EscalationStep, escalation_service::EscalationOutcome},
+    state::AppState,
+    user::user_locale::format_local_datetime,
+};
+use uuid::Uuid;
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::{error, info};
 
@@ -6,8 +11,12 @@ pub async fn start_notification_service(
     state: AppState,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let scheduler = JobScheduler::new().await?;
+    let state_for_escalations = state.clone();
+    let state_for_impersonations = state.clone();
+    let analytics_service = state.analytics_service.clone();
+    let jwt_keys = state.jwt_keys.clone();
 
-    // Run every minute to check for tasks with upcoming reminders
+    // Run every minute to check for due task reminders
     let job = Job::new_async("0 * * * * *", move |_uuid, _l| {
         let state = state.clone();
 
@@ -19,6 +28,60 @@ pub async fn start_notification_service(
     })?;
 
     scheduler.add(job).await?;
+
+    // Run every five minutes to escalate overdue tasks
+    let escalation_state = state_for_escalations;
+    let escalation_job = Job::new_async("0 */5 * * * *", move |_uuid, _l| {
+        let state = escalation_state.clone();
+
+        Box::pin(async move {
+            if let Err(e) = check_and_escalate_overdue_tasks(state).await {
+                error!("Error escalating overdue tasks: {:?}", e);
+            }
+        })
+    })?;
+
+    scheduler.add(escalation_job).await?;
+
+    // Run every minute to rotate JWT signing keys and load keys made by other instances
+    let key_job = Job::new_async("30 * * * * *", move |_uuid, _l| {
+        let jwt_keys = jwt_keys.clone();
+
+        Box::pin(async move {
+            if let Err(e) = jwt_keys.refresh().await {
+                error!("Error refreshing JWT signing keys: {:?}", e);
+            }
+        })
+    })?;
+
+    scheduler.add(key_job).await?;
+
+    // Run every hour to refresh the daily task snapshots behind the analytics trends
+    let snapshot_job = Job::new_async("0 15 * * * *", move |_uuid, _l| {
+        let analytics_service = analytics_service.clone();
+
+        Box::pin(async move {
+            match analytics_service.refresh_snapshots().await {
+                Ok(rows) => info!("Refreshed {} task snapshots", rows),
+                Err(e) => error!("Error refreshing task snapshots: {:?}", e),
+            }
+        })
+    })?;
+
+    scheduler.add(snapshot_job).await?;
+
+    // Run every minute to close and audit impersonation sessions that ran out
+    let impersonation_job = Job::new_async("45 * * * * *", move |_uuid, _l| {
+        let state = state_for_impersonations.clone();
+
+        Box::pin(async move {
+            if let Err(e) = end_expired_impersonations(state).await {
+                error!("Error ending expired impersonations: {:?}", e);
+            }
+        })
+    })?;
+
+    scheduler.add(impersonation_job).await?;
     scheduler.start().await?;
 
     info!("Notification service started");
@@ -28,36 +91,105 @@ pub async fn start_notification_service(
 async fn check_and_send_notifications(
     state: AppState,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Find tasks with reminders that are due and haven't been notified yet
-    let tasks = state.task_repository.find_due_reminders().await?;
-
-    for task in tasks {
-        // Create notification in database
-        let notification_message = format!(
-            "Reminder: {} is due soon!",
-            task.title
-        );
+    // Find reminders that are due and haven't been sent yet
+    let reminders = state.task_repository.find_due_reminders().await?;
+
+    for reminder in reminders {
+        // Describe the due date in the recipient's own time zone and locale
+        let recipient = state.user_repository.find_by_id(reminder.user_id).await?;
+        let notification_message = match (reminder.due_date, recipient) {
+            (Some(due_date), Some(recipient)) => format!(
+                "Reminder: {} is due {}",
+                reminder.title,
+                format_local_datetime(due_date, recipient.tz(), recipient.chrono_locale())
+            ),
+            _ => format!("Reminder: {} is due soon!", reminder.title),
+        };
 
         state.notification_repository.create(
-            task.user_id,
-            Some(task.id),
+            reminder.user_id,
+            Some(reminder.task_id),
             &notification_message,
         ).await?;
 
-        // Mark task as notified
-        state.task_repository.mark_as_notified(task.id).await?;
+        // Mark reminder as sent
+        state.task_repository.mark_as_notified(reminder.reminder_id).await?;
 
         // Broadcast to SSE clients
         let broadcast_message = format!(
             "{}:{}",
-            task.user_id,
+            reminder.user_id,
             notification_message
         );
         
         let _ = state.notification_tx.send(broadcast_message);
         
-        info!("Sent notification for task: {}", task.title);
+        info!("Sent reminder for task: {}", reminder.title);
+    }
+
+    Ok(())
+}
+
+async fn end_expired_impersonations(
+    state: AppState,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sessions = state.auth_service.end_expired_impersonations().await?;
+
+    for session in sessions {
+        info!("Impersonation session {} expired", session.id);
     }
 
     Ok(())
 }
+
+async fn check_and_escalate_overdue_tasks(
+    state: AppState,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let outcomes = state.escalation_service.evaluate_overdue(chrono::Utc::now()).await?;
+
+    for outcome in outcomes {
+        if let Some(task) = &outcome.updated_task {
+            let _ = state.task_tx.send((outcome.task.owner_id, task.clone()));
+        }
+
+        for recipient_id in &outcome.recipients {
+            let notification_message = escalation_message(&state, &outcome, *recipient_id).await?;
+
+            state.notification_repository.create(
+                *recipient_id,
+                Some(outcome.task.task_id),
+                &notification_message,
+            ).await?;
+
+            let _ = state.notification_tx.send(format!("{}:{}", recipient_id, notification_message));
+        }
+
+        info!("Escalation step {} fired for task: {}", outcome.step, outcome.task.title);
+    }
+
+    Ok(())
+}
+
+async fn escalation_message(
+    state: &AppState,
+    outcome: &EscalationOutcome,
+    recipient_id: Uuid,
+) -> Result<String, Box<dyn std::error::Error>> {
+    // Describe the due date in the recipient's own time zone and locale
+    let due = match state.user_repository.find_by_id(recipient_id).await? {
+        Some(recipient) => format_local_datetime(outcome.task.due_date, recipient.tz(), recipient.chrono_locale()),
+        None => outcome.task.due_date.to_rfc2822(),
+    };
+
+    let message = match (outcome.step, &outcome.updated_task) {
+        (EscalationStep::Overdue, _) => format!("Overdue: {} was due {}", outcome.task.title, due),
+        (EscalationStep::PriorityBumped, Some(task)) => format!(
+            "Overdue: {} was due {}, priority raised to {}",
+            outcome.task.title, due, task.priority
+        ),
+        (EscalationStep::PriorityBumped, None) => format!("Overdue: {} is still open since {}", outcome.task.title, due),
+        (EscalationStep::Escalated, _) => format!("Escalation: {} has been overdue since {}", outcome.task.title, due),
+    };
+
+    Ok(message)
+}
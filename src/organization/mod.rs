@@ -0,0 +1,6 @@
+// Declare submodules
+pub mod organization_models;
+pub mod organization_dto;
+pub mod organization_repository;
+pub mod organization_handlers;
+pub mod organization_service;
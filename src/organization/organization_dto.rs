@@ -0,0 +1,22 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use super::organization_models::OrgRole;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateOrganizationRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateOrganizationRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateOrganizationMemberRequest {
+    pub role: OrgRole,
+}
@@ -0,0 +1,234 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    audit::audit_models::{AuditAction, AuditTarget, NewAuditEntry},
+    error::{AppError, Result},
+    middleware::{AuthUser, ClientInfo, CurrentOrganization},
+    state::AppState,
+};
+use super::{
+    organization_dto::{CreateOrganizationRequest, UpdateOrganizationMemberRequest, UpdateOrganizationRequest},
+    organization_models::{Organization, OrganizationMember, OrganizationMembership},
+};
+
+/// List the organizations the current user belongs to
+#[utoipa::path(
+    get,
+    path = "/api/organizations",
+    responses(
+        (status = 200, description = "The user's organizations and roles", body = Vec<OrganizationMembership>),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "organizations",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_organizations(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<Vec<OrganizationMembership>>> {
+    let organizations = state.organization_service.list_mine(user_id).await?;
+    Ok(Json(organizations))
+}
+
+/// Create an organization owned by the current user
+#[utoipa::path(
+    post,
+    path = "/api/organizations",
+    request_body = CreateOrganizationRequest,
+    responses(
+        (status = 201, description = "Organization created", body = Organization),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "organizations",
+    security(("bearer_auth" = []))
+)]
+pub async fn create_organization(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Json(payload): Json<CreateOrganizationRequest>,
+) -> Result<impl IntoResponse> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let organization = state.organization_service.create(user_id, &payload.name).await?;
+    Ok((StatusCode::CREATED, Json(organization)))
+}
+
+/// Get the organization the current token acts in
+#[utoipa::path(
+    get,
+    path = "/api/organizations/current",
+    responses(
+        (status = 200, description = "The active organization and the user's role in it", body = OrganizationMembership),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "organizations",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_current_organization(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+) -> Result<Json<OrganizationMembership>> {
+    let membership = state.organization_service.get_membership(organization_id, user_id).await?;
+    Ok(Json(membership))
+}
+
+/// Rename an organization (admins and owners)
+#[utoipa::path(
+    put,
+    path = "/api/organizations/{organization_id}",
+    params(
+        ("organization_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = UpdateOrganizationRequest,
+    responses(
+        (status = 200, description = "Organization renamed", body = Organization),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only organization admins can rename it"),
+        (status = 404, description = "Organization not found")
+    ),
+    tag = "organizations",
+    security(("bearer_auth" = []))
+)]
+pub async fn update_organization(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(organization_id): Path<Uuid>,
+    Json(payload): Json<UpdateOrganizationRequest>,
+) -> Result<Json<Organization>> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let organization = state
+        .organization_service
+        .rename(organization_id, user_id, &payload.name)
+        .await?;
+    Ok(Json(organization))
+}
+
+/// List an organization's members
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{organization_id}/members",
+    params(
+        ("organization_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Members and their roles", body = Vec<OrganizationMember>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Organization not found")
+    ),
+    tag = "organizations",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_members(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<Vec<OrganizationMember>>> {
+    let members = state.organization_service.list_members(organization_id, user_id).await?;
+    Ok(Json(members))
+}
+
+/// Change a member's role (admins and owners; only owners manage owners)
+#[utoipa::path(
+    put,
+    path = "/api/organizations/{organization_id}/members/{user_id}",
+    params(
+        ("organization_id" = Uuid, Path, description = "Organization ID"),
+        ("user_id" = Uuid, Path, description = "Member's user ID")
+    ),
+    request_body = UpdateOrganizationMemberRequest,
+    responses(
+        (status = 200, description = "Role changed", body = OrganizationMember),
+        (status = 400, description = "An organization needs at least one owner"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only organization admins can manage members"),
+        (status = 404, description = "Organization or member not found")
+    ),
+    tag = "organizations",
+    security(("bearer_auth" = []))
+)]
+pub async fn update_member(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    client: ClientInfo,
+    Path((organization_id, member_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateOrganizationMemberRequest>,
+) -> Result<Json<OrganizationMember>> {
+    let (member, previous_role) = state
+        .organization_service
+        .change_member_role(organization_id, user_id, member_id, payload.role)
+        .await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                organization_id,
+                user_id,
+                AuditAction::OrganizationMemberRoleChanged,
+                AuditTarget::User,
+                member_id,
+            )
+            .with_before(json!({ "role": previous_role }))
+            .with_after(json!({ "role": member.role })),
+            &client,
+        )
+        .await;
+    Ok(Json(member))
+}
+
+/// Remove a member, or leave the organization by naming yourself
+#[utoipa::path(
+    delete,
+    path = "/api/organizations/{organization_id}/members/{user_id}",
+    params(
+        ("organization_id" = Uuid, Path, description = "Organization ID"),
+        ("user_id" = Uuid, Path, description = "Member's user ID")
+    ),
+    responses(
+        (status = 204, description = "Member removed"),
+        (status = 400, description = "An organization needs at least one owner"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only organization admins can manage members"),
+        (status = 404, description = "Organization or member not found")
+    ),
+    tag = "organizations",
+    security(("bearer_auth" = []))
+)]
+pub async fn remove_member(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    client: ClientInfo,
+    Path((organization_id, member_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    let role = state.organization_service.remove_member(organization_id, user_id, member_id).await?;
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                organization_id,
+                user_id,
+                AuditAction::OrganizationMemberRemoved,
+                AuditTarget::User,
+                member_id,
+            )
+            .with_before(json!({ "role": role })),
+            &client,
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// What a member may do in an organization; each role includes the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OrgRole {
+    /// Work on tasks and message the other members
+    Member,
+    /// Also rename the organization and manage members
+    Admin,
+    /// Also appoint owners; every organization keeps at least one
+    Owner,
+}
+
+impl OrgRole {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "member" => Some(OrgRole::Member),
+            "admin" => Some(OrgRole::Admin),
+            "owner" => Some(OrgRole::Owner),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for OrgRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrgRole::Member => write!(f, "member"),
+            OrgRole::Admin => write!(f, "admin"),
+            OrgRole::Owner => write!(f, "owner"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An organization as seen by one of its members.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct OrganizationMembership {
+    pub id: Uuid,
+    pub name: String,
+    pub role: String,
+    pub joined_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct OrganizationMember {
+    pub user_id: Uuid,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub role: String,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_org_roles_are_ordered_by_what_they_allow() {
+        assert!(OrgRole::Member < OrgRole::Admin);
+        assert!(OrgRole::Admin < OrgRole::Owner);
+        assert_eq!(OrgRole::parse(&OrgRole::Admin.to_string()), Some(OrgRole::Admin));
+        assert_eq!(OrgRole::parse("editor"), None);
+    }
+}
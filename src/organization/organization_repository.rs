@@ -0,0 +1,204 @@
+use crate::error::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::organization_models::{OrgRole, Organization, OrganizationMember, OrganizationMembership};
+
+#[derive(Clone)]
+pub struct OrganizationRepository {
+    pool: PgPool,
+}
+
+impl OrganizationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create an organization with `created_by` as its owner.
+    pub async fn create(&self, name: &str, created_by: Uuid) -> Result<Organization> {
+        let mut tx = self.pool.begin().await?;
+        let organization = self.create_with_tx(&mut tx, name, created_by).await?;
+        tx.commit().await?;
+
+        Ok(organization)
+    }
+
+    pub async fn create_with_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        name: &str,
+        created_by: Uuid,
+    ) -> Result<Organization> {
+        let organization = sqlx::query_as::<_, Organization>(
+            "INSERT INTO organizations (name, created_by) VALUES ($1, $2) RETURNING *"
+        )
+        .bind(name)
+        .bind(created_by)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO organization_members (organization_id, user_id, role, added_by)
+             VALUES ($1, $2, 'owner', $2)"
+        )
+        .bind(organization.id)
+        .bind(created_by)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(organization)
+    }
+
+    pub async fn update_name(&self, id: Uuid, name: &str) -> Result<Organization> {
+        let organization = sqlx::query_as::<_, Organization>(
+            "UPDATE organizations SET name = $2, updated_at = NOW() WHERE id = $1 RETURNING *"
+        )
+        .bind(id)
+        .bind(name)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(organization)
+    }
+
+    pub async fn find_for_user(&self, user_id: Uuid) -> Result<Vec<OrganizationMembership>> {
+        let organizations = sqlx::query_as::<_, OrganizationMembership>(
+            "SELECT o.id, o.name, m.role, m.joined_at, o.created_at
+             FROM organization_members m
+             JOIN organizations o ON o.id = m.organization_id
+             WHERE m.user_id = $1
+             ORDER BY m.joined_at ASC"
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(organizations)
+    }
+
+    pub async fn find_membership(&self, organization_id: Uuid, user_id: Uuid) -> Result<Option<OrganizationMembership>> {
+        let membership = sqlx::query_as::<_, OrganizationMembership>(
+            "SELECT o.id, o.name, m.role, m.joined_at, o.created_at
+             FROM organization_members m
+             JOIN organizations o ON o.id = m.organization_id
+             WHERE m.organization_id = $1 AND m.user_id = $2"
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(membership)
+    }
+
+    /// The user's role in the organization, or `None` if they are not a member.
+    pub async fn find_role(&self, organization_id: Uuid, user_id: Uuid) -> Result<Option<OrgRole>> {
+        let role: Option<String> = sqlx::query_scalar(
+            "SELECT role FROM organization_members WHERE organization_id = $1 AND user_id = $2"
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(role.as_deref().and_then(OrgRole::parse))
+    }
+
+    /// The organization the user joined first, used when nothing else was chosen.
+    pub async fn find_default_for_user(&self, user_id: Uuid) -> Result<Option<Uuid>> {
+        let organization_id: Option<Uuid> = sqlx::query_scalar(
+            "SELECT organization_id FROM organization_members
+             WHERE user_id = $1
+             ORDER BY joined_at ASC, organization_id ASC
+             LIMIT 1"
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(organization_id)
+    }
+
+    pub async fn find_members(&self, organization_id: Uuid) -> Result<Vec<OrganizationMember>> {
+        let members = sqlx::query_as::<_, OrganizationMember>(
+            "SELECT m.user_id, u.username, u.avatar_url, m.role, m.joined_at
+             FROM organization_members m
+             JOIN users u ON u.id = m.user_id
+             WHERE m.organization_id = $1 AND u.is_active = true
+             ORDER BY u.username ASC"
+        )
+        .bind(organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    pub async fn find_member(&self, organization_id: Uuid, user_id: Uuid) -> Result<Option<OrganizationMember>> {
+        let member = sqlx::query_as::<_, OrganizationMember>(
+            "SELECT m.user_id, u.username, u.avatar_url, m.role, m.joined_at
+             FROM organization_members m
+             JOIN users u ON u.id = m.user_id
+             WHERE m.organization_id = $1 AND m.user_id = $2"
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(member)
+    }
+
+    /// Change a member's role. Refuses (0 rows) to demote the last owner.
+    pub async fn update_member_role(&self, organization_id: Uuid, user_id: Uuid, role: OrgRole) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE organization_members SET role = $3
+             WHERE organization_id = $1 AND user_id = $2
+               AND (role <> 'owner' OR $3 = 'owner' OR (
+                   SELECT COUNT(*) FROM organization_members
+                   WHERE organization_id = $1 AND role = 'owner') > 1)"
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .bind(role.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Remove a member along with their memberships of the organization's
+    /// tasks. Refuses (0 rows) to remove the last owner. Tasks they own stay
+    /// in the organization.
+    pub async fn remove_member(&self, organization_id: Uuid, user_id: Uuid) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query(
+            "DELETE FROM organization_members
+             WHERE organization_id = $1 AND user_id = $2
+               AND (role <> 'owner' OR (
+                   SELECT COUNT(*) FROM organization_members
+                   WHERE organization_id = $1 AND role = 'owner') > 1)"
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            sqlx::query(
+                "DELETE FROM task_members
+                 WHERE user_id = $2
+                   AND task_id IN (SELECT id FROM tasks WHERE organization_id = $1)"
+            )
+            .bind(organization_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,194 @@
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, Result},
+    user::user_models::User,
+};
+use super::{
+    organization_models::{OrgRole, Organization, OrganizationMember, OrganizationMembership},
+    organization_repository::OrganizationRepository,
+};
+
+#[derive(Clone)]
+pub struct OrganizationService {
+    repo: OrganizationRepository,
+}
+
+impl OrganizationService {
+    pub fn new(repo: OrganizationRepository) -> Self {
+        Self { repo }
+    }
+
+    pub async fn list_mine(&self, user_id: Uuid) -> Result<Vec<OrganizationMembership>> {
+        self.repo.find_for_user(user_id).await
+    }
+
+    pub async fn create(&self, user_id: Uuid, name: &str) -> Result<Organization> {
+        let organization = self.repo.create(name.trim(), user_id).await?;
+        tracing::info!("Organization {} created by user {}", organization.id, user_id);
+        Ok(organization)
+    }
+
+    pub async fn get_membership(&self, organization_id: Uuid, user_id: Uuid) -> Result<OrganizationMembership> {
+        self.repo
+            .find_membership(organization_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Organization not found".into()))
+    }
+
+    pub async fn rename(&self, organization_id: Uuid, user_id: Uuid, name: &str) -> Result<Organization> {
+        let role = self.require_role(organization_id, user_id).await?;
+        if role < OrgRole::Admin {
+            return Err(AppError::Forbidden("Only organization admins can rename it".to_string()));
+        }
+
+        self.repo.update_name(organization_id, name.trim()).await
+    }
+
+    /// Members of the organization, which is also who a member can message
+    /// and share tasks with.
+    pub async fn list_members(&self, organization_id: Uuid, user_id: Uuid) -> Result<Vec<OrganizationMember>> {
+        self.require_role(organization_id, user_id).await?;
+        self.repo.find_members(organization_id).await
+    }
+
+    /// Fail unless `actor_id` may bring someone into the organization as
+    /// `role`: admins invite members and admins, only owners invite owners.
+    pub async fn check_can_invite(&self, organization_id: Uuid, actor_id: Uuid, role: OrgRole) -> Result<()> {
+        let actor_role = self.require_role(organization_id, actor_id).await?;
+        check_can_manage(actor_role, OrgRole::Member, Some(role))
+    }
+
+    /// Change a member's role; returns the member and the role they had.
+    pub async fn change_member_role(
+        &self,
+        organization_id: Uuid,
+        actor_id: Uuid,
+        member_id: Uuid,
+        role: OrgRole,
+    ) -> Result<(OrganizationMember, OrgRole)> {
+        let actor_role = self.require_role(organization_id, actor_id).await?;
+        let current = self.find_member_role(organization_id, member_id).await?;
+        check_can_manage(actor_role, current, Some(role))?;
+
+        if self.repo.update_member_role(organization_id, member_id, role).await? == 0 {
+            return Err(AppError::BadRequest("An organization needs at least one owner".to_string()));
+        }
+
+        Ok((self.find_member(organization_id, member_id).await?, current))
+    }
+
+    /// Remove a member, or leave when `member_id` is the actor. Returns the
+    /// role they had.
+    pub async fn remove_member(&self, organization_id: Uuid, actor_id: Uuid, member_id: Uuid) -> Result<OrgRole> {
+        let actor_role = self.require_role(organization_id, actor_id).await?;
+        let current = self.find_member_role(organization_id, member_id).await?;
+        if member_id != actor_id {
+            check_can_manage(actor_role, current, None)?;
+        }
+
+        if self.repo.remove_member(organization_id, member_id).await? == 0 {
+            return Err(AppError::BadRequest("An organization needs at least one owner".to_string()));
+        }
+        tracing::info!("User {} removed from organization {} by {}", member_id, organization_id, actor_id);
+
+        Ok(current)
+    }
+
+    pub async fn is_member(&self, organization_id: Uuid, user_id: Uuid) -> Result<bool> {
+        Ok(self.repo.find_role(organization_id, user_id).await?.is_some())
+    }
+
+    /// The user's role, failing with `404` for non-members so organizations
+    /// they do not belong to stay invisible.
+    pub async fn require_role(&self, organization_id: Uuid, user_id: Uuid) -> Result<OrgRole> {
+        self.repo
+            .find_role(organization_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Organization not found".into()))
+    }
+
+    /// The organization a request acts in: the one named by the token if the
+    /// user still belongs to it, else the one they joined first.
+    pub async fn resolve_active(&self, user_id: Uuid, requested: Option<Uuid>) -> Result<Uuid> {
+        if let Some(organization_id) = requested {
+            if self.is_member(organization_id, user_id).await? {
+                return Ok(organization_id);
+            }
+            return Err(AppError::Forbidden("You are not a member of this organization".to_string()));
+        }
+
+        self.repo
+            .find_default_for_user(user_id)
+            .await?
+            .ok_or_else(|| AppError::Forbidden("You do not belong to any organization".to_string()))
+    }
+
+    /// The organization `user_id` signs into by default, without creating one.
+    pub async fn find_default_for(&self, user_id: Uuid) -> Result<Option<Uuid>> {
+        self.repo.find_default_for_user(user_id).await
+    }
+
+    /// The organization to sign `user` into. Someone who belongs to none,
+    /// such as a new sign-up, gets a personal one.
+    pub async fn default_for(&self, user: &User) -> Result<Uuid> {
+        if let Some(organization_id) = self.repo.find_default_for_user(user.id).await? {
+            return Ok(organization_id);
+        }
+
+        let organization = self.create(user.id, &personal_name(user)).await?;
+        Ok(organization.id)
+    }
+
+    /// The personal organization of a user who is signing up.
+    pub async fn create_personal_with_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user: &User,
+    ) -> Result<Organization> {
+        self.repo.create_with_tx(tx, &personal_name(user), user.id).await
+    }
+
+    async fn find_member(&self, organization_id: Uuid, user_id: Uuid) -> Result<OrganizationMember> {
+        self.repo
+            .find_member(organization_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Member not found".into()))
+    }
+
+    async fn find_member_role(&self, organization_id: Uuid, user_id: Uuid) -> Result<OrgRole> {
+        self.repo
+            .find_role(organization_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Member not found".into()))
+    }
+}
+
+fn personal_name(user: &User) -> String {
+    format!("{}'s workspace", user.username)
+}
+
+/// Admins manage members and admins; only owners touch owners.
+fn check_can_manage(actor: OrgRole, target: OrgRole, new_role: Option<OrgRole>) -> Result<()> {
+    if actor < OrgRole::Admin {
+        return Err(AppError::Forbidden("Only organization admins can manage members".to_string()));
+    }
+    if actor < OrgRole::Owner && (target == OrgRole::Owner || new_role == Some(OrgRole::Owner)) {
+        return Err(AppError::Forbidden("Only organization owners can manage owners".to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_owners_manage_owners() {
+        assert!(check_can_manage(OrgRole::Member, OrgRole::Member, None).is_err());
+        assert!(check_can_manage(OrgRole::Admin, OrgRole::Member, Some(OrgRole::Admin)).is_ok());
+        assert!(check_can_manage(OrgRole::Admin, OrgRole::Member, Some(OrgRole::Owner)).is_err());
+        assert!(check_can_manage(OrgRole::Admin, OrgRole::Owner, None).is_err());
+        assert!(check_can_manage(OrgRole::Owner, OrgRole::Owner, Some(OrgRole::Member)).is_ok());
+    }
+}
@@ -0,0 +1,6 @@
+// Declare submodules
+pub mod public_link_models;
+pub mod public_link_dto;
+pub mod public_link_repository;
+pub mod public_link_handlers;
+pub mod public_link_service;
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use super::public_link_models::TaskPublicLink;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreatePublicLinkRequest {
+    /// Leave out for a link that works until revoked
+    #[validate(range(min = 1, max = 8760))]
+    pub expires_in_hours: Option<i64>,
+    /// Viewers have to give this password
+    #[validate(length(min = 8, max = 128))]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedPublicLinkResponse {
+    #[serde(flatten)]
+    pub link: TaskPublicLink,
+    /// Shown only once
+    pub token: String,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct PublicLinkPasswordRequest {
+    #[validate(length(min = 1, max = 128))]
+    pub password: String,
+}
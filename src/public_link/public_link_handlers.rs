@@ -0,0 +1,152 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::{AppError, Result},
+    middleware::{AuthUser, CurrentOrganization},
+    state::AppState,
+};
+use super::{
+    public_link_dto::{CreatePublicLinkRequest, PublicLinkPasswordRequest},
+    public_link_models::{PublicTaskView, TaskPublicLink},
+};
+
+/// Create a public read-only link to a task (owner only)
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{task_id}/public-links",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID")
+    ),
+    request_body = CreatePublicLinkRequest,
+    responses(
+        (status = 201, description = "Public link created; the token is only shown once", body = CreatedPublicLinkResponse),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only task owner can manage public links")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn create_public_link(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path(task_id): Path<Uuid>,
+    Json(payload): Json<CreatePublicLinkRequest>,
+) -> Result<impl IntoResponse> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let link = state.public_link_service.create_link(organization_id, task_id, user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(link)))
+}
+
+/// List a task's active public links with their view counts (owner only)
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{task_id}/public-links",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 200, description = "Active public links", body = Vec<TaskPublicLink>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only task owner can manage public links")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_public_links(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path(task_id): Path<Uuid>,
+) -> Result<Json<Vec<TaskPublicLink>>> {
+    let links = state.public_link_service.list_links(organization_id, task_id, user_id).await?;
+    Ok(Json(links))
+}
+
+/// Revoke a public link (owner only)
+#[utoipa::path(
+    delete,
+    path = "/api/tasks/{task_id}/public-links/{link_id}",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ("link_id" = Uuid, Path, description = "Public link ID")
+    ),
+    responses(
+        (status = 204, description = "Public link revoked"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only task owner can manage public links"),
+        (status = 404, description = "Public link not found")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_public_link(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path((task_id, link_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    state.public_link_service.revoke_link(organization_id, task_id, link_id, user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// View a task through a public link, without signing in
+#[utoipa::path(
+    get,
+    path = "/api/public/tasks/{token}",
+    params(
+        ("token" = String, Path, description = "Public link token")
+    ),
+    responses(
+        (status = 200, description = "Read-only view of the task", body = PublicTaskView),
+        (status = 401, description = "The link needs a password"),
+        (status = 404, description = "Link not found, revoked or expired"),
+        (status = 429, description = "Too many requests")
+    ),
+    tag = "public"
+)]
+pub async fn get_public_task(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Json<PublicTaskView>> {
+    let task = state.public_link_service.view(&token, None).await?;
+    Ok(Json(task))
+}
+
+/// View a task through a password-protected public link
+#[utoipa::path(
+    post,
+    path = "/api/public/tasks/{token}",
+    params(
+        ("token" = String, Path, description = "Public link token")
+    ),
+    request_body = PublicLinkPasswordRequest,
+    responses(
+        (status = 200, description = "Read-only view of the task", body = PublicTaskView),
+        (status = 401, description = "Wrong password"),
+        (status = 404, description = "Link not found, revoked or expired"),
+        (status = 429, description = "Too many requests")
+    ),
+    tag = "public"
+)]
+pub async fn unlock_public_task(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    Json(payload): Json<PublicLinkPasswordRequest>,
+) -> Result<Json<PublicTaskView>> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let task = state.public_link_service.view(&token, Some(&payload.password)).await?;
+    Ok(Json(task))
+}
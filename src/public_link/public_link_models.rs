@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TaskPublicLink {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub created_by: Option<Uuid>,
+    /// Whether viewers have to give a password
+    pub password_protected: bool,
+    pub view_count: i64,
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    /// Never expires when not set
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What is needed to let someone through a link.
+#[derive(Debug, Clone, FromRow)]
+pub struct PublicLinkAccess {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub organization_id: Uuid,
+    pub password_hash: Option<String>,
+}
+
+/// The part of a task that is safe to show to anyone holding the link.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PublicTaskView {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: String,
+    pub priority: String,
+    pub due_date: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::Result;
+use super::public_link_models::{PublicLinkAccess, TaskPublicLink};
+
+/// Columns of `TaskPublicLink`; the password hash itself never leaves the repository.
+const LINK_COLUMNS: &str = "id, task_id, created_by, password_hash IS NOT NULL AS password_protected, \
+     view_count, last_viewed_at, expires_at, revoked_at, created_at";
+
+#[derive(Clone)]
+pub struct PublicLinkRepository {
+    pool: PgPool,
+}
+
+impl PublicLinkRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        task_id: Uuid,
+        created_by: Uuid,
+        token_hash: &str,
+        password_hash: Option<&str>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<TaskPublicLink> {
+        let link = sqlx::query_as::<_, TaskPublicLink>(&format!(
+            "INSERT INTO task_public_links (task_id, created_by, token_hash, password_hash, expires_at)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING {}",
+            LINK_COLUMNS
+        ))
+        .bind(task_id)
+        .bind(created_by)
+        .bind(token_hash)
+        .bind(password_hash)
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(link)
+    }
+
+    pub async fn find_by_task(&self, task_id: Uuid) -> Result<Vec<TaskPublicLink>> {
+        let links = sqlx::query_as::<_, TaskPublicLink>(&format!(
+            "SELECT {} FROM task_public_links
+             WHERE task_id = $1 AND revoked_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())
+             ORDER BY created_at DESC",
+            LINK_COLUMNS
+        ))
+        .bind(task_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(links)
+    }
+
+    pub async fn revoke(&self, task_id: Uuid, link_id: Uuid) -> Result<Option<TaskPublicLink>> {
+        let link = sqlx::query_as::<_, TaskPublicLink>(&format!(
+            "UPDATE task_public_links SET revoked_at = NOW()
+             WHERE id = $1 AND task_id = $2 AND revoked_at IS NULL
+             RETURNING {}",
+            LINK_COLUMNS
+        ))
+        .bind(link_id)
+        .bind(task_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(link)
+    }
+
+    pub async fn find_active(&self, token_hash: &str) -> Result<Option<PublicLinkAccess>> {
+        let link = sqlx::query_as::<_, PublicLinkAccess>(
+            "SELECT l.id, l.task_id, t.organization_id, l.password_hash
+             FROM task_public_links l
+             JOIN tasks t ON t.id = l.task_id
+             WHERE l.token_hash = $1 AND l.revoked_at IS NULL AND (l.expires_at IS NULL OR l.expires_at > NOW())"
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(link)
+    }
+
+    pub async fn record_view(&self, link_id: Uuid) -> Result<()> {
+        sqlx::query(
+            "UPDATE task_public_links SET view_count = view_count + 1, last_viewed_at = NOW() WHERE id = $1"
+        )
+        .bind(link_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
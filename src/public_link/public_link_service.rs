@@ -0,0 +1,147 @@
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    auth::{
+        password::{hash_password, verify_password},
+        secure_token::{generate_token, hash_token},
+    },
+    error::{AppError, Result},
+    state::Config,
+    task::task_repository::TaskRepository,
+};
+use super::{
+    public_link_dto::{CreatePublicLinkRequest, CreatedPublicLinkResponse},
+    public_link_models::{PublicTaskView, TaskPublicLink},
+    public_link_repository::PublicLinkRepository,
+};
+
+#[derive(Clone)]
+pub struct PublicLinkService {
+    repo: PublicLinkRepository,
+    task_repo: TaskRepository,
+    public_url: String,
+}
+
+impl PublicLinkService {
+    pub fn new(repo: PublicLinkRepository, task_repo: TaskRepository, config: &Config) -> Self {
+        Self {
+            repo,
+            task_repo,
+            public_url: config.task_public_link_url.clone(),
+        }
+    }
+
+    pub async fn create_link(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        requesting_user: Uuid,
+        payload: CreatePublicLinkRequest,
+    ) -> Result<CreatedPublicLinkResponse> {
+        self.require_owner(organization_id, task_id, requesting_user).await?;
+
+        let token = generate_token();
+        let password_hash = payload.password.as_deref().map(hash_password).transpose()?;
+        let expires_at = payload.expires_in_hours.map(|hours| Utc::now() + Duration::hours(hours));
+        let link = self
+            .repo
+            .create(task_id, requesting_user, &hash_token(&token), password_hash.as_deref(), expires_at)
+            .await?;
+
+        let _ = self.task_repo.log_task_activity(
+            organization_id,
+            task_id,
+            requesting_user,
+            "public_link_created",
+            Some(serde_json::json!({
+                "public_link_id": link.id,
+                "expires_at": link.expires_at,
+                "password_protected": link.password_protected,
+            })),
+        ).await;
+
+        Ok(CreatedPublicLinkResponse {
+            url: format!("{}?token={}", self.public_url, token),
+            link,
+            token,
+        })
+    }
+
+    pub async fn list_links(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        requesting_user: Uuid,
+    ) -> Result<Vec<TaskPublicLink>> {
+        self.require_owner(organization_id, task_id, requesting_user).await?;
+        self.repo.find_by_task(task_id).await
+    }
+
+    pub async fn revoke_link(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        link_id: Uuid,
+        requesting_user: Uuid,
+    ) -> Result<()> {
+        self.require_owner(organization_id, task_id, requesting_user).await?;
+
+        let link = self
+            .repo
+            .revoke(task_id, link_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Public link not found".into()))?;
+
+        let _ = self.task_repo.log_task_activity(
+            organization_id,
+            task_id,
+            requesting_user,
+            "public_link_revoked",
+            Some(serde_json::json!({"public_link_id": link.id, "view_count": link.view_count})),
+        ).await;
+
+        Ok(())
+    }
+
+    /// Show the task behind a link to anyone, counting the view.
+    pub async fn view(&self, token: &str, password: Option<&str>) -> Result<PublicTaskView> {
+        let link = self
+            .repo
+            .find_active(&hash_token(token))
+            .await?
+            .ok_or_else(|| AppError::NotFound("Link not found or expired".into()))?;
+
+        if let Some(password_hash) = &link.password_hash {
+            let password = password
+                .ok_or_else(|| AppError::Unauthorized("This link needs a password".to_string()))?;
+            if !verify_password(password, password_hash)? {
+                return Err(AppError::Unauthorized("Wrong password".to_string()));
+            }
+        }
+
+        let task = self
+            .task_repo
+            .find_by_id_any(link.organization_id, link.task_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Link not found or expired".into()))?;
+        self.repo.record_view(link.id).await?;
+
+        Ok(PublicTaskView {
+            title: task.title,
+            description: task.description,
+            status: task.status,
+            priority: task.priority,
+            due_date: task.due_date,
+            created_at: task.created_at,
+            updated_at: task.updated_at,
+        })
+    }
+
+    async fn require_owner(&self, organization_id: Uuid, task_id: Uuid, user_id: Uuid) -> Result<()> {
+        if !self.task_repo.is_task_owner(organization_id, task_id, user_id).await? {
+            return Err(AppError::Forbidden("Only task owner can manage public links".to_string()));
+        }
+        Ok(())
+    }
+}
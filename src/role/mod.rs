@@ -0,0 +1,7 @@
+// Declare submodules
+pub mod permission;
+pub mod role_models;
+pub mod role_dto;
+pub mod role_repository;
+pub mod role_handlers;
+pub mod role_service;
@@ -0,0 +1,79 @@
+/// Something a role allows beyond managing one's own account and tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Permission {
+    /// List users and look at their accounts and sessions
+    UsersRead,
+    /// Edit other users' profiles
+    UsersUpdate,
+    /// Activate and deactivate accounts
+    UsersDeactivate,
+    UsersDelete,
+    /// Reset two-factor authentication, revoke sessions and lift lockouts
+    UsersManageSecurity,
+    /// Act as a user through a short-lived token, without their account settings
+    UsersImpersonate,
+    RolesRead,
+    /// Give roles to users and take them away
+    RolesAssign,
+    /// Create, change and delete roles
+    RolesManage,
+    /// Read any task, not only owned or shared ones, and anyone's workload
+    TasksReadAny,
+    /// Read and export the audit log
+    AuditRead,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 11] = [
+        Permission::UsersRead,
+        Permission::UsersUpdate,
+        Permission::UsersDeactivate,
+        Permission::UsersDelete,
+        Permission::UsersManageSecurity,
+        Permission::UsersImpersonate,
+        Permission::RolesRead,
+        Permission::RolesAssign,
+        Permission::RolesManage,
+        Permission::TasksReadAny,
+        Permission::AuditRead,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|permission| permission.as_str() == value)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UsersRead => "users.read",
+            Self::UsersUpdate => "users.update",
+            Self::UsersDeactivate => "users.deactivate",
+            Self::UsersDelete => "users.delete",
+            Self::UsersManageSecurity => "users.manage_security",
+            Self::UsersImpersonate => "users.impersonate",
+            Self::RolesRead => "roles.read",
+            Self::RolesAssign => "roles.assign",
+            Self::RolesManage => "roles.manage",
+            Self::TasksReadAny => "tasks.read_any",
+            Self::AuditRead => "audit.read",
+        }
+    }
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_round_trips_every_permission() {
+        for permission in Permission::ALL {
+            assert_eq!(Permission::parse(permission.as_str()), Some(permission));
+        }
+        assert_eq!(Permission::parse("admin"), None);
+    }
+}
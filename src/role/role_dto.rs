@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+fn validate_role_name(name: &str) -> Result<(), ValidationError> {
+    if name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-') {
+        Ok(())
+    } else {
+        Err(ValidationError::new("role_name")
+            .with_message("may only contain lowercase letters, digits, '_' and '-'".into()))
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateRoleRequest {
+    #[validate(length(min = 2, max = 50), custom(function = "validate_role_name"))]
+    pub name: String,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+    /// Permission names such as `users.read`
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateRoleRequest {
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+    /// Replaces the role's permissions when set
+    pub permissions: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserPermissionsResponse {
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+}
@@ -0,0 +1,331 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    audit::audit_models::{AuditAction, AuditTarget, NewAuditEntry},
+    error::{AppError, Result},
+    middleware::{AuthUser, Authorized, ClientInfo},
+    state::AppState,
+};
+use super::{
+    permission::Permission,
+    role_dto::{CreateRoleRequest, UpdateRoleRequest, UserPermissionsResponse},
+    role_models::{Role, RoleAssignment},
+};
+
+/// Get the current user's roles and permissions
+#[utoipa::path(
+    get,
+    path = "/api/users/me/permissions",
+    responses(
+        (status = 200, description = "Roles and the permissions they grant", body = UserPermissionsResponse),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_my_permissions(
+    State(state): State<AppState>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<UserPermissionsResponse>> {
+    let permissions = state.role_service.get_my_permissions(user_id).await?;
+    Ok(Json(permissions))
+}
+
+/// List roles and their permissions (requires `roles.read`)
+#[utoipa::path(
+    get,
+    path = "/api/admin/roles",
+    responses(
+        (status = 200, description = "All roles", body = Vec<Role>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission")
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_roles(
+    State(state): State<AppState>,
+    authorized: Authorized,
+) -> Result<Json<Vec<Role>>> {
+    authorized.require(Permission::RolesRead)?;
+
+    let roles = state.role_service.list_roles().await?;
+    Ok(Json(roles))
+}
+
+/// Create a role (requires `roles.manage`)
+#[utoipa::path(
+    post,
+    path = "/api/admin/roles",
+    request_body = CreateRoleRequest,
+    responses(
+        (status = 201, description = "Role created", body = Role),
+        (status = 400, description = "Validation error or role exists"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission")
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn create_role(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    client: ClientInfo,
+    Json(payload): Json<CreateRoleRequest>,
+) -> Result<impl IntoResponse> {
+    authorized.require(Permission::RolesManage)?;
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let role = state.role_service.create_role(&authorized.permissions, payload).await?;
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                authorized.organization_id,
+                authorized.user_id,
+                AuditAction::RoleCreated,
+                AuditTarget::Role,
+                &role.name,
+            )
+            .with_after(&role),
+            &client,
+        )
+        .await;
+
+    Ok((StatusCode::CREATED, Json(role)))
+}
+
+/// Change a role's description or permissions (requires `roles.manage`)
+#[utoipa::path(
+    put,
+    path = "/api/admin/roles/{role_name}",
+    params(
+        ("role_name" = String, Path, description = "Role name")
+    ),
+    request_body = UpdateRoleRequest,
+    responses(
+        (status = 200, description = "Role updated", body = Role),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission or built-in role"),
+        (status = 404, description = "Role not found")
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn update_role(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    client: ClientInfo,
+    Path(role_name): Path<String>,
+    Json(payload): Json<UpdateRoleRequest>,
+) -> Result<Json<Role>> {
+    authorized.require(Permission::RolesManage)?;
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let before = state.role_service.find_role(&role_name).await?;
+    let role = state
+        .role_service
+        .update_role(&authorized.permissions, &role_name, payload)
+        .await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                authorized.organization_id,
+                authorized.user_id,
+                AuditAction::RoleUpdated,
+                AuditTarget::Role,
+                &role.name,
+            )
+            .with_before(before)
+            .with_after(&role),
+            &client,
+        )
+        .await;
+    Ok(Json(role))
+}
+
+/// Delete a role, taking it away from everyone holding it (requires `roles.manage`)
+#[utoipa::path(
+    delete,
+    path = "/api/admin/roles/{role_name}",
+    params(
+        ("role_name" = String, Path, description = "Role name")
+    ),
+    responses(
+        (status = 204, description = "Role deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission or built-in role"),
+        (status = 404, description = "Role not found")
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_role(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    client: ClientInfo,
+    Path(role_name): Path<String>,
+) -> Result<StatusCode> {
+    authorized.require(Permission::RolesManage)?;
+
+    let role = state.role_service.delete_role(&role_name).await?;
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                authorized.organization_id,
+                authorized.user_id,
+                AuditAction::RoleDeleted,
+                AuditTarget::Role,
+                &role.name,
+            )
+            .with_before(role),
+            &client,
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List a user's roles (requires `roles.read`)
+#[utoipa::path(
+    get,
+    path = "/api/admin/users/{user_id}/roles",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "The user's roles", body = Vec<RoleAssignment>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_user_roles(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<Vec<RoleAssignment>>> {
+    authorized.require(Permission::RolesRead)?;
+
+    let roles = state.role_service.get_user_roles(user_id).await?;
+    Ok(Json(roles))
+}
+
+/// Give a user a role (requires `roles.assign` and every permission of the role)
+#[utoipa::path(
+    put,
+    path = "/api/admin/users/{user_id}/roles/{role_name}",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID"),
+        ("role_name" = String, Path, description = "Role name")
+    ),
+    responses(
+        (status = 200, description = "The user's roles after the change", body = Vec<RoleAssignment>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission"),
+        (status = 404, description = "User or role not found")
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn assign_role(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    client: ClientInfo,
+    Path((user_id, role_name)): Path<(Uuid, String)>,
+) -> Result<Json<Vec<RoleAssignment>>> {
+    authorized.require(Permission::RolesAssign)?;
+
+    let before = state.role_service.get_user_roles(user_id).await?;
+    let roles = state
+        .role_service
+        .assign_role(authorized.user_id, &authorized.permissions, user_id, &role_name)
+        .await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                authorized.organization_id,
+                authorized.user_id,
+                AuditAction::RoleAssigned,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_before(json!({ "roles": role_names(&before) }))
+            .with_after(json!({ "roles": role_names(&roles) })),
+            &client,
+        )
+        .await;
+    Ok(Json(roles))
+}
+
+/// Take a role away from a user (requires `roles.assign` and every permission of the role)
+#[utoipa::path(
+    delete,
+    path = "/api/admin/users/{user_id}/roles/{role_name}",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID"),
+        ("role_name" = String, Path, description = "Role name")
+    ),
+    responses(
+        (status = 200, description = "The user's roles after the change", body = Vec<RoleAssignment>),
+        (status = 400, description = "Nobody would be left to assign roles"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission"),
+        (status = 404, description = "Role not found or not held by the user")
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn unassign_role(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    client: ClientInfo,
+    Path((user_id, role_name)): Path<(Uuid, String)>,
+) -> Result<Json<Vec<RoleAssignment>>> {
+    authorized.require(Permission::RolesAssign)?;
+
+    let before = state.role_service.get_user_roles(user_id).await?;
+    let roles = state
+        .role_service
+        .unassign_role(&authorized.permissions, user_id, &role_name)
+        .await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                authorized.organization_id,
+                authorized.user_id,
+                AuditAction::RoleUnassigned,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_before(json!({ "roles": role_names(&before) }))
+            .with_after(json!({ "roles": role_names(&roles) })),
+            &client,
+        )
+        .await;
+    Ok(Json(roles))
+}
+
+fn role_names(roles: &[RoleAssignment]) -> Vec<&str> {
+    roles.iter().map(|role| role.role_name.as_str()).collect()
+}
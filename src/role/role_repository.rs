@@ -0,0 +1,159 @@
+use crate::error::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::role_models::{Role, RoleAssignment};
+
+#[derive(Clone)]
+pub struct RoleRepository {
+    pool: PgPool,
+}
+
+impl RoleRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_all(&self) -> Result<Vec<Role>> {
+        let roles = sqlx::query_as::<_, Role>("SELECT * FROM roles ORDER BY built_in DESC, name")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(roles)
+    }
+
+    pub async fn find_by_name(&self, name: &str) -> Result<Option<Role>> {
+        let role = sqlx::query_as::<_, Role>("SELECT * FROM roles WHERE name = $1")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(role)
+    }
+
+    pub async fn create(&self, name: &str, description: &str, permissions: &[String]) -> Result<Role> {
+        let role = sqlx::query_as::<_, Role>(
+            "INSERT INTO roles (name, description, permissions) VALUES ($1, $2, $3) RETURNING *"
+        )
+        .bind(name)
+        .bind(description)
+        .bind(permissions)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(role)
+    }
+
+    pub async fn update(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        permissions: Option<&[String]>,
+    ) -> Result<Role> {
+        let role = sqlx::query_as::<_, Role>(
+            r#"
+            UPDATE roles
+            SET description = COALESCE($2, description),
+                permissions = COALESCE($3, permissions),
+                updated_at = NOW()
+            WHERE name = $1
+            RETURNING *
+            "#
+        )
+        .bind(name)
+        .bind(description)
+        .bind(permissions)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(role)
+    }
+
+    pub async fn delete(&self, name: &str) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM roles WHERE name = $1 AND NOT built_in")
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Permission names granted by any of the user's roles.
+    pub async fn find_user_permissions(&self, user_id: Uuid) -> Result<Vec<String>> {
+        let permissions = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT unnest(r.permissions)
+            FROM user_roles ur
+            JOIN roles r ON r.name = ur.role_name
+            WHERE ur.user_id = $1
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(permissions)
+    }
+
+    pub async fn find_user_roles(&self, user_id: Uuid) -> Result<Vec<RoleAssignment>> {
+        let roles = sqlx::query_as::<_, RoleAssignment>(
+            "SELECT role_name, assigned_by, assigned_at FROM user_roles WHERE user_id = $1 ORDER BY role_name"
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(roles)
+    }
+
+    pub async fn assign(&self, user_id: Uuid, role_name: &str, assigned_by: Uuid) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO user_roles (user_id, role_name, assigned_by) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING"
+        )
+        .bind(user_id)
+        .bind(role_name)
+        .bind(assigned_by)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn unassign(&self, user_id: Uuid, role_name: &str) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM user_roles WHERE user_id = $1 AND role_name = $2")
+            .bind(user_id)
+            .bind(role_name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Active users holding `permission` through a role other than
+    /// `except_role` of `except_user`.
+    pub async fn count_holders_except(
+        &self,
+        permission: &str,
+        except_user: Uuid,
+        except_role: &str,
+    ) -> Result<i64> {
+        let count = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(DISTINCT ur.user_id)
+            FROM user_roles ur
+            JOIN roles r ON r.name = ur.role_name
+            JOIN users u ON u.id = ur.user_id
+            WHERE $1 = ANY(r.permissions)
+              AND u.is_active
+              AND NOT (ur.user_id = $2 AND ur.role_name = $3)
+            "#
+        )
+        .bind(permission)
+        .bind(except_user)
+        .bind(except_role)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, Result},
+    user::user_repository::UserRepository,
+};
+use super::{
+    permission::Permission,
+    role_dto::{CreateRoleRequest, UpdateRoleRequest, UserPermissionsResponse},
+    role_models::{Role, RoleAssignment},
+    role_repository::RoleRepository,
+};
+
+#[derive(Clone)]
+pub struct RoleService {
+    repo: RoleRepository,
+    user_repo: UserRepository,
+}
+
+impl RoleService {
+    pub fn new(repo: RoleRepository, user_repo: UserRepository) -> Self {
+        Self { repo, user_repo }
+    }
+
+    /// Everything the user's roles allow; names no longer known are ignored.
+    pub async fn permissions_for_user(&self, user_id: Uuid) -> Result<HashSet<Permission>> {
+        let names = self.repo.find_user_permissions(user_id).await?;
+        Ok(names.iter().filter_map(|name| Permission::parse(name)).collect())
+    }
+
+    pub async fn get_my_permissions(&self, user_id: Uuid) -> Result<UserPermissionsResponse> {
+        let roles = self.repo.find_user_roles(user_id).await?;
+        let mut permissions: Vec<String> = self
+            .permissions_for_user(user_id)
+            .await?
+            .into_iter()
+            .map(|permission| permission.to_string())
+            .collect();
+        permissions.sort();
+
+        Ok(UserPermissionsResponse {
+            roles: roles.into_iter().map(|role| role.role_name).collect(),
+            permissions,
+        })
+    }
+
+    pub async fn list_roles(&self) -> Result<Vec<Role>> {
+        self.repo.find_all().await
+    }
+
+    pub async fn create_role(&self, actor: &HashSet<Permission>, payload: CreateRoleRequest) -> Result<Role> {
+        let permissions = Self::check_grantable(actor, &payload.permissions)?;
+
+        if self.repo.find_by_name(&payload.name).await?.is_some() {
+            return Err(AppError::BadRequest("Role already exists".to_string()));
+        }
+
+        self.repo
+            .create(&payload.name, payload.description.as_deref().unwrap_or(""), &permissions)
+            .await
+    }
+
+    pub async fn update_role(
+        &self,
+        actor: &HashSet<Permission>,
+        name: &str,
+        payload: UpdateRoleRequest,
+    ) -> Result<Role> {
+        let role = self.find_role(name).await?;
+        if role.built_in {
+            return Err(AppError::Forbidden("Built-in roles cannot be changed".to_string()));
+        }
+
+        let permissions = match &payload.permissions {
+            Some(permissions) => Some(Self::check_grantable(actor, permissions)?),
+            None => None,
+        };
+
+        self.repo
+            .update(name, payload.description.as_deref(), permissions.as_deref())
+            .await
+    }
+
+    /// Delete a role; returns it as it was.
+    pub async fn delete_role(&self, name: &str) -> Result<Role> {
+        let role = self.find_role(name).await?;
+        if role.built_in {
+            return Err(AppError::Forbidden("Built-in roles cannot be deleted".to_string()));
+        }
+
+        self.repo.delete(name).await?;
+        Ok(role)
+    }
+
+    pub async fn get_user_roles(&self, user_id: Uuid) -> Result<Vec<RoleAssignment>> {
+        self.ensure_user_exists(user_id).await?;
+        self.repo.find_user_roles(user_id).await
+    }
+
+    /// Give `user_id` a role; the actor has to hold every permission it grants.
+    pub async fn assign_role(
+        &self,
+        actor_id: Uuid,
+        actor: &HashSet<Permission>,
+        user_id: Uuid,
+        role_name: &str,
+    ) -> Result<Vec<RoleAssignment>> {
+        self.ensure_user_exists(user_id).await?;
+        let role = self.find_role(role_name).await?;
+        Self::check_grantable(actor, &role.permissions)?;
+
+        self.repo.assign(user_id, &role.name, actor_id).await?;
+        self.repo.find_user_roles(user_id).await
+    }
+
+    /// Take a role away, unless that leaves nobody able to assign roles.
+    pub async fn unassign_role(
+        &self,
+        actor: &HashSet<Permission>,
+        user_id: Uuid,
+        role_name: &str,
+    ) -> Result<Vec<RoleAssignment>> {
+        let role = self.find_role(role_name).await?;
+        Self::check_grantable(actor, &role.permissions)?;
+
+        let assign = Permission::RolesAssign.as_str();
+        if role.permissions.iter().any(|permission| permission == assign)
+            && self.repo.count_holders_except(assign, user_id, &role.name).await? == 0
+        {
+            return Err(AppError::BadRequest(
+                "At least one active user must keep the permission to assign roles".to_string(),
+            ));
+        }
+
+        if self.repo.unassign(user_id, &role.name).await? == 0 {
+            return Err(AppError::NotFound("User does not have this role".to_string()));
+        }
+        self.repo.find_user_roles(user_id).await
+    }
+
+    pub async fn find_role(&self, name: &str) -> Result<Role> {
+        self.repo
+            .find_by_name(name)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Role not found".into()))
+    }
+
+    async fn ensure_user_exists(&self, user_id: Uuid) -> Result<()> {
+        self.user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+        Ok(())
+    }
+
+    /// Parse permission names, refusing unknown ones and any the actor lacks,
+    /// so nobody can hand out more than they have.
+    fn check_grantable(actor: &HashSet<Permission>, names: &[String]) -> Result<Vec<String>> {
+        let mut permissions = Vec::with_capacity(names.len());
+
+        for name in names {
+            let permission = Permission::parse(name)
+                .ok_or_else(|| AppError::Validation(format!("Unknown permission: {}", name)))?;
+            if !actor.contains(&permission) {
+                return Err(AppError::Forbidden(format!(
+                    "Cannot grant the {} permission without holding it",
+                    permission
+                )));
+            }
+            if !permissions.contains(&permission) {
+                permissions.push(permission);
+            }
+        }
+
+        Ok(permissions.iter().map(|permission| permission.to_string()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_held_permissions_can_be_granted() {
+        let actor: HashSet<Permission> = [Permission::UsersRead, Permission::RolesAssign].into();
+        let names = |values: &[&str]| values.iter().map(|value| value.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            RoleService::check_grantable(&actor, &names(&["users.read", "users.read"])).unwrap(),
+            names(&["users.read"])
+        );
+        assert!(matches!(
+            RoleService::check_grantable(&actor, &names(&["users.delete"])),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(matches!(
+            RoleService::check_grantable(&actor, &names(&["everything"])),
+            Err(AppError::Validation(_))
+        ));
+    }
+}
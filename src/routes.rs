@@ -1,27 +1,81 @@
 use crate::{
+    analytics::{
+        analytics_dto::{BurndownPoint, BurndownResponse, StreaksResponse, TrendPeriod, TrendsResponse},
+        analytics_handlers,
+        analytics_models::TrendGrouping,
+    },
+    audit::{audit_handlers, audit_models::AuditLogEntry},
     auth::{
-        auth_dto::{AuthResponse, LoginRequest, RefreshTokenRequest, RefreshTokenResponse, RegisterRequest},
+        auth_dto::{
+            AuthResponse, ForgotPasswordRequest, LoginRequest, RefreshTokenRequest, RefreshTokenResponse,
+            RegisterRequest, ResetPasswordRequest, ChangePasswordRequest, VerifyEmailRequest, DisableTwoFactorRequest,
+            RecoveryCodesResponse, TwoFactorChallengeResponse, TwoFactorCodeRequest, TwoFactorSetupResponse,
+            TwoFactorVerifyRequest, SessionResponse, RevokedSessionsResponse, CreatePersonalAccessTokenRequest,
+            CreatedPersonalAccessTokenResponse, PersonalAccessTokenResponse, AuthorizationUrlResponse,
+            IdentityResponse, OAuthProviderResponse, OAuthLoginCodeRequest, LockoutResponse,
+            SwitchOrganizationRequest, ImpersonationResponse,
+        },
         auth_handlers,
     },
+    escalation::{
+        escalation_dto::UpsertEscalationPolicyRequest,
+        escalation_handlers,
+        escalation_models::EscalationPolicy,
+    },
+    invitation::{
+        invitation_dto::{
+            CreateInvitationRequest, CreateJoinLinkRequest, CreateOrganizationInvitationRequest,
+            CreatedJoinLinkResponse, JoinTaskRequest,
+        },
+        invitation_handlers,
+        invitation_models::{
+            OrganizationInvitation, ReceivedInvitation, ReceivedOrganizationInvitation, TaskInvitation, TaskJoinLink,
+        },
+    },
+    organization::{
+        organization_dto::{CreateOrganizationRequest, UpdateOrganizationMemberRequest, UpdateOrganizationRequest},
+        organization_handlers,
+        organization_models::{OrgRole, Organization, OrganizationMember, OrganizationMembership},
+    },
+    public_link::{
+        public_link_dto::{CreatePublicLinkRequest, CreatedPublicLinkResponse, PublicLinkPasswordRequest},
+        public_link_handlers,
+        public_link_models::{PublicTaskView, TaskPublicLink},
+    },
     message::{
         message_dto::{ConversationUser, SendMessageRequest},
         message_handlers,
         message_models::{Message, MessageResponse},
     },
-    middleware::auth_middleware,
+    middleware::{
+        auth_middleware, rate_limit, request_id, request_id::REQUEST_ID_HEADER, RateLimiter, TrustedProxies,
+    },
     notification::{
         notification_dto::UpdateNotificationPreferencesRequest,
         notification_handlers,
         notification_models::Notification,
     },
+    role::{
+        role_dto::{CreateRoleRequest, UpdateRoleRequest, UserPermissionsResponse},
+        role_handlers,
+        role_models::{Role, RoleAssignment},
+    },
     state::AppState,
     task::{
-        task_dto::{CreateTaskRequest, UpdateTaskRequest, UpdateTaskStatusRequest},
+        task_dto::{
+            CalendarDay, CalendarEntry, CalendarEntryKind, CalendarResponse, CreateTaskRequest,
+            CreateReminderRequest, QuickAddInterpretation, QuickAddRequest, QuickAddResponse,
+            MemberWorkload, SnoozeReminderRequest, TransferOwnershipRequest, UpdateTaskMemberRoleRequest,
+            UpdateTaskRequest, UpdateTaskStatusRequest, WorkloadResponse,
+        },
         task_handlers,
-        task_models::{Task, TaskPriority, TaskStatus},
+        task_models::{Task, TaskPriority, TaskReminder, TaskRole, TaskStatus},
     },
     user::{
-        user_dto::{UpdateProfileRequest, UserStatsResponse},
+        user_dto::{
+            CurrentUserResponse, ImpersonateUserRequest, ImpersonatorResponse, UpdateProfileRequest,
+            UserStatsResponse,
+        },
         user_handlers,
         user_models::{User, UserResponse},
     },
@@ -29,9 +83,10 @@ use crate::{
 use axum::{
     middleware,
     routing::{delete, get, patch, post, put},
-    Router,
+    Extension, Router,
 };
 use axum::http::{header::{AUTHORIZATION, CONTENT_TYPE}, Method};
+use std::sync::Arc;
 use tower_http::cors::{AllowOrigin, CorsLayer};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
@@ -43,10 +98,49 @@ use utoipa_swagger_ui::SwaggerUi;
         crate::auth::auth_handlers::login,
         crate::auth::auth_handlers::google_login,
         crate::auth::auth_handlers::google_callback,
+        crate::auth::auth_handlers::list_oauth_providers,
+        crate::auth::auth_handlers::oauth_login,
+        crate::auth::auth_handlers::oauth_callback,
+        crate::auth::auth_handlers::exchange_oauth_login_code,
+        crate::auth::auth_handlers::jwks,
+        crate::auth::auth_handlers::link_identity,
+        crate::auth::auth_handlers::list_identities,
+        crate::auth::auth_handlers::unlink_identity,
         crate::auth::auth_handlers::refresh_token,
+        crate::auth::auth_handlers::switch_organization,
         crate::auth::auth_handlers::logout,
+        crate::auth::auth_handlers::forgot_password,
+        crate::auth::auth_handlers::reset_password,
+        crate::auth::auth_handlers::verify_email,
+        crate::auth::auth_handlers::resend_verification_email,
+        crate::auth::auth_handlers::verify_two_factor,
+        crate::auth::auth_handlers::setup_two_factor,
+        crate::auth::auth_handlers::confirm_two_factor,
+        crate::auth::auth_handlers::disable_two_factor,
+        crate::auth::auth_handlers::list_sessions,
+        crate::auth::auth_handlers::revoke_session,
+        crate::auth::auth_handlers::revoke_other_sessions,
+        crate::auth::auth_handlers::end_impersonation,
+        crate::auth::auth_handlers::change_password,
+        crate::auth::auth_handlers::list_personal_access_tokens,
+        crate::auth::auth_handlers::create_personal_access_token,
+        crate::auth::auth_handlers::revoke_personal_access_token,
+        crate::organization::organization_handlers::list_organizations,
+        crate::organization::organization_handlers::create_organization,
+        crate::organization::organization_handlers::get_current_organization,
+        crate::organization::organization_handlers::update_organization,
+        crate::organization::organization_handlers::list_members,
+        crate::organization::organization_handlers::update_member,
+        crate::organization::organization_handlers::remove_member,
+        crate::invitation::invitation_handlers::create_organization_invitation,
+        crate::invitation::invitation_handlers::list_organization_invitations,
+        crate::invitation::invitation_handlers::revoke_organization_invitation,
         crate::task::task_handlers::get_tasks,
         crate::task::task_handlers::get_task,
+        crate::task::task_handlers::get_calendar,
+        crate::task::task_handlers::get_workload,
+        crate::task::task_handlers::admin_get_workload,
+        crate::task::task_handlers::quick_add_task,
         crate::task::task_handlers::create_task,
         crate::task::task_handlers::update_task,
         crate::task::task_handlers::delete_task,
@@ -54,8 +148,38 @@ use utoipa_swagger_ui::SwaggerUi;
         crate::task::task_handlers::task_stream,
         crate::task::task_handlers::share_task,
         crate::task::task_handlers::remove_task_member,
+        crate::task::task_handlers::update_task_member_role,
+        crate::task::task_handlers::transfer_task_ownership,
         crate::task::task_handlers::get_task_members,
+        crate::invitation::invitation_handlers::create_invitation,
+        crate::invitation::invitation_handlers::list_task_invitations,
+        crate::invitation::invitation_handlers::revoke_invitation,
+        crate::invitation::invitation_handlers::create_join_link,
+        crate::invitation::invitation_handlers::list_join_links,
+        crate::invitation::invitation_handlers::revoke_join_link,
+        crate::invitation::invitation_handlers::list_my_invitations,
+        crate::invitation::invitation_handlers::accept_invitation,
+        crate::invitation::invitation_handlers::decline_invitation,
+        crate::invitation::invitation_handlers::list_my_organization_invitations,
+        crate::invitation::invitation_handlers::accept_organization_invitation,
+        crate::invitation::invitation_handlers::decline_organization_invitation,
+        crate::invitation::invitation_handlers::join_task,
+        crate::public_link::public_link_handlers::create_public_link,
+        crate::public_link::public_link_handlers::list_public_links,
+        crate::public_link::public_link_handlers::revoke_public_link,
+        crate::public_link::public_link_handlers::get_public_task,
+        crate::public_link::public_link_handlers::unlock_public_task,
         crate::task::task_handlers::get_task_activity,
+        crate::task::task_handlers::get_task_reminders,
+        crate::task::task_handlers::add_task_reminder,
+        crate::task::task_handlers::delete_task_reminder,
+        crate::task::task_handlers::snooze_task_reminder,
+        crate::escalation::escalation_handlers::get_default_policy,
+        crate::escalation::escalation_handlers::set_default_policy,
+        crate::escalation::escalation_handlers::delete_default_policy,
+        crate::escalation::escalation_handlers::get_task_policy,
+        crate::escalation::escalation_handlers::set_task_policy,
+        crate::escalation::escalation_handlers::delete_task_policy,
         crate::notification::notification_handlers::get_notifications,
         crate::notification::notification_handlers::notification_stream,
         crate::notification::notification_handlers::mark_notification_read,
@@ -64,12 +188,32 @@ use utoipa_swagger_ui::SwaggerUi;
         crate::user::user_handlers::get_current_user,
         crate::user::user_handlers::update_current_user,
         crate::user::user_handlers::get_user_stats,
+        crate::analytics::analytics_handlers::get_trends,
+        crate::analytics::analytics_handlers::get_burndown,
+        crate::analytics::analytics_handlers::get_streaks,
+        crate::role::role_handlers::get_my_permissions,
         crate::user::user_handlers::get_all_users,
         crate::user::user_handlers::get_user_by_id,
         crate::user::user_handlers::admin_update_user,
         crate::user::user_handlers::delete_user,
         crate::user::user_handlers::update_user_status,
-        crate::user::user_handlers::update_admin_status,
+        crate::user::user_handlers::reset_two_factor,
+        crate::user::user_handlers::impersonate_user,
+        crate::user::user_handlers::admin_list_sessions,
+        crate::user::user_handlers::admin_revoke_session,
+        crate::user::user_handlers::list_lockouts,
+        crate::user::user_handlers::clear_lockout,
+        crate::user::user_handlers::clear_user_lockout,
+        crate::role::role_handlers::list_roles,
+        crate::role::role_handlers::create_role,
+        crate::role::role_handlers::update_role,
+        crate::role::role_handlers::delete_role,
+        crate::role::role_handlers::get_user_roles,
+        crate::role::role_handlers::assign_role,
+        crate::role::role_handlers::unassign_role,
+        crate::task::task_handlers::admin_get_task,
+        crate::audit::audit_handlers::list_audit_log,
+        crate::audit::audit_handlers::export_audit_log,
         crate::message::message_handlers::send_message,
         crate::message::message_handlers::get_conversation,
         crate::message::message_handlers::get_conversations,
@@ -82,12 +226,88 @@ use utoipa_swagger_ui::SwaggerUi;
             AuthResponse,
             RefreshTokenRequest,
             RefreshTokenResponse,
+            ForgotPasswordRequest,
+            ResetPasswordRequest,
+            ChangePasswordRequest,
+            VerifyEmailRequest,
+            TwoFactorChallengeResponse,
+            TwoFactorVerifyRequest,
+            TwoFactorSetupResponse,
+            TwoFactorCodeRequest,
+            RecoveryCodesResponse,
+            DisableTwoFactorRequest,
+            SessionResponse,
+            RevokedSessionsResponse,
+            CreatePersonalAccessTokenRequest,
+            PersonalAccessTokenResponse,
+            CreatedPersonalAccessTokenResponse,
+            OAuthProviderResponse,
+            AuthorizationUrlResponse,
+            IdentityResponse,
+            OAuthLoginCodeRequest,
+            LockoutResponse,
+            SwitchOrganizationRequest,
+            ImpersonationResponse,
+            Organization,
+            OrganizationMembership,
+            OrganizationMember,
+            OrgRole,
+            CreateOrganizationRequest,
+            UpdateOrganizationRequest,
+            CreateOrganizationInvitationRequest,
+            OrganizationInvitation,
+            ReceivedOrganizationInvitation,
+            UpdateOrganizationMemberRequest,
             CreateTaskRequest,
             UpdateTaskRequest,
             UpdateTaskStatusRequest,
+            CalendarResponse,
+            CalendarDay,
+            CalendarEntry,
+            CalendarEntryKind,
+            WorkloadResponse,
+            MemberWorkload,
+            QuickAddRequest,
+            QuickAddInterpretation,
+            QuickAddResponse,
+            CreateReminderRequest,
+            SnoozeReminderRequest,
+            UpdateTaskMemberRoleRequest,
+            TransferOwnershipRequest,
+            TaskRole,
+            CreateInvitationRequest,
+            TaskInvitation,
+            ReceivedInvitation,
+            CreateJoinLinkRequest,
+            TaskJoinLink,
+            CreatedJoinLinkResponse,
+            JoinTaskRequest,
+            CreatePublicLinkRequest,
+            TaskPublicLink,
+            CreatedPublicLinkResponse,
+            PublicLinkPasswordRequest,
+            PublicTaskView,
+            TaskReminder,
+            EscalationPolicy,
+            UpsertEscalationPolicyRequest,
             UpdateNotificationPreferencesRequest,
             UpdateProfileRequest,
             UserStatsResponse,
+            CurrentUserResponse,
+            ImpersonatorResponse,
+            ImpersonateUserRequest,
+            TrendGrouping,
+            TrendPeriod,
+            TrendsResponse,
+            BurndownPoint,
+            BurndownResponse,
+            StreaksResponse,
+            UserPermissionsResponse,
+            Role,
+            RoleAssignment,
+            AuditLogEntry,
+            CreateRoleRequest,
+            UpdateRoleRequest,
             SendMessageRequest,
             ConversationUser,
             User,
@@ -102,10 +322,15 @@ use utoipa_swagger_ui::SwaggerUi;
     ),
     tags(
         (name = "auth", description = "Authentication endpoints"),
+        (name = "organizations", description = "Organizations, their members and roles"),
         (name = "tasks", description = "Task management endpoints"),
+        (name = "invitations", description = "Endpoints for answering task invitations and joining by link"),
+        (name = "public", description = "Read-only task views through public links, no sign-in needed"),
+        (name = "escalations", description = "Overdue escalation policy endpoints"),
         (name = "notifications", description = "Notification endpoints"),
         (name = "users", description = "User profile endpoints"),
-        (name = "admin", description = "Admin user management endpoints"),
+        (name = "analytics", description = "Productivity trends, burndown and streaks from the task history"),
+        (name = "admin", description = "User, role and task administration and the audit log; each endpoint needs a permission"),
         (name = "messages", description = "User messaging endpoints")
     ),
     modifiers(&SecurityAddon)
@@ -149,7 +374,8 @@ pub fn create_router(state: AppState) -> Router {
             Method::PATCH,
             Method::OPTIONS,
         ])
-        .allow_headers([AUTHORIZATION, CONTENT_TYPE])
+        .allow_headers([AUTHORIZATION, CONTENT_TYPE, REQUEST_ID_HEADER.clone()])
+        .expose_headers([REQUEST_ID_HEADER.clone()])
         .allow_credentials(true);
 
     // Public routes (no auth required)
@@ -157,14 +383,57 @@ pub fn create_router(state: AppState) -> Router {
         .route("/register", post(auth_handlers::register))
         .route("/login", post(auth_handlers::login))
         .route("/refresh", post(auth_handlers::refresh_token))
+        .route("/switch-organization", post(auth_handlers::switch_organization))
         .route("/logout", post(auth_handlers::logout))
+        .route("/forgot-password", post(auth_handlers::forgot_password))
+        .route("/reset-password", post(auth_handlers::reset_password))
+        .route("/verify-email", post(auth_handlers::verify_email))
+        .route("/2fa/verify", post(auth_handlers::verify_two_factor))
         .route("/google", get(auth_handlers::google_login))
-        .route("/google/callback", get(auth_handlers::google_callback));
+        .route("/google/callback", get(auth_handlers::google_callback))
+        .route("/oauth/providers", get(auth_handlers::list_oauth_providers))
+        .route("/oauth/:provider", get(auth_handlers::oauth_login))
+        .route("/oauth/:provider/callback", get(auth_handlers::oauth_callback))
+        .route("/oauth/exchange", post(auth_handlers::exchange_oauth_login_code))
+        .route_layer(middleware::from_fn_with_state(
+            RateLimiter::new(
+                state.config.auth_rate_limit_requests,
+                std::time::Duration::from_secs(state.config.auth_rate_limit_window_seconds),
+            ),
+            rate_limit,
+        ))
+        .merge(
+            Router::new()
+                .route("/verify-email/resend", post(auth_handlers::resend_verification_email))
+                .route("/2fa/setup", post(auth_handlers::setup_two_factor))
+                .route("/2fa/confirm", post(auth_handlers::confirm_two_factor))
+                .route("/2fa/disable", post(auth_handlers::disable_two_factor))
+                .route("/change-password", post(auth_handlers::change_password))
+                .route("/sessions", get(auth_handlers::list_sessions))
+                .route("/sessions/revoke-others", post(auth_handlers::revoke_other_sessions))
+                .route("/sessions/:session_id", delete(auth_handlers::revoke_session))
+                .route("/impersonation/end", post(auth_handlers::end_impersonation))
+                .route(
+                    "/tokens",
+                    get(auth_handlers::list_personal_access_tokens).post(auth_handlers::create_personal_access_token),
+                )
+                .route("/tokens/:token_id", delete(auth_handlers::revoke_personal_access_token))
+                .route("/oauth/:provider/link", post(auth_handlers::link_identity))
+                .route("/identities", get(auth_handlers::list_identities))
+                .route("/identities/:identity_id", delete(auth_handlers::unlink_identity))
+                .route_layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    auth_middleware,
+                )),
+        );
 
     // Protected routes (auth required)
     let task_routes = Router::new()
         .route("/", get(task_handlers::get_tasks).post(task_handlers::create_task))
         .route("/stream", get(task_handlers::task_stream))
+        .route("/calendar", get(task_handlers::get_calendar))
+        .route("/workload", get(task_handlers::get_workload))
+        .route("/quick-add", post(task_handlers::quick_add_task))
         .route(
             "/:id",
             get(task_handlers::get_task)
@@ -174,8 +443,107 @@ pub fn create_router(state: AppState) -> Router {
         .route("/:id/status", patch(task_handlers::update_task_status))
         .route("/:id/share", post(task_handlers::share_task))
         .route("/:id/members", get(task_handlers::get_task_members))
-        .route("/:id/members/:user_id", delete(task_handlers::remove_task_member))
+        .route(
+            "/:id/members/:user_id",
+            put(task_handlers::update_task_member_role).delete(task_handlers::remove_task_member),
+        )
+        .route("/:id/transfer", post(task_handlers::transfer_task_ownership))
+        .route(
+            "/:id/invitations",
+            get(invitation_handlers::list_task_invitations).post(invitation_handlers::create_invitation),
+        )
+        .route("/:id/invitations/:invitation_id", delete(invitation_handlers::revoke_invitation))
+        .route(
+            "/:id/join-links",
+            get(invitation_handlers::list_join_links).post(invitation_handlers::create_join_link),
+        )
+        .route("/:id/join-links/:link_id", delete(invitation_handlers::revoke_join_link))
+        .route(
+            "/:id/public-links",
+            get(public_link_handlers::list_public_links).post(public_link_handlers::create_public_link),
+        )
+        .route("/:id/public-links/:link_id", delete(public_link_handlers::revoke_public_link))
         .route("/:id/activity", get(task_handlers::get_task_activity))
+        .route(
+            "/:id/reminders",
+            get(task_handlers::get_task_reminders).post(task_handlers::add_task_reminder),
+        )
+        .route("/:id/reminders/:reminder_id", delete(task_handlers::delete_task_reminder))
+        .route("/:id/reminders/:reminder_id/snooze", post(task_handlers::snooze_task_reminder))
+        .route(
+            "/:id/escalation-policy",
+            get(escalation_handlers::get_task_policy)
+                .put(escalation_handlers::set_task_policy)
+                .delete(escalation_handlers::delete_task_policy),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ));
+
+    // Public task views (no auth required); rate limited against password guessing
+    let public_routes = Router::new()
+        .route(
+            "/tasks/:token",
+            get(public_link_handlers::get_public_task).post(public_link_handlers::unlock_public_task),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            RateLimiter::new(
+                state.config.auth_rate_limit_requests,
+                std::time::Duration::from_secs(state.config.auth_rate_limit_window_seconds),
+            ),
+            rate_limit,
+        ));
+
+    let invitation_routes = Router::new()
+        .route("/", get(invitation_handlers::list_my_invitations))
+        .route("/join", post(invitation_handlers::join_task))
+        .route("/:id/accept", post(invitation_handlers::accept_invitation))
+        .route("/:id/decline", post(invitation_handlers::decline_invitation))
+        .route("/organizations", get(invitation_handlers::list_my_organization_invitations))
+        .route(
+            "/organizations/:id/accept",
+            post(invitation_handlers::accept_organization_invitation),
+        )
+        .route(
+            "/organizations/:id/decline",
+            post(invitation_handlers::decline_organization_invitation),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ));
+
+    let organization_routes = Router::new()
+        .route(
+            "/",
+            get(organization_handlers::list_organizations).post(organization_handlers::create_organization),
+        )
+        .route("/current", get(organization_handlers::get_current_organization))
+        .route("/:id", put(organization_handlers::update_organization))
+        .route("/:id/members", get(organization_handlers::list_members))
+        .route(
+            "/:id/invitations",
+            get(invitation_handlers::list_organization_invitations)
+                .post(invitation_handlers::create_organization_invitation),
+        )
+        .route(
+            "/:id/invitations/:invitation_id",
+            delete(invitation_handlers::revoke_organization_invitation),
+        )
+        .route(
+            "/:id/members/:user_id",
+            put(organization_handlers::update_member).delete(organization_handlers::remove_member),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ));
+
+    let analytics_routes = Router::new()
+        .route("/trends", get(analytics_handlers::get_trends))
+        .route("/burndown", get(analytics_handlers::get_burndown))
+        .route("/streaks", get(analytics_handlers::get_streaks))
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
             auth_middleware,
@@ -198,6 +566,13 @@ pub fn create_router(state: AppState) -> Router {
     let user_routes = Router::new()
         .route("/me", get(user_handlers::get_current_user).put(user_handlers::update_current_user))
         .route("/me/stats", get(user_handlers::get_user_stats))
+        .route("/me/permissions", get(role_handlers::get_my_permissions))
+        .route(
+            "/me/escalation-policy",
+            get(escalation_handlers::get_default_policy)
+                .put(escalation_handlers::set_default_policy)
+                .delete(escalation_handlers::delete_default_policy),
+        )
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
             auth_middleware,
@@ -210,10 +585,31 @@ pub fn create_router(state: AppState) -> Router {
             .put(user_handlers::admin_update_user)
             .delete(user_handlers::delete_user))
         .route("/users/:user_id/status", patch(user_handlers::update_user_status))
-        .route("/users/:user_id/admin", patch(user_handlers::update_admin_status))
+        .route("/users/:user_id/roles", get(role_handlers::get_user_roles))
+        .route(
+            "/users/:user_id/roles/:role_name",
+            put(role_handlers::assign_role).delete(role_handlers::unassign_role),
+        )
+        .route("/users/:user_id/2fa", delete(user_handlers::reset_two_factor))
+        .route("/users/:user_id/impersonate", post(user_handlers::impersonate_user))
+        .route("/users/:user_id/sessions", get(user_handlers::admin_list_sessions))
+        .route("/users/:user_id/sessions/:session_id", delete(user_handlers::admin_revoke_session))
+        .route("/users/:user_id/lockout", delete(user_handlers::clear_user_lockout))
+        .route("/lockouts", get(user_handlers::list_lockouts))
+        .route("/lockouts/:lockout_id", delete(user_handlers::clear_lockout))
+        .route("/roles", get(role_handlers::list_roles).post(role_handlers::create_role))
+        .route(
+            "/roles/:role_name",
+            put(role_handlers::update_role).delete(role_handlers::delete_role),
+        )
+        .route("/tasks/:task_id", get(task_handlers::admin_get_task))
+        .route("/workload", get(task_handlers::admin_get_workload))
+        .route("/audit-log", get(audit_handlers::list_audit_log))
+        .route("/audit-log/export", get(audit_handlers::export_audit_log))
+        // Each handler checks its own permission with the `Authorized` extractor
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
-            crate::middleware::admin_middleware,
+            auth_middleware,
         ));
 
     let message_routes = Router::new()
@@ -236,16 +632,23 @@ pub fn create_router(state: AppState) -> Router {
 
     let api_routes = Router::new()
         .nest("/auth", auth_routes)
+        .nest("/organizations", organization_routes)
         .nest("/tasks", task_routes)
+        .nest("/invitations", invitation_routes)
+        .nest("/public", public_routes)
         .nest("/notifications", notification_routes)
         .nest("/users", user_routes)
+        .nest("/analytics", analytics_routes)
         .nest("/admin", admin_routes)
         .nest("/messages", message_routes)
         .merge(ws_routes);
 
     Router::new()
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        .route("/.well-known/jwks.json", get(auth_handlers::jwks))
         .nest("/api", api_routes)
+        .layer(Extension(TrustedProxies(Arc::new(state.config.trusted_proxies.clone()))))
+        .layer(middleware::from_fn(request_id))
         .layer(cors)
         .with_state(state)
 }
@@ -1,9 +1,17 @@
+use crate::auth::jwt::REFRESH_TOKEN_DAYS;
+use crate::auth::jwt_keys::{JwtKeys, SigningAlgorithm};
+use crate::auth::password_policy::PasswordPolicy;
+use crate::auth::verification_policy::UnverifiedUserPolicy;
 use crate::db::DbPool;
-use oauth2::basic::BasicClient;
+use crate::auth::oauth::{OAuthProviderConfig, OAuthProviderRegistry};
+use ipnet::IpNet;
+use std::net::IpAddr;
 use std::sync::Arc;
 use tokio::sync::broadcast;
 
 use crate::{
+    analytics::analytics_service::AnalyticsService,
+    audit::audit_service::AuditService,
     user::user_repository::UserRepository,
     task::task_repository::TaskRepository,
     notification::notification_repository::NotificationRepository,
@@ -13,6 +21,11 @@ use crate::{
     task::task_service::TaskService,
     auth::auth_service::AuthService,
     message::message_service::MessageService,
+    escalation::escalation_service::EscalationService,
+    role::role_service::RoleService,
+    invitation::invitation_service::InvitationService,
+    organization::organization_service::OrganizationService,
+    public_link::public_link_service::PublicLinkService,
     websocket::ConnectionManager,
 };
 
@@ -22,7 +35,8 @@ use crate::{
 pub struct AppState {
     pub db: DbPool,
     pub config: Arc<Config>,
-    pub oauth_client: BasicClient,
+    pub oauth_providers: Arc<OAuthProviderRegistry>,
+    pub jwt_keys: JwtKeys,
     pub notification_tx: broadcast::Sender<String>,
     pub task_tx: broadcast::Sender<(uuid::Uuid, crate::task::task_models::Task)>,
     pub ws_connections: Arc<ConnectionManager>,
@@ -35,32 +49,139 @@ pub struct AppState {
     pub task_service: TaskService,
     pub auth_service: AuthService,
     pub message_service: MessageService,
+    pub escalation_service: EscalationService,
+    pub role_service: RoleService,
+    pub invitation_service: InvitationService,
+    pub organization_service: OrganizationService,
+    pub public_link_service: PublicLinkService,
+    pub analytics_service: AnalyticsService,
+    pub audit_service: AuditService,
 }
 
 #[derive(Clone)]
 pub struct Config {
-    pub jwt_secret: String,
+    pub jwt_issuer: String,
+    pub jwt_audience: String,
+    pub jwt_algorithm: SigningAlgorithm,
+    pub jwt_key_rotation_days: i64,
+    pub jwt_key_overlap_days: i64,
     pub jwt_expiration_hours: i64,
-    pub google_client_id: String,
-    pub google_client_secret: String,
-    pub google_redirect_uri: String,
+    pub oauth_providers: Vec<OAuthProviderConfig>,
+    /// Where the app may ask to be sent back to after an OAuth login
+    pub oauth_allowed_redirects: Vec<String>,
+    pub oauth_default_redirect_url: Option<String>,
+    pub mail_transport: String,
+    pub mail_from: String,
+    pub mail_file_dir: String,
+    pub password_reset_url: String,
+    pub password_reset_token_minutes: i64,
+    pub email_verification_url: String,
+    pub email_verification_token_hours: i64,
+    pub email_verification_resend_seconds: i64,
+    pub task_invitation_url: String,
+    pub task_join_url: String,
+    pub task_public_link_url: String,
+    pub unverified_user_policy: UnverifiedUserPolicy,
+    pub totp_issuer: String,
+    pub password_policy: PasswordPolicy,
+    /// Requests per client IP and window on the public `/api/auth` routes
+    pub auth_rate_limit_requests: u32,
+    pub auth_rate_limit_window_seconds: u64,
+    /// Proxies whose `X-Forwarded-For` / `X-Real-IP` headers are believed
+    pub trusted_proxies: Vec<IpNet>,
 }
 
 impl Config {
     pub fn from_env() -> Self {
         Self {
-            jwt_secret: std::env::var("JWT_SECRET")
-                .expect("JWT_SECRET must be set"),
+            jwt_issuer: std::env::var("JWT_ISSUER")
+                .unwrap_or_else(|_| "task-manager".to_string()),
+            jwt_audience: std::env::var("JWT_AUDIENCE")
+                .unwrap_or_else(|_| "task-manager-api".to_string()),
+            jwt_algorithm: SigningAlgorithm::parse(
+                &std::env::var("JWT_ALGORITHM").unwrap_or_else(|_| "EdDSA".to_string()),
+            )
+            .expect("JWT_ALGORITHM must be EdDSA or RS256"),
+            jwt_key_rotation_days: std::env::var("JWT_KEY_ROTATION_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .ok()
+                .filter(|days| *days >= 2)
+                .expect("JWT_KEY_ROTATION_DAYS must be a number of at least 2"),
+            // Tokens must not outlive the key that verifies them
+            jwt_key_overlap_days: std::env::var("JWT_KEY_OVERLAP_DAYS")
+                .unwrap_or_else(|_| "8".to_string())
+                .parse()
+                .ok()
+                .filter(|days| *days > REFRESH_TOKEN_DAYS)
+                .expect("JWT_KEY_OVERLAP_DAYS must be a number larger than the refresh token lifetime (7)"),
             jwt_expiration_hours: std::env::var("JWT_EXPIRATION_HOURS")
                 .unwrap_or_else(|_| "24".to_string())
                 .parse()
                 .expect("JWT_EXPIRATION_HOURS must be a number"),
-            google_client_id: std::env::var("GOOGLE_CLIENT_ID")
-                .expect("GOOGLE_CLIENT_ID must be set"),
-            google_client_secret: std::env::var("GOOGLE_CLIENT_SECRET")
-                .expect("GOOGLE_CLIENT_SECRET must be set"),
-            google_redirect_uri: std::env::var("GOOGLE_REDIRECT_URI")
-                .expect("GOOGLE_REDIRECT_URI must be set"),
+            oauth_providers: crate::auth::oauth::provider_configs_from_env(),
+            oauth_allowed_redirects: std::env::var("OAUTH_ALLOWED_REDIRECTS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty())
+                .collect(),
+            oauth_default_redirect_url: std::env::var("OAUTH_DEFAULT_REDIRECT_URL").ok(),
+            mail_transport: std::env::var("MAIL_TRANSPORT")
+                .unwrap_or_else(|_| "log".to_string()),
+            mail_from: std::env::var("MAIL_FROM")
+                .unwrap_or_else(|_| "no-reply@taskmanager.local".to_string()),
+            mail_file_dir: std::env::var("MAIL_FILE_DIR")
+                .unwrap_or_else(|_| "mail".to_string()),
+            password_reset_url: std::env::var("PASSWORD_RESET_URL")
+                .unwrap_or_else(|_| "http://localhost:3000/reset-password".to_string()),
+            password_reset_token_minutes: std::env::var("PASSWORD_RESET_TOKEN_MINUTES")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .expect("PASSWORD_RESET_TOKEN_MINUTES must be a number"),
+            email_verification_url: std::env::var("EMAIL_VERIFICATION_URL")
+                .unwrap_or_else(|_| "http://localhost:3000/verify-email".to_string()),
+            email_verification_token_hours: std::env::var("EMAIL_VERIFICATION_TOKEN_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()
+                .expect("EMAIL_VERIFICATION_TOKEN_HOURS must be a number"),
+            email_verification_resend_seconds: std::env::var("EMAIL_VERIFICATION_RESEND_SECONDS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .expect("EMAIL_VERIFICATION_RESEND_SECONDS must be a number"),
+            task_invitation_url: std::env::var("TASK_INVITATION_URL")
+                .unwrap_or_else(|_| "http://localhost:3000/invitations".to_string()),
+            task_join_url: std::env::var("TASK_JOIN_URL")
+                .unwrap_or_else(|_| "http://localhost:3000/join".to_string()),
+            task_public_link_url: std::env::var("TASK_PUBLIC_LINK_URL")
+                .unwrap_or_else(|_| "http://localhost:3000/shared".to_string()),
+            unverified_user_policy: UnverifiedUserPolicy::parse(
+                &std::env::var("UNVERIFIED_USER_POLICY").unwrap_or_else(|_| "restricted".to_string()),
+            )
+            .expect("UNVERIFIED_USER_POLICY must be full, restricted or read_only"),
+            totp_issuer: std::env::var("TOTP_ISSUER")
+                .unwrap_or_else(|_| "Task Manager".to_string()),
+            password_policy: PasswordPolicy::from_env(),
+            auth_rate_limit_requests: std::env::var("AUTH_RATE_LIMIT_REQUESTS")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .expect("AUTH_RATE_LIMIT_REQUESTS must be a number"),
+            auth_rate_limit_window_seconds: std::env::var("AUTH_RATE_LIMIT_WINDOW_SECONDS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .expect("AUTH_RATE_LIMIT_WINDOW_SECONDS must be a number"),
+            trusted_proxies: std::env::var("TRUSTED_PROXIES")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|proxy| !proxy.is_empty())
+                .map(|proxy| {
+                    proxy
+                        .parse::<IpNet>()
+                        .or_else(|_| proxy.parse::<IpAddr>().map(IpNet::from))
+                        .expect("TRUSTED_PROXIES must be a comma-separated list of IP addresses or CIDR ranges")
+                })
+                .collect(),
         }
     }
 }
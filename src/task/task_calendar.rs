@@ -0,0 +1,170 @@
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use super::{
+    task_dto::{CalendarDay, CalendarEntry, CalendarEntryKind},
+    task_models::Task,
+};
+
+/// Longest range the calendar endpoint will expand, in days.
+pub const MAX_CALENDAR_DAYS: i64 = 92;
+
+/// UTC instant at which `date` starts in `tz`.
+pub fn local_day_start(date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+    match tz.from_local_datetime(&midnight).earliest() {
+        Some(start) => start.with_timezone(&Utc),
+        // Midnight skipped by a DST jump: the day starts an hour later.
+        None => tz
+            .from_local_datetime(&(midnight + Duration::hours(1)))
+            .earliest()
+            .map(|start| start.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&midnight)),
+    }
+}
+
+/// Calendar date of `at` as seen in `tz`.
+pub fn local_date(at: DateTime<Utc>, tz: Tz) -> NaiveDate {
+    at.with_timezone(&tz).date_naive()
+}
+
+fn is_open(task: &Task) -> bool {
+    task.status != "Completed" && task.status != "Archived"
+}
+
+/// Bucket tasks into one entry per local day of `[from, to)`.
+///
+/// A task shows up on the day it is due and on each day one of the
+/// `reminders` (task id, firing time) fires. Unfinished tasks due before
+/// `today` are additionally carried over onto `today`, if it falls inside
+/// the range.
+pub fn build_calendar_days(
+    tasks: Vec<Task>,
+    reminders: Vec<(Uuid, DateTime<Utc>)>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    tz: Tz,
+    today: NaiveDate,
+) -> Vec<CalendarDay> {
+    let first_day = local_date(from, tz);
+    let last_day = local_date(to - Duration::microseconds(1), tz);
+    let today_start = local_day_start(today, tz);
+    let in_range = |at: DateTime<Utc>| at >= from && at < to;
+
+    let mut buckets: BTreeMap<NaiveDate, Vec<CalendarEntry>> = first_day
+        .iter_days()
+        .take_while(|day| *day <= last_day)
+        .map(|day| (day, Vec::new()))
+        .collect();
+
+    let tasks_by_id: HashMap<Uuid, &Task> = tasks.iter().map(|task| (task.id, task)).collect();
+    for (task_id, fire_at) in reminders.into_iter().filter(|(_, at)| in_range(*at)) {
+        if let (Some(task), Some(entries)) = (tasks_by_id.get(&task_id), buckets.get_mut(&local_date(fire_at, tz))) {
+            entries.push(CalendarEntry {
+                kind: CalendarEntryKind::Reminder,
+                at: fire_at,
+                task: (*task).clone(),
+            });
+        }
+    }
+
+    for task in &tasks {
+        if let Some(due) = task.due_date.filter(|due| in_range(*due)) {
+            if let Some(entries) = buckets.get_mut(&local_date(due, tz)) {
+                entries.push(CalendarEntry {
+                    kind: CalendarEntryKind::Due,
+                    at: due,
+                    task: task.clone(),
+                });
+            }
+        }
+
+        if let Some(due) = task.due_date.filter(|due| *due < today_start) {
+            if is_open(task) {
+                if let Some(entries) = buckets.get_mut(&today) {
+                    entries.push(CalendarEntry {
+                        kind: CalendarEntryKind::Overdue,
+                        at: due,
+                        task: task.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    buckets
+        .into_iter()
+        .map(|(date, mut entries)| {
+            entries.sort_by_key(|entry| entry.at);
+            let count = |kind| entries.iter().filter(|e| e.kind == kind).count();
+
+            CalendarDay {
+                date,
+                total: entries.len(),
+                due_count: count(CalendarEntryKind::Due),
+                reminder_count: count(CalendarEntryKind::Reminder),
+                overdue_count: count(CalendarEntryKind::Overdue),
+                entries,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(status: &str, due: Option<&str>) -> Task {
+        Task {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            organization_id: Uuid::new_v4(),
+            title: "Task".to_string(),
+            description: None,
+            status: status.to_string(),
+            priority: "Medium".to_string(),
+            due_date: due.map(|s| s.parse().unwrap()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_groups_by_local_day() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+        let from = local_day_start(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(), tz);
+        let to = local_day_start(NaiveDate::from_ymd_opt(2025, 3, 4).unwrap(), tz);
+
+        // 02:00 UTC on March 3rd is still March 2nd in New York.
+        let tasks = vec![task("Pending", Some("2025-03-03T02:00:00Z"))];
+        let reminders = vec![(tasks[0].id, "2025-03-01T15:00:00Z".parse().unwrap())];
+        let days = build_calendar_days(tasks, reminders, from, to, tz, NaiveDate::from_ymd_opt(2025, 1, 1).unwrap());
+
+        assert_eq!(days.len(), 3);
+        assert_eq!(days[0].reminder_count, 1);
+        assert_eq!(days[1].due_count, 1);
+        assert_eq!(days[2].total, 0);
+    }
+
+    #[test]
+    fn test_carries_overdue_onto_today() {
+        let tz = Tz::UTC;
+        let from = "2025-03-01T00:00:00Z".parse().unwrap();
+        let to = "2025-03-08T00:00:00Z".parse().unwrap();
+        let today = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+
+        let tasks = vec![
+            task("Pending", Some("2025-02-20T09:00:00Z")),
+            task("InProgress", Some("2025-03-02T09:00:00Z")),
+            task("Completed", Some("2025-03-03T09:00:00Z")),
+        ];
+        let days = build_calendar_days(tasks, Vec::new(), from, to, tz, today);
+
+        let today_bucket = days.iter().find(|d| d.date == today).unwrap();
+        assert_eq!(today_bucket.overdue_count, 2);
+        assert_eq!(days[1].due_count, 1);
+        assert_eq!(days[2].due_count, 1);
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use validator::Validate;
@@ -11,7 +11,11 @@ pub struct CreateTaskRequest {
     pub description: Option<String>,
     pub priority: Option<String>,
     pub due_date: Option<DateTime<Utc>>,
+    /// Shorthand for a single absolute reminder for the creator
     pub reminder_time: Option<DateTime<Utc>>,
+    /// Reminders for the creator; a `user_id` other than the creator is rejected
+    #[validate(nested)]
+    pub reminders: Option<Vec<CreateReminderRequest>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
@@ -22,7 +26,6 @@ pub struct UpdateTaskRequest {
     pub status: Option<String>,
     pub priority: Option<String>,
     pub due_date: Option<DateTime<Utc>>,
-    pub reminder_time: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -44,6 +47,20 @@ pub struct PaginatedResponse<T> {
 pub struct ShareTaskRequest {
     #[validate(length(min = 1))]
     pub user_ids: Vec<Uuid>,
+    /// `viewer` or `editor` (default)
+    pub role: Option<super::task_models::TaskRole>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateTaskMemberRoleRequest {
+    /// `viewer` or `editor`
+    pub role: super::task_models::TaskRole,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TransferOwnershipRequest {
+    /// Must already be a member of the task
+    pub new_owner_id: Uuid,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -65,3 +82,115 @@ pub struct TaskActivityResponse {
     pub details: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
+
+// Reminder DTOs
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateReminderRequest {
+    /// Fire at this exact time
+    pub remind_at: Option<DateTime<Utc>>,
+    /// Fire this many minutes before the task's due date
+    #[validate(range(min = 0, max = 525600))]
+    pub offset_minutes: Option<i32>,
+    /// Member to remind (defaults to the caller; only the owner may set others)
+    pub user_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SnoozeReminderRequest {
+    /// Snooze for this many minutes from now
+    #[validate(range(min = 1, max = 10080))]
+    pub minutes: Option<i64>,
+    /// Or snooze until this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+// Calendar DTOs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarEntryKind {
+    Due,
+    Reminder,
+    Overdue,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CalendarEntry {
+    pub kind: CalendarEntryKind,
+    pub at: DateTime<Utc>,
+    pub task: super::task_models::Task,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    pub total: usize,
+    pub due_count: usize,
+    pub reminder_count: usize,
+    pub overdue_count: usize,
+    pub entries: Vec<CalendarEntry>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CalendarResponse {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub timezone: String,
+    pub days: Vec<CalendarDay>,
+}
+
+// Workload DTOs
+/// One person's open work and how much they finished lately.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct MemberWorkload {
+    pub user_id: Uuid,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    /// Pending or in-progress tasks they own or can edit
+    pub open_tasks: i64,
+    pub open_low: i64,
+    pub open_medium: i64,
+    pub open_high: i64,
+    pub open_urgent: i64,
+    pub overdue: i64,
+    /// Open tasks due within `due_within_days`
+    pub due_soon: i64,
+    /// Tasks they marked completed within `completed_within_days`
+    pub completed_recently: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WorkloadResponse {
+    pub due_within_days: i64,
+    pub completed_within_days: i64,
+    pub members: Vec<MemberWorkload>,
+}
+
+// Quick-add DTOs
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct QuickAddRequest {
+    /// e.g. "Call supplier !high tomorrow 9am remind 1h before"
+    #[validate(length(min = 1, max = 1000))]
+    pub text: String,
+    /// Only return the interpretation, without creating the task
+    #[serde(default)]
+    pub preview: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct QuickAddInterpretation {
+    pub title: String,
+    pub priority: Option<String>,
+    pub due_date: Option<DateTime<Utc>>,
+    pub reminder_time: Option<DateTime<Utc>>,
+    /// Set when the reminder was given relative to the due date
+    pub reminder_offset_minutes: Option<i32>,
+    /// Fragments of the text that were recognised as task attributes
+    pub recognized: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuickAddResponse {
+    pub timezone: String,
+    pub parsed: QuickAddInterpretation,
+    pub task: Option<super::task_models::Task>,
+}
@@ -4,6 +4,8 @@ use axum::{
     response::{IntoResponse, sse::{Event, KeepAlive, Sse}},
     Extension, Json,
 };
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use futures::stream::Stream;
 use serde::Deserialize;
 use tokio_stream::{wrappers::BroadcastStream, StreamExt};
@@ -12,11 +14,16 @@ use validator::Validate;
 
 use crate::{
     error::{AppError, Result},
+    middleware::{Authorized, CurrentOrganization},
+    role::permission::Permission,
     state::AppState,
 };
 use super::{
-    task_dto::{CreateTaskRequest, UpdateTaskRequest, UpdateTaskStatusRequest, PaginatedResponse},
-    task_models::Task,
+    task_dto::{
+        CalendarResponse, CreateReminderRequest, CreateTaskRequest, PaginatedResponse, QuickAddRequest,
+        SnoozeReminderRequest, UpdateTaskRequest, UpdateTaskStatusRequest, WorkloadResponse,
+    },
+    task_models::{Task, TaskReminder},
 };
 
 #[derive(Deserialize)]
@@ -30,6 +37,26 @@ pub struct TaskFilters {
     limit: Option<u32>,
 }
 
+#[derive(Deserialize)]
+pub struct CalendarQuery {
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    tz: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct WorkloadQuery {
+    days: Option<i64>,
+    throughput_days: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct AdminWorkloadQuery {
+    user_ids: String,
+    days: Option<i64>,
+    throughput_days: Option<i64>,
+}
+
 /// Get all tasks for the authenticated user
 #[utoipa::path(
     get,
@@ -53,6 +80,7 @@ pub struct TaskFilters {
 pub async fn get_tasks(
     State(state): State<AppState>,
     Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
     Query(filters): Query<TaskFilters>,
 ) -> Result<Json<PaginatedResponse<Task>>> {
     let page = filters.page.unwrap_or(1);
@@ -68,7 +96,7 @@ pub async fn get_tasks(
         limit: Some(limit),
     };
 
-    let (tasks, total) = state.task_service.list_tasks(user_id, repo_filters).await?;
+    let (tasks, total) = state.task_service.list_tasks(organization_id, user_id, repo_filters).await?;
 
     let total_pages = (total as f64 / limit as f64).ceil() as u32;
 
@@ -81,8 +109,48 @@ pub async fn get_tasks(
     }))
 }
 
-// ... (get_task)
+/// Get tasks grouped per day for a calendar view
+#[utoipa::path(
+    get,
+    path = "/api/tasks/calendar",
+    params(
+        ("from" = DateTime<Utc>, Query, description = "Start of the range (inclusive, RFC 3339)"),
+        ("to" = DateTime<Utc>, Query, description = "End of the range (exclusive, RFC 3339)"),
+        ("tz" = Option<String>, Query, description = "IANA time zone used to group days (defaults to the user's time zone)")
+    ),
+    responses(
+        (status = 200, description = "Tasks grouped per day", body = CalendarResponse),
+        (status = 400, description = "Invalid range or time zone"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_calendar(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Query(query): Query<CalendarQuery>,
+) -> Result<Json<CalendarResponse>> {
+    let tz = match query.tz.as_deref() {
+        Some(name) => name
+            .parse::<Tz>()
+            .map_err(|_| AppError::BadRequest(format!("Unknown time zone: {}", name)))?,
+        None => state
+            .user_repository
+            .find_by_id(user_id)
+            .await?
+            .map(|user| user.tz())
+            .unwrap_or(Tz::UTC),
+    };
+
+    let calendar = state
+        .task_service
+        .get_calendar(organization_id, user_id, query.from, query.to, tz)
+        .await?;
 
+    Ok(Json(calendar))
+}
 
 // ... (get_task)
 #[utoipa::path(
@@ -102,12 +170,125 @@ pub async fn get_tasks(
 pub async fn get_task(
     State(state): State<AppState>,
     Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path(task_id): Path<Uuid>,
+) -> Result<Json<Task>> {
+    let task = state.task_service.get_task(organization_id, user_id, task_id).await?;
+    Ok(Json(task))
+}
+
+/// Get any task, shared with the caller or not (requires `tasks.read_any`)
+#[utoipa::path(
+    get,
+    path = "/api/admin/tasks/{task_id}",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 200, description = "Task details", body = Task),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission"),
+        (status = 404, description = "Task not found")
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn admin_get_task(
+    State(state): State<AppState>,
+    authorized: Authorized,
     Path(task_id): Path<Uuid>,
 ) -> Result<Json<Task>> {
-    let task = state.task_service.get_task(user_id, task_id).await?;
+    authorized.require(Permission::TasksReadAny)?;
+
+    let task = state.task_service.get_any_task(authorized.organization_id, task_id).await?;
     Ok(Json(task))
 }
 
+/// Compare the workload of everyone the caller shares tasks with
+#[utoipa::path(
+    get,
+    path = "/api/tasks/workload",
+    params(
+        ("days" = Option<i64>, Query, description = "Count open tasks due within this many days (default 7, at most 90)"),
+        ("throughput_days" = Option<i64>, Query, description = "Count completions over this many past days (default 14, at most 90)")
+    ),
+    responses(
+        (status = 200, description = "Open, overdue, due-soon and recently completed tasks per person", body = WorkloadResponse),
+        (status = 400, description = "Day range out of bounds"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_workload(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Query(query): Query<WorkloadQuery>,
+) -> Result<Json<WorkloadResponse>> {
+    let workload = state
+        .task_service
+        .get_workload(
+            organization_id,
+            user_id,
+            None,
+            query.days.unwrap_or(7),
+            query.throughput_days.unwrap_or(14),
+        )
+        .await?;
+
+    Ok(Json(workload))
+}
+
+/// Compare the workload of chosen users (requires `tasks.read_any`)
+#[utoipa::path(
+    get,
+    path = "/api/admin/workload",
+    params(
+        ("user_ids" = String, Query, description = "Comma-separated user IDs"),
+        ("days" = Option<i64>, Query, description = "Count open tasks due within this many days (default 7, at most 90)"),
+        ("throughput_days" = Option<i64>, Query, description = "Count completions over this many past days (default 14, at most 90)")
+    ),
+    responses(
+        (status = 200, description = "Open, overdue, due-soon and recently completed tasks per person", body = WorkloadResponse),
+        (status = 400, description = "Invalid user ID or day range"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission")
+    ),
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+pub async fn admin_get_workload(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    Query(query): Query<AdminWorkloadQuery>,
+) -> Result<Json<WorkloadResponse>> {
+    authorized.require(Permission::TasksReadAny)?;
+
+    let mut user_ids = query
+        .user_ids
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse::<Uuid>().map_err(|_| AppError::BadRequest(format!("Invalid user ID: {}", id))))
+        .collect::<Result<Vec<_>>>()?;
+    user_ids.sort();
+    user_ids.dedup();
+
+    let workload = state
+        .task_service
+        .get_workload(
+            authorized.organization_id,
+            authorized.user_id,
+            Some(user_ids),
+            query.days.unwrap_or(7),
+            query.throughput_days.unwrap_or(14),
+        )
+        .await?;
+
+    Ok(Json(workload))
+}
+
 // ... (create_task)
 #[utoipa::path(
     post,
@@ -124,12 +305,13 @@ pub async fn get_task(
 pub async fn create_task(
     State(state): State<AppState>,
     Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
     Json(payload): Json<CreateTaskRequest>,
 ) -> Result<impl IntoResponse> {
     payload.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
-    let task = state.task_service.create_task(user_id, payload).await?;
+    let task = state.task_service.create_task(organization_id, user_id, payload).await?;
 
     // Broadcast task creation
     let _ = state.task_tx.send((user_id, task.clone()));
@@ -137,6 +319,53 @@ pub async fn create_task(
     Ok((StatusCode::CREATED, Json(task)))
 }
 
+/// Create a task from a natural-language text
+#[utoipa::path(
+    post,
+    path = "/api/tasks/quick-add",
+    request_body = QuickAddRequest,
+    responses(
+        (status = 201, description = "Task created", body = super::task_dto::QuickAddResponse),
+        (status = 200, description = "Preview of the interpretation, nothing created", body = super::task_dto::QuickAddResponse),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn quick_add_task(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Json(payload): Json<QuickAddRequest>,
+) -> Result<impl IntoResponse> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let tz = state
+        .user_repository
+        .find_by_id(user_id)
+        .await?
+        .map(|user| user.tz())
+        .unwrap_or(Tz::UTC);
+
+    let response = state
+        .task_service
+        .quick_add(organization_id, user_id, &payload.text, tz, payload.preview)
+        .await?;
+
+    let status = match response.task {
+        Some(ref task) => {
+            // Broadcast task creation
+            let _ = state.task_tx.send((user_id, task.clone()));
+            StatusCode::CREATED
+        }
+        None => StatusCode::OK,
+    };
+
+    Ok((status, Json(response)))
+}
+
 // ... (update_task)
 #[utoipa::path(
     put,
@@ -157,13 +386,14 @@ pub async fn create_task(
 pub async fn update_task(
     State(state): State<AppState>,
     Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
     Path(task_id): Path<Uuid>,
     Json(payload): Json<UpdateTaskRequest>,
 ) -> Result<Json<Task>> {
     payload.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
-    let task = state.task_service.update_task(user_id, task_id, payload).await?;
+    let task = state.task_service.update_task(organization_id, user_id, task_id, payload).await?;
 
     // Broadcast task update
     let _ = state.task_tx.send((user_id, task.clone()));
@@ -189,9 +419,10 @@ pub async fn update_task(
 pub async fn delete_task(
     State(state): State<AppState>,
     Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
     Path(task_id): Path<Uuid>,
 ) -> Result<StatusCode> {
-    let rows_affected = state.task_service.delete_task(user_id, task_id).await?;
+    let rows_affected = state.task_service.delete_task(organization_id, user_id, task_id).await?;
 
     if rows_affected == 0 {
         return Err(AppError::NotFound("Task not found".to_string()));
@@ -220,10 +451,11 @@ pub async fn delete_task(
 pub async fn update_task_status(
     State(state): State<AppState>,
     Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
     Path(task_id): Path<Uuid>,
     Json(payload): Json<UpdateTaskStatusRequest>,
 ) -> Result<Json<Task>> {
-    let task = state.task_service.update_status(user_id, task_id, payload).await?;
+    let task = state.task_service.update_status(organization_id, user_id, task_id, payload).await?;
 
     // Broadcast task status update
     let _ = state.task_tx.send((user_id, task.clone()));
@@ -247,11 +479,12 @@ pub async fn update_task_status(
 pub async fn task_stream(
     State(state): State<AppState>,
     Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
 ) -> Sse<impl Stream<Item = std::result::Result<Event, std::convert::Infallible>>> {
     let rx = state.task_tx.subscribe();
     let stream = BroadcastStream::new(rx)
         .filter_map(move |result| match result {
-            Ok((task_user_id, task)) if task_user_id == user_id => {
+            Ok((task_user_id, task)) if task_user_id == user_id && task.organization_id == organization_id => {
                 let json = serde_json::to_string(&task).ok()?;
                 Some(Ok(Event::default().data(json)))
             }
@@ -263,7 +496,7 @@ pub async fn task_stream(
 
 // Collaboration endpoints
 
-/// Share task with other users
+/// Invite registered users to a task by id; they join once they accept
 #[utoipa::path(
     post,
     path = "/api/tasks/{task_id}/share",
@@ -272,10 +505,11 @@ pub async fn task_stream(
     ),
     request_body = super::task_dto::ShareTaskRequest,
     responses(
-        (status = 200, description = "Task shared successfully"),
+        (status = 200, description = "Invitations sent", body = Vec<crate::invitation::invitation_models::TaskInvitation>),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - Only task owner can share"),
-        (status = 404, description = "Task not found")
+        (status = 400, description = "Owner role, already a member or already invited"),
+        (status = 403, description = "Forbidden - Only task owner can invite"),
+        (status = 404, description = "User not found")
     ),
     tag = "tasks",
     security(("bearer_auth" = []))
@@ -283,31 +517,25 @@ pub async fn task_stream(
 pub async fn share_task(
     State(state): State<AppState>,
     Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
     Path(task_id): Path<Uuid>,
     Json(payload): Json<super::task_dto::ShareTaskRequest>,
-) -> Result<impl IntoResponse> {
+) -> Result<Json<Vec<crate::invitation::invitation_models::TaskInvitation>>> {
     payload.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
-    state.task_service.share_task(task_id, payload.user_ids.clone(), user_id).await?;
-
-    // Broadcast task shared event via WebSocket
-    for shared_user_id in payload.user_ids {
-        let task = state.task_service.get_task(user_id, task_id).await?;
-        let ws_message = crate::websocket::types::WsMessage::TaskShared(
-            crate::websocket::types::TaskSharedPayload {
-                task_id,
-                task_title: task.title.clone(),
-                shared_by: user_id,
-                shared_by_username: state.user_repository.find_by_id(user_id).await?
-                    .map(|u| u.username)
-                    .unwrap_or_else(|| "Unknown".to_string()),
-            }
-        );
-        state.ws_connections.send_to_user(&shared_user_id, ws_message);
+    let sent = state
+        .invitation_service
+        .invite_user_ids(organization_id, task_id, user_id, payload.user_ids, payload.role)
+        .await?;
+
+    let mut invitations = Vec::with_capacity(sent.len());
+    for sent in sent {
+        crate::invitation::invitation_handlers::notify_invitee(&state, &sent).await?;
+        invitations.push(sent.invitation);
     }
 
-    Ok(StatusCode::OK)
+    Ok(Json(invitations))
 }
 
 /// Remove collaborator from task
@@ -330,12 +558,13 @@ pub async fn share_task(
 pub async fn remove_task_member(
     State(state): State<AppState>,
     Extension(requesting_user): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
     Path((task_id, user_id)): Path<(Uuid, Uuid)>,
 ) -> Result<StatusCode> {
-    state.task_service.remove_collaborator(task_id, user_id, requesting_user).await?;
+    state.task_service.remove_collaborator(organization_id, task_id, user_id, requesting_user).await?;
 
     // Broadcast member removed event via WebSocket
-    let task = state.task_service.get_task(requesting_user, task_id).await?;
+    let task = state.task_service.get_task(organization_id, requesting_user, task_id).await?;
     let ws_message = crate::websocket::types::WsMessage::TaskMemberRemoved(
         crate::websocket::types::TaskMemberRemovedPayload {
             task_id,
@@ -348,6 +577,102 @@ pub async fn remove_task_member(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Make a task member a viewer or an editor
+#[utoipa::path(
+    put,
+    path = "/api/tasks/{task_id}/members/{user_id}",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ("user_id" = Uuid, Path, description = "Member's user ID")
+    ),
+    request_body = super::task_dto::UpdateTaskMemberRoleRequest,
+    responses(
+        (status = 200, description = "Task members after the change", body = Vec<super::task_models::TaskMemberInfo>),
+        (status = 400, description = "The owner's role cannot be changed this way"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only task owner can change roles"),
+        (status = 404, description = "User is not a task member")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn update_task_member_role(
+    State(state): State<AppState>,
+    Extension(requesting_user): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path((task_id, member_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<super::task_dto::UpdateTaskMemberRoleRequest>,
+) -> Result<Json<Vec<super::task_models::TaskMemberInfo>>> {
+    let members = state
+        .task_service
+        .change_member_role(organization_id, task_id, member_id, payload.role, requesting_user)
+        .await?;
+
+    let task = state.task_service.get_task(organization_id, requesting_user, task_id).await?;
+    let ws_message = crate::websocket::types::WsMessage::TaskMemberRoleChanged(
+        crate::websocket::types::TaskMemberRoleChangedPayload {
+            task_id,
+            task_title: task.title,
+            role: payload.role,
+            changed_by: requesting_user,
+        }
+    );
+    state.ws_connections.send_to_user(&member_id, ws_message);
+
+    Ok(Json(members))
+}
+
+/// Transfer ownership of a task to another member
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{task_id}/transfer",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID")
+    ),
+    request_body = super::task_dto::TransferOwnershipRequest,
+    responses(
+        (status = 200, description = "Ownership transferred; the previous owner is now an editor", body = Task),
+        (status = 400, description = "New owner is not a task member"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only task owner can transfer ownership")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn transfer_task_ownership(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path(task_id): Path<Uuid>,
+    Json(payload): Json<super::task_dto::TransferOwnershipRequest>,
+) -> Result<Json<Task>> {
+    let task = state
+        .task_service
+        .transfer_ownership(organization_id, task_id, payload.new_owner_id, user_id)
+        .await?;
+
+    let previous_owner = state.user_repository.find_by_id(user_id).await?
+        .map(|u| u.username)
+        .unwrap_or_else(|| "Unknown".to_string());
+    let message = format!("{} made you the owner of \"{}\"", previous_owner, task.title);
+    state.notification_repository.create(task.user_id, Some(task.id), &message).await?;
+    let _ = state.notification_tx.send(format!("{}:{}", task.user_id, message));
+
+    let ws_message = crate::websocket::types::WsMessage::TaskOwnershipTransferred(
+        crate::websocket::types::TaskOwnershipTransferredPayload {
+            task_id,
+            task_title: task.title.clone(),
+            previous_owner_id: user_id,
+            new_owner_id: task.user_id,
+        }
+    );
+    for member in state.task_service.get_task_members(organization_id, task_id, user_id).await? {
+        state.ws_connections.send_to_user(&member.user_id, ws_message.clone());
+    }
+
+    Ok(Json(task))
+}
+
 /// Get task members
 #[utoipa::path(
     get,
@@ -367,9 +692,10 @@ pub async fn remove_task_member(
 pub async fn get_task_members(
     State(state): State<AppState>,
     Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
     Path(task_id): Path<Uuid>,
 ) -> Result<Json<Vec<super::task_models::TaskMemberInfo>>> {
-    let members = state.task_service.get_task_members(task_id, user_id).await?;
+    let members = state.task_service.get_task_members(organization_id, task_id, user_id).await?;
     Ok(Json(members))
 }
 
@@ -392,8 +718,132 @@ pub async fn get_task_members(
 pub async fn get_task_activity(
     State(state): State<AppState>,
     Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
     Path(task_id): Path<Uuid>,
 ) -> Result<Json<Vec<super::task_dto::TaskActivityResponse>>> {
-    let activity = state.task_service.get_task_activity(task_id, user_id).await?;
+    let activity = state.task_service.get_task_activity(organization_id, task_id, user_id).await?;
     Ok(Json(activity))
 }
+
+// Reminder endpoints
+
+/// Get task reminders
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{task_id}/reminders",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 200, description = "Task reminders retrieved successfully", body = Vec<TaskReminder>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Access denied")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_task_reminders(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path(task_id): Path<Uuid>,
+) -> Result<Json<Vec<TaskReminder>>> {
+    let reminders = state.task_service.get_reminders(organization_id, task_id, user_id).await?;
+    Ok(Json(reminders))
+}
+
+/// Add a reminder to a task
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{task_id}/reminders",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID")
+    ),
+    request_body = CreateReminderRequest,
+    responses(
+        (status = 201, description = "Reminder created", body = TaskReminder),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Access denied")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn add_task_reminder(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path(task_id): Path<Uuid>,
+    Json(payload): Json<CreateReminderRequest>,
+) -> Result<impl IntoResponse> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let reminder = state.task_service.add_reminder(organization_id, task_id, user_id, payload).await?;
+
+    Ok((StatusCode::CREATED, Json(reminder)))
+}
+
+/// Delete a task reminder
+#[utoipa::path(
+    delete,
+    path = "/api/tasks/{task_id}/reminders/{reminder_id}",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ("reminder_id" = Uuid, Path, description = "Reminder ID")
+    ),
+    responses(
+        (status = 204, description = "Reminder deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Access denied"),
+        (status = 404, description = "Reminder not found")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_task_reminder(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path((task_id, reminder_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    state.task_service.delete_reminder(organization_id, task_id, reminder_id, user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Snooze a reminder that has already fired
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{task_id}/reminders/{reminder_id}/snooze",
+    params(
+        ("task_id" = Uuid, Path, description = "Task ID"),
+        ("reminder_id" = Uuid, Path, description = "Reminder ID")
+    ),
+    request_body = SnoozeReminderRequest,
+    responses(
+        (status = 200, description = "Reminder rescheduled", body = TaskReminder),
+        (status = 400, description = "Reminder has not fired or invalid snooze time"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Only the reminded user can snooze"),
+        (status = 404, description = "Reminder not found")
+    ),
+    tag = "tasks",
+    security(("bearer_auth" = []))
+)]
+pub async fn snooze_task_reminder(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<Uuid>,
+    CurrentOrganization(organization_id): CurrentOrganization,
+    Path((task_id, reminder_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<SnoozeReminderRequest>,
+) -> Result<Json<TaskReminder>> {
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let reminder = state
+        .task_service
+        .snooze_reminder(organization_id, task_id, reminder_id, user_id, payload)
+        .await?;
+
+    Ok(Json(reminder))
+}
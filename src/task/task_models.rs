@@ -44,17 +44,49 @@ impl std::fmt::Display for TaskPriority {
     }
 }
 
+/// What a task member may do; each role includes the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskRole {
+    /// Read the task, its members and activity, and set own reminders
+    Viewer,
+    /// Also change the task and its status
+    Editor,
+    /// Also share, delete, manage members and transfer ownership
+    Owner,
+}
+
+impl TaskRole {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "viewer" => Some(TaskRole::Viewer),
+            "editor" => Some(TaskRole::Editor),
+            "owner" => Some(TaskRole::Owner),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TaskRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskRole::Viewer => write!(f, "viewer"),
+            TaskRole::Editor => write!(f, "editor"),
+            TaskRole::Owner => write!(f, "owner"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Task {
     pub id: Uuid,
     pub user_id: Uuid,
+    pub organization_id: Uuid,
     pub title: String,
     pub description: Option<String>,
     pub status: String,
     pub priority: String,
     pub due_date: Option<DateTime<Utc>>,
-    pub reminder_time: Option<DateTime<Utc>>,
-    pub notified: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -79,6 +111,31 @@ pub struct TaskActivity {
     pub created_at: DateTime<Utc>,
 }
 
+/// A reminder addressed to one task member, either at a fixed time or a
+/// number of minutes before the task is due.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TaskReminder {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub user_id: Uuid,
+    pub remind_at: Option<DateTime<Utc>>,
+    pub offset_minutes: Option<i32>,
+    pub fire_at: Option<DateTime<Utc>>,
+    pub notified_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A reminder whose time has come, joined with what the message needs.
+#[derive(Debug, Clone, FromRow)]
+pub struct DueReminder {
+    pub reminder_id: Uuid,
+    pub task_id: Uuid,
+    pub user_id: Uuid,
+    pub title: String,
+    pub due_date: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TaskWithMembers {
     #[serde(flatten)]
@@ -115,4 +172,12 @@ mod tests {
         assert_eq!(TaskPriority::High.to_string(), "High");
         assert_eq!(TaskPriority::Urgent.to_string(), "Urgent");
     }
+
+    #[test]
+    fn test_task_roles_are_ordered_by_what_they_allow() {
+        assert!(TaskRole::Viewer < TaskRole::Editor);
+        assert!(TaskRole::Editor < TaskRole::Owner);
+        assert_eq!(TaskRole::parse(&TaskRole::Editor.to_string()), Some(TaskRole::Editor));
+        assert_eq!(TaskRole::parse("collaborator"), None);
+    }
 }
@@ -0,0 +1,317 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+use super::task_dto::QuickAddInterpretation;
+use crate::error::{AppError, Result};
+
+/// Time of day used when a quick-add text names a day but no time.
+const DEFAULT_DUE_TIME: (u32, u32) = (9, 0);
+/// Longest reminder lead time, the limit `CreateReminderRequest` enforces.
+const MAX_REMINDER_OFFSET_MINUTES: i64 = 525_600;
+
+/// Parse texts such as `Call supplier !high tomorrow 9am remind 1h before`.
+///
+/// Supported fragments, anywhere in the text:
+/// - priority: `!low`, `!medium`, `!high`, `!urgent`
+/// - day: `today`, `tomorrow`, a weekday (optionally preceded by `next`),
+///   or an ISO date such as `2025-03-14`
+/// - time: `9am`, `9:30pm`, `9 am`, `17:00`, `noon`, `midnight`, optionally preceded by `at`
+/// - reminder: `remind 1h before`, `remind 30 minutes before`
+///
+/// Relative days and times are resolved in `tz`. Everything else is the title.
+pub fn parse_quick_add(input: &str, now: DateTime<Utc>, tz: Tz) -> Result<QuickAddInterpretation> {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let today = now.with_timezone(&tz).date_naive();
+
+    let mut title_words = Vec::new();
+    let mut recognized = Vec::new();
+    let mut priority = None;
+    let mut date: Option<NaiveDate> = None;
+    let mut time: Option<NaiveTime> = None;
+    let mut remind_before: Option<Duration> = None;
+
+    let mut i = 0;
+    while i < words.len() {
+        let word = normalize(words[i]);
+        let rest = &words[i + 1..];
+
+        if let Some(p) = word.strip_prefix('!').and_then(parse_priority) {
+            priority = Some(p.to_string());
+            recognized.push(words[i].to_string());
+            i += 1;
+            continue;
+        }
+
+        if word == "remind" || word == "reminder" {
+            if let Some((offset, used)) = parse_duration(rest) {
+                let offset = offset.ok_or_else(offset_too_long)?;
+                let mut consumed = 1 + used;
+                if rest.get(used).map(|w| normalize(w)) == Some("before".to_string()) {
+                    consumed += 1;
+                }
+                remind_before = Some(offset);
+                recognized.push(words[i..i + consumed].join(" "));
+                i += consumed;
+                continue;
+            }
+        }
+
+        if let Some((day, used)) = parse_day(&word, rest, today) {
+            date = Some(day);
+            recognized.push(words[i..i + 1 + used].join(" "));
+            i += 1 + used;
+            continue;
+        }
+
+        let (time_start, skip) = if word == "at" { (i + 1, 1) } else { (i, 0) };
+        if let Some((at, used)) = words.get(time_start).and_then(|w| parse_time(&normalize(w), &words[time_start + 1..])) {
+            time = Some(at);
+            let consumed = skip + 1 + used;
+            recognized.push(words[i..i + consumed].join(" "));
+            i += consumed;
+            continue;
+        }
+
+        title_words.push(words[i]);
+        i += 1;
+    }
+
+    let title = title_words.join(" ");
+    if title.is_empty() {
+        return Err(AppError::Validation("Quick-add text must contain a title".to_string()));
+    }
+
+    let due_date = match (date, time) {
+        (None, None) => None,
+        (Some(day), at) => {
+            let at = at.unwrap_or_else(|| NaiveTime::from_hms_opt(DEFAULT_DUE_TIME.0, DEFAULT_DUE_TIME.1, 0).unwrap());
+            Some(resolve_local(day, at, tz))
+        }
+        // A bare time means the next time the clock shows it.
+        (None, Some(at)) => {
+            let candidate = resolve_local(today, at, tz);
+            if candidate > now {
+                Some(candidate)
+            } else {
+                Some(resolve_local(today + Duration::days(1), at, tz))
+            }
+        }
+    };
+
+    let reminder_time = match (remind_before, due_date) {
+        (Some(offset), Some(due)) => Some(
+            due.checked_sub_signed(offset)
+                .ok_or_else(|| AppError::Validation("Reminder time is out of range".to_string()))?,
+        ),
+        (Some(_), None) => {
+            return Err(AppError::Validation(
+                "A relative reminder needs a due date or time".to_string(),
+            ))
+        }
+        _ => None,
+    };
+
+    Ok(QuickAddInterpretation {
+        title,
+        priority,
+        due_date,
+        reminder_time,
+        reminder_offset_minutes: remind_before.map(offset_minutes).transpose()?,
+        recognized,
+    })
+}
+
+/// Whole minutes of a reminder offset, within the range reminders accept.
+fn offset_minutes(offset: Duration) -> Result<i32> {
+    i32::try_from(offset.num_minutes())
+        .ok()
+        .filter(|minutes| (0..=MAX_REMINDER_OFFSET_MINUTES).contains(&i64::from(*minutes)))
+        .ok_or_else(offset_too_long)
+}
+
+fn offset_too_long() -> AppError {
+    AppError::Validation(format!(
+        "A reminder can be at most {} minutes before the due date",
+        MAX_REMINDER_OFFSET_MINUTES
+    ))
+}
+
+fn normalize(word: &str) -> String {
+    word.trim_end_matches([',', '.', ';']).to_lowercase()
+}
+
+fn parse_priority(word: &str) -> Option<&'static str> {
+    match word {
+        "low" => Some("Low"),
+        "medium" | "med" => Some("Medium"),
+        "high" => Some("High"),
+        "urgent" => Some("Urgent"),
+        _ => None,
+    }
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Returns the day and how many words after `word` it consumed.
+fn parse_day(word: &str, rest: &[&str], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    match word {
+        "today" | "tonight" => return Some((today, 0)),
+        "tomorrow" | "tmr" => return Some((today + Duration::days(1), 0)),
+        _ => {}
+    }
+
+    let (weekday, used) = if word == "next" {
+        (parse_weekday(&normalize(rest.first()?))?, 1)
+    } else if let Some(weekday) = parse_weekday(word) {
+        (weekday, 0)
+    } else {
+        return NaiveDate::parse_from_str(word, "%Y-%m-%d").ok().map(|d| (d, 0));
+    };
+
+    // Always the upcoming occurrence, never today.
+    let ahead = (weekday.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64 + 7) % 7;
+    let ahead = if ahead == 0 { 7 } else { ahead };
+    Some((today + Duration::days(ahead), used))
+}
+
+/// Returns the time and how many words after `word` it consumed.
+fn parse_time(word: &str, rest: &[&str]) -> Option<(NaiveTime, usize)> {
+    match word {
+        "noon" => return Some((NaiveTime::from_hms_opt(12, 0, 0)?, 0)),
+        "midnight" => return Some((NaiveTime::from_hms_opt(0, 0, 0)?, 0)),
+        _ => {}
+    }
+
+    let (clock, meridiem, used) = if let Some(clock) = word.strip_suffix("am") {
+        (clock, Some(false), 0)
+    } else if let Some(clock) = word.strip_suffix("pm") {
+        (clock, Some(true), 0)
+    } else {
+        match rest.first().map(|w| normalize(w)).as_deref() {
+            Some("am") => (word, Some(false), 1),
+            Some("pm") => (word, Some(true), 1),
+            _ => (word, None, 0),
+        }
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        // A bare number is only a time with am/pm, otherwise it's part of the title.
+        None if meridiem.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+
+    let hour = match meridiem {
+        Some(pm) if (1..=12).contains(&hour) => hour % 12 + if pm { 12 } else { 0 },
+        Some(_) => return None,
+        None => hour,
+    };
+
+    NaiveTime::from_hms_opt(hour, minute, 0).map(|t| (t, used))
+}
+
+/// Parse `1h`, `30m`, `2d`, `1w` or `30 minutes`, returning the words consumed.
+/// The duration is `None` when it is longer than a reminder may be.
+fn parse_duration(words: &[&str]) -> Option<(Option<Duration>, usize)> {
+    let first = normalize(words.first()?);
+    let split = first.find(|c: char| !c.is_ascii_digit()).unwrap_or(first.len());
+    let (amount, unit, used) = if split == first.len() {
+        (first.parse::<i64>().ok()?, normalize(words.get(1)?), 2)
+    } else {
+        (first[..split].parse::<i64>().ok()?, first[split..].to_string(), 1)
+    };
+
+    let offset = match unit.as_str() {
+        "m" | "min" | "mins" | "minute" | "minutes" => Duration::try_minutes(amount),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Duration::try_hours(amount),
+        "d" | "day" | "days" => Duration::try_days(amount),
+        "w" | "week" | "weeks" => Duration::try_weeks(amount),
+        _ => return None,
+    };
+
+    Some((offset.filter(|offset| offset.num_minutes() <= MAX_REMINDER_OFFSET_MINUTES), used))
+}
+
+fn resolve_local(day: NaiveDate, at: NaiveTime, tz: Tz) -> DateTime<Utc> {
+    let local = day.and_time(at);
+    tz.from_local_datetime(&local)
+        .earliest()
+        // Inside a DST gap: take the same wall-clock time an hour later.
+        .or_else(|| tz.from_local_datetime(&(local + Duration::hours(1))).earliest())
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&local))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Wednesday, 12:00 in Berlin.
+    fn now() -> DateTime<Utc> {
+        "2025-03-05T11:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_parses_priority_day_time_and_reminder() {
+        let parsed = parse_quick_add(
+            "Call supplier !high tomorrow 9am remind 1h before",
+            now(),
+            chrono_tz::Europe::Berlin,
+        )
+        .unwrap();
+
+        assert_eq!(parsed.title, "Call supplier");
+        assert_eq!(parsed.priority.as_deref(), Some("High"));
+        assert_eq!(parsed.due_date, Some("2025-03-06T08:00:00Z".parse().unwrap()));
+        assert_eq!(parsed.reminder_time, Some("2025-03-06T07:00:00Z".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_parses_weekday_and_pm_time() {
+        let parsed = parse_quick_add("Submit report friday 5pm", now(), chrono_tz::Europe::Berlin).unwrap();
+
+        assert_eq!(parsed.title, "Submit report");
+        assert_eq!(parsed.priority, None);
+        assert_eq!(parsed.due_date, Some("2025-03-07T16:00:00Z".parse().unwrap()));
+        assert_eq!(parsed.reminder_time, None);
+    }
+
+    #[test]
+    fn test_bare_time_rolls_over_to_tomorrow() {
+        let parsed = parse_quick_add("Standup at 9:30", now(), Tz::UTC).unwrap();
+        assert_eq!(parsed.title, "Standup");
+        assert_eq!(parsed.due_date, Some("2025-03-06T09:30:00Z".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_plain_numbers_stay_in_title() {
+        let parsed = parse_quick_add("Order 5 chairs", now(), Tz::UTC).unwrap();
+        assert_eq!(parsed.title, "Order 5 chairs");
+        assert_eq!(parsed.due_date, None);
+    }
+
+    #[test]
+    fn test_rejects_missing_title_and_dangling_reminder() {
+        assert!(parse_quick_add("tomorrow 9am", now(), Tz::UTC).is_err());
+        assert!(parse_quick_add("Pay rent remind 1d before", now(), Tz::UTC).is_err());
+    }
+
+    #[test]
+    fn test_rejects_out_of_range_reminder_offsets() {
+        assert!(parse_quick_add("Pay rent tomorrow remind 99999999999999w before", now(), Tz::UTC).is_err());
+        assert!(parse_quick_add("Pay rent tomorrow remind 999999999d before", now(), Tz::UTC).is_err());
+        assert!(parse_quick_add("Pay rent tomorrow remind 366d before", now(), Tz::UTC).is_err());
+        assert!(parse_quick_add("Pay rent tomorrow remind 365d before", now(), Tz::UTC).is_ok());
+    }
+}
@@ -2,8 +2,17 @@ use crate::error::Result;
 use sqlx::PgPool;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
-use super::task_models::Task;
+use super::task_models::{DueReminder, Task, TaskReminder, TaskRole};
+use super::task_dto::MemberWorkload;
 
+/// Tasks of organization `$1` visible to `$2`: owned outright or shared
+/// through `task_members`.
+pub(crate) const ACCESSIBLE_TASKS: &str = "FROM tasks t
+     LEFT JOIN task_members tm ON t.id = tm.task_id
+     WHERE t.organization_id = $1 AND (t.user_id = $2 OR tm.user_id = $2)";
+
+/// Every query is scoped to one organization: a task, and everything hanging
+/// off it, is only found through the organization it belongs to.
 #[derive(Clone)]
 pub struct TaskRepository {
     pool: PgPool,
@@ -24,10 +33,15 @@ impl TaskRepository {
         Self { pool }
     }
 
-    pub async fn find_all(&self, user_id: Uuid, filters: TaskFilters) -> Result<(Vec<Task>, i64)> {
-        let mut query = "SELECT * FROM tasks WHERE user_id = $1".to_string();
-        let mut count_query = "SELECT COUNT(*) FROM tasks WHERE user_id = $1".to_string();
-        let mut params_count = 1;
+    pub async fn find_all(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        filters: TaskFilters,
+    ) -> Result<(Vec<Task>, i64)> {
+        let mut query = "SELECT * FROM tasks WHERE organization_id = $1 AND user_id = $2".to_string();
+        let mut count_query = "SELECT COUNT(*) FROM tasks WHERE organization_id = $1 AND user_id = $2".to_string();
+        let mut params_count = 2;
 
         if let Some(ref _status) = filters.status {
             params_count += 1;
@@ -51,7 +65,9 @@ impl TaskRepository {
         }
 
         // Calculate total count before pagination
-        let mut count_db_query = sqlx::query_scalar::<_, i64>(&count_query).bind(user_id);
+        let mut count_db_query = sqlx::query_scalar::<_, i64>(&count_query)
+            .bind(organization_id)
+            .bind(user_id);
 
         if let Some(status) = &filters.status {
             count_db_query = count_db_query.bind(status);
@@ -88,7 +104,9 @@ impl TaskRepository {
 
         query.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
 
-        let mut db_query = sqlx::query_as::<_, Task>(&query).bind(user_id);
+        let mut db_query = sqlx::query_as::<_, Task>(&query)
+            .bind(organization_id)
+            .bind(user_id);
 
         if let Some(status) = filters.status {
             db_query = db_query.bind(status);
@@ -107,26 +125,36 @@ impl TaskRepository {
         Ok((tasks, total_count))
     }
 
-    pub async fn find_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Option<Task>> {
-        let task = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = $1 AND user_id = $2")
-            .bind(id)
-            .bind(user_id)
-            .fetch_optional(&self.pool)
-            .await?;
+    pub async fn find_by_id(&self, organization_id: Uuid, id: Uuid, user_id: Uuid) -> Result<Option<Task>> {
+        let task = sqlx::query_as::<_, Task>(
+            "SELECT * FROM tasks WHERE id = $1 AND user_id = $2 AND organization_id = $3"
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(organization_id)
+        .fetch_optional(&self.pool)
+        .await?;
         Ok(task)
     }
 
+    /// Create a task with its creator as owner and its first reminders,
+    /// given as `(remind_at, offset_minutes)` and all for the creator. Either
+    /// everything is written or nothing is.
+    #[allow(clippy::too_many_arguments)]
     pub async fn create(
         &self,
+        organization_id: Uuid,
         user_id: Uuid,
         title: &str,
         description: Option<&str>,
         priority: &str,
         due_date: Option<DateTime<Utc>>,
-        reminder_time: Option<DateTime<Utc>>,
+        reminders: &[(Option<DateTime<Utc>>, Option<i32>)],
     ) -> Result<Task> {
+        let mut tx = self.pool.begin().await?;
+
         let task = sqlx::query_as::<_, Task>(
-            "INSERT INTO tasks (user_id, title, description, priority, due_date, reminder_time)
+            "INSERT INTO tasks (user_id, title, description, priority, due_date, organization_id)
              VALUES ($1, $2, $3, $4, $5, $6)
              RETURNING *"
         )
@@ -135,23 +163,57 @@ impl TaskRepository {
         .bind(description)
         .bind(priority)
         .bind(due_date)
-        .bind(reminder_time)
-        .fetch_one(&self.pool)
+        .bind(organization_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO task_members (task_id, user_id, role, added_by)
+             VALUES ($1, $2, 'owner', $2)"
+        )
+        .bind(task.id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO task_activity (task_id, user_id, action, details)
+             VALUES ($1, $2, 'created', $3)"
+        )
+        .bind(task.id)
+        .bind(user_id)
+        .bind(serde_json::json!({"title": task.title}))
+        .execute(&mut *tx)
         .await?;
 
+        for (remind_at, offset_minutes) in reminders {
+            sqlx::query(
+                "INSERT INTO task_reminders (task_id, user_id, remind_at, offset_minutes, fire_at, created_by)
+                 VALUES ($1, $2, $3, $4, COALESCE($3, $5 - make_interval(mins => $4)), $2)"
+            )
+            .bind(task.id)
+            .bind(user_id)
+            .bind(remind_at)
+            .bind(offset_minutes)
+            .bind(task.due_date)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
         Ok(task)
     }
 
     pub async fn update(
         &self,
+        organization_id: Uuid,
         id: Uuid,
-        user_id: Uuid,
         title: Option<&str>,
         description: Option<&str>,
         status: Option<&str>,
         priority: Option<&str>,
         due_date: Option<DateTime<Utc>>,
-        reminder_time: Option<DateTime<Utc>>,
     ) -> Result<Task> {
         let task = sqlx::query_as::<_, Task>(
             "UPDATE tasks SET
@@ -160,10 +222,8 @@ impl TaskRepository {
                 status = COALESCE($3, status),
                 priority = COALESCE($4, priority),
                 due_date = COALESCE($5, due_date),
-                reminder_time = COALESCE($6, reminder_time),
-                notified = CASE WHEN $6 IS NOT NULL THEN false ELSE notified END,
                 updated_at = NOW()
-             WHERE id = $7 AND user_id = $8
+             WHERE id = $6 AND organization_id = $7
              RETURNING *"
         )
         .bind(title)
@@ -171,34 +231,34 @@ impl TaskRepository {
         .bind(status)
         .bind(priority)
         .bind(due_date)
-        .bind(reminder_time)
         .bind(id)
-        .bind(user_id)
+        .bind(organization_id)
         .fetch_one(&self.pool)
         .await?;
 
         Ok(task)
     }
 
-    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64> {
-        let result = sqlx::query("DELETE FROM tasks WHERE id = $1 AND user_id = $2")
+    pub async fn delete(&self, organization_id: Uuid, id: Uuid, user_id: Uuid) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM tasks WHERE id = $1 AND user_id = $2 AND organization_id = $3")
             .bind(id)
             .bind(user_id)
+            .bind(organization_id)
             .execute(&self.pool)
             .await?;
         
         Ok(result.rows_affected())
     }
 
-    pub async fn update_status(&self, id: Uuid, user_id: Uuid, status: &str) -> Result<Option<Task>> {
+    pub async fn update_status(&self, organization_id: Uuid, id: Uuid, status: &str) -> Result<Option<Task>> {
         let task = sqlx::query_as::<_, Task>(
             "UPDATE tasks SET status = $1, updated_at = NOW()
-             WHERE id = $2 AND user_id = $3
+             WHERE id = $2 AND organization_id = $3
              RETURNING *"
         )
         .bind(status)
         .bind(id)
-        .bind(user_id)
+        .bind(organization_id)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -206,163 +266,436 @@ impl TaskRepository {
     }
 
 
-    pub async fn find_due_reminders(&self) -> Result<Vec<Task>> {
+    /// Reminders that are due and not yet sent, for tasks that are still open.
+    ///
+    /// Runs for the reminder scheduler across all organizations; each
+    /// reminder only ever reaches the member it belongs to.
+    pub async fn find_due_reminders(&self) -> Result<Vec<DueReminder>> {
         let now = Utc::now();
-        let tasks = sqlx::query_as::<_, Task>(
-            "SELECT * FROM tasks 
-             WHERE reminder_time <= $1 
-             AND notified = false 
-             AND reminder_time IS NOT NULL"
+        let reminders = sqlx::query_as::<_, DueReminder>(
+            "SELECT r.id AS reminder_id, r.task_id, r.user_id, t.title, t.due_date
+             FROM task_reminders r
+             JOIN tasks t ON t.id = r.task_id
+             WHERE r.fire_at <= $1
+             AND r.notified_at IS NULL
+             AND t.status NOT IN ('Completed', 'Archived')
+             ORDER BY r.fire_at ASC"
         )
         .bind(now)
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(tasks)
+        Ok(reminders)
     }
 
-    pub async fn mark_as_notified(&self, id: Uuid) -> Result<()> {
-        sqlx::query("UPDATE tasks SET notified = true WHERE id = $1")
-            .bind(id)
+    /// Scheduler counterpart of `find_due_reminders`, so not organization-scoped either.
+    pub async fn mark_as_notified(&self, reminder_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE task_reminders SET notified_at = NOW() WHERE id = $1")
+            .bind(reminder_id)
             .execute(&self.pool)
             .await?;
 
         Ok(())
     }
 
-    pub async fn get_user_stats(&self, user_id: Uuid) -> Result<(i64, i64, i64, i64, i64, i64, i64, i64, i64)> {
-        let total_tasks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tasks WHERE user_id = $1")
-            .bind(user_id)
-            .fetch_one(&self.pool)
-            .await?;
-
-        let pending_tasks: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND status = 'Pending'"
+    pub async fn create_reminder(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        user_id: Uuid,
+        remind_at: Option<DateTime<Utc>>,
+        offset_minutes: Option<i32>,
+        created_by: Uuid,
+    ) -> Result<TaskReminder> {
+        let reminder = sqlx::query_as::<_, TaskReminder>(
+            "INSERT INTO task_reminders (task_id, user_id, remind_at, offset_minutes, fire_at, created_by)
+             SELECT t.id, $2, $3, $4, COALESCE($3, t.due_date - make_interval(mins => $4)), $5
+             FROM tasks t
+             WHERE t.id = $1 AND t.organization_id = $6
+             RETURNING *"
         )
+        .bind(task_id)
         .bind(user_id)
+        .bind(remind_at)
+        .bind(offset_minutes)
+        .bind(created_by)
+        .bind(organization_id)
         .fetch_one(&self.pool)
         .await?;
 
-        let in_progress_tasks: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND status = 'InProgress'"
+        Ok(reminder)
+    }
+
+    pub async fn get_task_reminders(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+    ) -> Result<Vec<TaskReminder>> {
+        let reminders = sqlx::query_as::<_, TaskReminder>(
+            "SELECT r.* FROM task_reminders r
+             JOIN tasks t ON t.id = r.task_id
+             WHERE r.task_id = $1 AND t.organization_id = $2
+             ORDER BY r.fire_at ASC NULLS LAST, r.created_at ASC"
         )
-        .bind(user_id)
-        .fetch_one(&self.pool)
+        .bind(task_id)
+        .bind(organization_id)
+        .fetch_all(&self.pool)
         .await?;
 
-        let completed_tasks: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND status = 'Completed'"
+        Ok(reminders)
+    }
+
+    pub async fn find_reminder(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        reminder_id: Uuid,
+    ) -> Result<Option<TaskReminder>> {
+        let reminder = sqlx::query_as::<_, TaskReminder>(
+            "SELECT r.* FROM task_reminders r
+             JOIN tasks t ON t.id = r.task_id
+             WHERE r.id = $1 AND r.task_id = $2 AND t.organization_id = $3"
         )
-        .bind(user_id)
-        .fetch_one(&self.pool)
+        .bind(reminder_id)
+        .bind(task_id)
+        .bind(organization_id)
+        .fetch_optional(&self.pool)
         .await?;
 
-        let archived_tasks: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND status = 'Archived'"
+        Ok(reminder)
+    }
+
+    pub async fn delete_reminder(&self, organization_id: Uuid, reminder_id: Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM task_reminders r USING tasks t
+             WHERE r.id = $1 AND t.id = r.task_id AND t.organization_id = $2"
         )
-        .bind(user_id)
-        .fetch_one(&self.pool)
+        .bind(reminder_id)
+        .bind(organization_id)
+        .execute(&self.pool)
         .await?;
 
-        let low_priority_tasks: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND priority = 'Low'"
+        Ok(result.rows_affected())
+    }
+
+    /// Re-arm a reminder at `until`. A snoozed reminder becomes absolute so a
+    /// later due-date change does not undo the snooze.
+    pub async fn snooze_reminder(
+        &self,
+        organization_id: Uuid,
+        reminder_id: Uuid,
+        until: DateTime<Utc>,
+    ) -> Result<TaskReminder> {
+        let reminder = sqlx::query_as::<_, TaskReminder>(
+            "UPDATE task_reminders r
+             SET remind_at = $1, offset_minutes = NULL, fire_at = $1, notified_at = NULL
+             FROM tasks t
+             WHERE r.id = $2 AND t.id = r.task_id AND t.organization_id = $3
+             RETURNING r.*"
         )
-        .bind(user_id)
+        .bind(until)
+        .bind(reminder_id)
+        .bind(organization_id)
         .fetch_one(&self.pool)
         .await?;
 
-        let medium_priority_tasks: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND priority = 'Medium'"
+        Ok(reminder)
+    }
+
+    /// Recompute due-relative reminders after a task's due date changed.
+    /// Reminders whose firing time moved are re-armed.
+    pub async fn reschedule_relative_reminders(&self, organization_id: Uuid, task_id: Uuid) -> Result<()> {
+        sqlx::query(
+            "UPDATE task_reminders r SET
+                fire_at = t.due_date - make_interval(mins => r.offset_minutes),
+                notified_at = CASE
+                    WHEN r.fire_at IS DISTINCT FROM t.due_date - make_interval(mins => r.offset_minutes)
+                    THEN NULL ELSE r.notified_at END
+             FROM tasks t
+             WHERE t.id = r.task_id AND r.task_id = $1 AND t.organization_id = $2
+               AND r.offset_minutes IS NOT NULL"
         )
-        .bind(user_id)
-        .fetch_one(&self.pool)
+        .bind(task_id)
+        .bind(organization_id)
+        .execute(&self.pool)
         .await?;
 
-        let high_priority_tasks: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND priority = 'High'"
+        Ok(())
+    }
+
+    pub async fn get_user_stats(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(i64, i64, i64, i64, i64, i64, i64, i64, i64)> {
+        let stats = sqlx::query_as(
+            "SELECT
+                COUNT(*),
+                COUNT(*) FILTER (WHERE status = 'Pending'),
+                COUNT(*) FILTER (WHERE status = 'InProgress'),
+                COUNT(*) FILTER (WHERE status = 'Completed'),
+                COUNT(*) FILTER (WHERE status = 'Archived'),
+                COUNT(*) FILTER (WHERE priority = 'Low'),
+                COUNT(*) FILTER (WHERE priority = 'Medium'),
+                COUNT(*) FILTER (WHERE priority = 'High'),
+                COUNT(*) FILTER (WHERE priority = 'Urgent')
+             FROM tasks WHERE organization_id = $1 AND user_id = $2"
         )
+        .bind(organization_id)
         .bind(user_id)
         .fetch_one(&self.pool)
         .await?;
 
-        let urgent_priority_tasks: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND priority = 'Urgent'"
+        Ok(stats)
+    }
+
+    pub async fn get_user_due_counts(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        today: (DateTime<Utc>, DateTime<Utc>),
+        week: (DateTime<Utc>, DateTime<Utc>),
+        now: DateTime<Utc>,
+    ) -> Result<(i64, i64, i64)> {
+        let counts: (i64, i64, i64) = sqlx::query_as(
+            "SELECT
+                COUNT(*) FILTER (WHERE due_date >= $2 AND due_date < $3),
+                COUNT(*) FILTER (WHERE due_date >= $4 AND due_date < $5),
+                COUNT(*) FILTER (WHERE due_date < $6 AND status NOT IN ('Completed', 'Archived'))
+             FROM tasks WHERE user_id = $1 AND organization_id = $7"
         )
         .bind(user_id)
+        .bind(today.0)
+        .bind(today.1)
+        .bind(week.0)
+        .bind(week.1)
+        .bind(now)
+        .bind(organization_id)
         .fetch_one(&self.pool)
         .await?;
 
-        Ok((
-            total_tasks,
-            pending_tasks,
-            in_progress_tasks,
-            completed_tasks,
-            archived_tasks,
-            low_priority_tasks,
-            medium_priority_tasks,
-            high_priority_tasks,
-            urgent_priority_tasks,
-        ))
+        Ok(counts)
+    }
+
+    /// Everyone who shares a task with the user, the user included.
+    pub async fn find_collaborator_ids(&self, organization_id: Uuid, user_id: Uuid) -> Result<Vec<Uuid>> {
+        let query = format!(
+            "SELECT t.user_id {0}
+             UNION
+             SELECT m.user_id FROM task_members m
+             WHERE m.task_id IN (SELECT t.id {0})
+             UNION
+             SELECT $2",
+            ACCESSIBLE_TASKS
+        );
+
+        let user_ids: Vec<Uuid> = sqlx::query_scalar(&query)
+            .bind(organization_id)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(user_ids)
+    }
+
+    /// Open work per person in one pass. A person carries the tasks they own
+    /// or edit; viewers carry nothing. Completions are the tasks they set to
+    /// `Completed` since `completed_since`, read from the activity log.
+    /// People outside the organization are left out.
+    pub async fn get_workload(
+        &self,
+        organization_id: Uuid,
+        user_ids: &[Uuid],
+        now: DateTime<Utc>,
+        due_before: DateTime<Utc>,
+        completed_since: DateTime<Utc>,
+    ) -> Result<Vec<MemberWorkload>> {
+        let workload = sqlx::query_as::<_, MemberWorkload>(
+            "WITH open_tasks AS (
+                SELECT DISTINCT p.user_id, t.id, t.priority, t.due_date
+                FROM UNNEST($2::uuid[]) AS p(user_id)
+                JOIN tasks t ON t.organization_id = $1
+                    AND t.status IN ('Pending', 'InProgress')
+                    AND (t.user_id = p.user_id OR EXISTS (
+                        SELECT 1 FROM task_members tm
+                        WHERE tm.task_id = t.id AND tm.user_id = p.user_id
+                          AND tm.role IN ('owner', 'editor')))
+            ),
+            completions AS (
+                SELECT a.user_id, COUNT(DISTINCT a.task_id) AS completed
+                FROM task_activity a
+                JOIN tasks t ON t.id = a.task_id AND t.organization_id = $1
+                WHERE a.user_id = ANY($2) AND a.created_at >= $5
+                  AND ((a.action = 'status_updated' AND a.details->>'new_status' = 'Completed')
+                    OR (a.action = 'updated' AND a.details->>'status' = 'Completed'))
+                GROUP BY a.user_id
+            )
+            SELECT
+                u.id AS user_id,
+                u.username,
+                u.avatar_url,
+                COUNT(o.id) AS open_tasks,
+                COUNT(o.id) FILTER (WHERE o.priority = 'Low') AS open_low,
+                COUNT(o.id) FILTER (WHERE o.priority = 'Medium') AS open_medium,
+                COUNT(o.id) FILTER (WHERE o.priority = 'High') AS open_high,
+                COUNT(o.id) FILTER (WHERE o.priority = 'Urgent') AS open_urgent,
+                COUNT(o.id) FILTER (WHERE o.due_date < $3) AS overdue,
+                COUNT(o.id) FILTER (WHERE o.due_date >= $3 AND o.due_date < $4) AS due_soon,
+                COALESCE(MAX(c.completed), 0) AS completed_recently
+             FROM users u
+             JOIN organization_members om ON om.user_id = u.id AND om.organization_id = $1
+             LEFT JOIN open_tasks o ON o.user_id = u.id
+             LEFT JOIN completions c ON c.user_id = u.id
+             WHERE u.id = ANY($2)
+             GROUP BY u.id, u.username, u.avatar_url
+             ORDER BY open_tasks DESC, overdue DESC, u.username ASC"
+        )
+        .bind(organization_id)
+        .bind(user_ids)
+        .bind(now)
+        .bind(due_before)
+        .bind(completed_since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(workload)
     }
 
     // Collaborative task methods
-    pub async fn add_task_member(
+
+    pub async fn update_member_role(
         &self,
+        organization_id: Uuid,
         task_id: Uuid,
         user_id: Uuid,
-        role: &str,
-        added_by: Uuid,
-    ) -> Result<()> {
-        sqlx::query(
-            "INSERT INTO task_members (task_id, user_id, role, added_by)
-             VALUES ($1, $2, $3, $4)
-             ON CONFLICT (task_id, user_id) DO NOTHING"
+        role: TaskRole,
+    ) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE task_members SET role = $3
+             WHERE task_id = $1 AND user_id = $2 AND role <> 'owner'
+               AND task_id IN (SELECT id FROM tasks WHERE organization_id = $4)"
         )
         .bind(task_id)
         .bind(user_id)
-        .bind(role)
-        .bind(added_by)
+        .bind(role.to_string())
+        .bind(organization_id)
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected())
     }
 
-    pub async fn remove_task_member(&self, task_id: Uuid, user_id: Uuid) -> Result<()> {
-        sqlx::query("DELETE FROM task_members WHERE task_id = $1 AND user_id = $2")
+    /// Hand the task to `to`, who becomes its owner while `from` stays on as
+    /// an editor. Returns `None` if `from` no longer owns the task.
+    pub async fn transfer_ownership(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        from: Uuid,
+        to: Uuid,
+    ) -> Result<Option<Task>> {
+        let mut tx = self.pool.begin().await?;
+
+        let task = sqlx::query_as::<_, Task>(
+            "UPDATE tasks SET user_id = $3, updated_at = NOW()
+             WHERE id = $1 AND user_id = $2 AND organization_id = $4
+             RETURNING *"
+        )
+        .bind(task_id)
+        .bind(from)
+        .bind(to)
+        .bind(organization_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(task) = task else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            "INSERT INTO task_members (task_id, user_id, role, added_by)
+             VALUES ($1, $2, 'editor', $3)
+             ON CONFLICT (task_id, user_id) DO UPDATE SET role = 'editor'"
+        )
+        .bind(task_id)
+        .bind(from)
+        .bind(to)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE task_members SET role = 'owner' WHERE task_id = $1 AND user_id = $2")
             .bind(task_id)
-            .bind(user_id)
-            .execute(&self.pool)
+            .bind(to)
+            .execute(&mut *tx)
             .await?;
 
+        sqlx::query(
+            "INSERT INTO task_activity (task_id, user_id, action, details)
+             VALUES ($1, $2, 'ownership_transferred', $3)"
+        )
+        .bind(task_id)
+        .bind(from)
+        .bind(serde_json::json!({"previous_owner_id": from, "new_owner_id": to}))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(task))
+    }
+
+    pub async fn remove_task_member(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<()> {
+        sqlx::query(
+            "DELETE FROM task_members
+             WHERE task_id = $1 AND user_id = $2
+               AND task_id IN (SELECT id FROM tasks WHERE organization_id = $3)"
+        )
+        .bind(task_id)
+        .bind(user_id)
+        .bind(organization_id)
+        .execute(&self.pool)
+        .await?;
+
         Ok(())
     }
 
-    pub async fn get_task_members(&self, task_id: Uuid) -> Result<Vec<super::task_models::TaskMemberInfo>> {
+    pub async fn get_task_members(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+    ) -> Result<Vec<super::task_models::TaskMemberInfo>> {
         let members = sqlx::query_as::<_, super::task_models::TaskMemberInfo>(
             "SELECT tm.user_id, u.username, u.avatar_url, tm.role, tm.added_at
              FROM task_members tm
+             JOIN tasks t ON t.id = tm.task_id
              JOIN users u ON u.id = tm.user_id
-             WHERE tm.task_id = $1
+             WHERE tm.task_id = $1 AND t.organization_id = $2
              ORDER BY tm.added_at ASC"
         )
         .bind(task_id)
+        .bind(organization_id)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(members)
     }
 
-    pub async fn get_user_tasks_including_shared(&self, user_id: Uuid, filters: TaskFilters) -> Result<(Vec<Task>, i64)> {
-        let mut query = "SELECT DISTINCT t.* FROM tasks t 
-                         LEFT JOIN task_members tm ON t.id = tm.task_id
-                         WHERE (t.user_id = $1 OR tm.user_id = $1)".to_string();
+    pub async fn get_user_tasks_including_shared(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        filters: TaskFilters,
+    ) -> Result<(Vec<Task>, i64)> {
+        let mut query = format!("SELECT DISTINCT t.* {}", ACCESSIBLE_TASKS);
         
-        let mut count_query = "SELECT COUNT(DISTINCT t.id) FROM tasks t
-                               LEFT JOIN task_members tm ON t.id = tm.task_id
-                               WHERE (t.user_id = $1 OR tm.user_id = $1)".to_string();
+        let mut count_query = format!("SELECT COUNT(DISTINCT t.id) {}", ACCESSIBLE_TASKS);
         
-        let mut params_count = 1;
+        let mut params_count = 2;
 
         if let Some(ref _status) = filters.status {
             params_count += 1;
@@ -386,7 +719,9 @@ impl TaskRepository {
         }
 
         // Calculate total count
-        let mut count_db_query = sqlx::query_scalar::<_, i64>(&count_query).bind(user_id);
+        let mut count_db_query = sqlx::query_scalar::<_, i64>(&count_query)
+            .bind(organization_id)
+            .bind(user_id);
 
         if let Some(status) = &filters.status {
             count_db_query = count_db_query.bind(status);
@@ -423,7 +758,9 @@ impl TaskRepository {
 
         query.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
 
-        let mut db_query = sqlx::query_as::<_, Task>(&query).bind(user_id);
+        let mut db_query = sqlx::query_as::<_, Task>(&query)
+            .bind(organization_id)
+            .bind(user_id);
 
         if let Some(status) = filters.status {
             db_query = db_query.bind(status);
@@ -442,38 +779,124 @@ impl TaskRepository {
         Ok((tasks, total_count))
     }
 
-    pub async fn is_task_member(&self, task_id: Uuid, user_id: Uuid) -> Result<bool> {
+    /// Firing times of the user's own reminders in `[from, to)`, by task.
+    pub async fn find_calendar_reminders(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(Uuid, DateTime<Utc>)>> {
+        let reminders = sqlx::query_as::<_, (Uuid, DateTime<Utc>)>(
+            "SELECT r.task_id, r.fire_at FROM task_reminders r
+             JOIN tasks t ON t.id = r.task_id
+             WHERE r.user_id = $1 AND r.fire_at >= $2 AND r.fire_at < $3 AND t.organization_id = $4"
+        )
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .bind(organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(reminders)
+    }
+
+    /// Tasks the user can access that are due or have one of their reminders in `[from, to)`.
+    /// When `overdue_before` is set, unfinished tasks due before it are included
+    /// as well so the caller can carry them over.
+    pub async fn find_calendar_tasks(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        overdue_before: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Task>> {
+        let query = format!(
+            "SELECT DISTINCT t.* {}
+             AND ((t.due_date >= $3 AND t.due_date < $4)
+                OR EXISTS (SELECT 1 FROM task_reminders r
+                    WHERE r.task_id = t.id AND r.user_id = $2
+                    AND r.fire_at >= $3 AND r.fire_at < $4)
+                OR ($5::timestamptz IS NOT NULL
+                    AND t.due_date < $5
+                    AND t.status NOT IN ('Completed', 'Archived')))
+             ORDER BY t.due_date ASC NULLS LAST",
+            ACCESSIBLE_TASKS
+        );
+
+        let tasks = sqlx::query_as::<_, Task>(&query)
+            .bind(organization_id)
+            .bind(user_id)
+            .bind(from)
+            .bind(to)
+            .bind(overdue_before)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(tasks)
+    }
+
+    pub async fn is_task_member(&self, organization_id: Uuid, task_id: Uuid, user_id: Uuid) -> Result<bool> {
         let count: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM task_members WHERE task_id = $1 AND user_id = $2"
+            "SELECT COUNT(*) FROM task_members tm
+             JOIN tasks t ON t.id = tm.task_id
+             WHERE tm.task_id = $1 AND tm.user_id = $2 AND t.organization_id = $3"
         )
         .bind(task_id)
         .bind(user_id)
+        .bind(organization_id)
         .fetch_one(&self.pool)
         .await?;
 
         Ok(count > 0)
     }
 
-    pub async fn is_task_owner(&self, task_id: Uuid, user_id: Uuid) -> Result<bool> {
+    pub async fn is_task_owner(&self, organization_id: Uuid, task_id: Uuid, user_id: Uuid) -> Result<bool> {
         let count: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM tasks WHERE id = $1 AND user_id = $2"
+            "SELECT COUNT(*) FROM tasks WHERE id = $1 AND user_id = $2 AND organization_id = $3"
         )
         .bind(task_id)
         .bind(user_id)
+        .bind(organization_id)
         .fetch_one(&self.pool)
         .await?;
 
         Ok(count > 0)
     }
 
-    pub async fn has_task_access(&self, task_id: Uuid, user_id: Uuid) -> Result<bool> {
+    /// The user's role on the task, or `None` without access.
+    pub async fn find_member_role(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<TaskRole>> {
+        let role: Option<Option<String>> = sqlx::query_scalar(
+            "SELECT CASE WHEN t.user_id = $2 THEN 'owner' ELSE tm.role END
+             FROM tasks t
+             LEFT JOIN task_members tm ON tm.task_id = t.id AND tm.user_id = $2
+             WHERE t.id = $1 AND t.organization_id = $3"
+        )
+        .bind(task_id)
+        .bind(user_id)
+        .bind(organization_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(role.flatten().as_deref().and_then(TaskRole::parse))
+    }
+
+    pub async fn has_task_access(&self, organization_id: Uuid, task_id: Uuid, user_id: Uuid) -> Result<bool> {
         let count: i64 = sqlx::query_scalar(
             "SELECT COUNT(*) FROM tasks t
              LEFT JOIN task_members tm ON t.id = tm.task_id
-             WHERE t.id = $1 AND (t.user_id = $2 OR tm.user_id = $2)"
+             WHERE t.id = $1 AND t.organization_id = $3 AND (t.user_id = $2 OR tm.user_id = $2)"
         )
         .bind(task_id)
         .bind(user_id)
+        .bind(organization_id)
         .fetch_one(&self.pool)
         .await?;
 
@@ -482,6 +905,7 @@ impl TaskRepository {
 
     pub async fn log_task_activity(
         &self,
+        organization_id: Uuid,
         task_id: Uuid,
         user_id: Uuid,
         action: &str,
@@ -489,44 +913,68 @@ impl TaskRepository {
     ) -> Result<()> {
         sqlx::query(
             "INSERT INTO task_activity (task_id, user_id, action, details)
-             VALUES ($1, $2, $3, $4)"
+             SELECT id, $2, $3, $4 FROM tasks WHERE id = $1 AND organization_id = $5"
         )
         .bind(task_id)
         .bind(user_id)
         .bind(action)
         .bind(details)
+        .bind(organization_id)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    pub async fn get_task_activity(&self, task_id: Uuid) -> Result<Vec<super::task_dto::TaskActivityResponse>> {
+    pub async fn get_task_activity(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+    ) -> Result<Vec<super::task_dto::TaskActivityResponse>> {
         let activities = sqlx::query_as::<_, super::task_dto::TaskActivityResponse>(
             "SELECT ta.id, ta.user_id, u.username, ta.action, ta.details, ta.created_at
              FROM task_activity ta
+             JOIN tasks t ON t.id = ta.task_id
              LEFT JOIN users u ON u.id = ta.user_id
-             WHERE ta.task_id = $1
+             WHERE ta.task_id = $1 AND t.organization_id = $2
              ORDER BY ta.created_at DESC"
         )
         .bind(task_id)
+        .bind(organization_id)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(activities)
     }
 
-    pub async fn find_by_id_with_access(&self, id: Uuid, user_id: Uuid) -> Result<Option<Task>> {
+    pub async fn find_by_id_with_access(
+        &self,
+        organization_id: Uuid,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<Task>> {
         let task = sqlx::query_as::<_, Task>(
             "SELECT DISTINCT t.* FROM tasks t
              LEFT JOIN task_members tm ON t.id = tm.task_id
-             WHERE t.id = $1 AND (t.user_id = $2 OR tm.user_id = $2)"
+             WHERE t.id = $1 AND t.organization_id = $3 AND (t.user_id = $2 OR tm.user_id = $2)"
         )
         .bind(id)
         .bind(user_id)
+        .bind(organization_id)
         .fetch_optional(&self.pool)
         .await?;
         
         Ok(task)
     }
+
+    /// Any task of the organization, regardless of who owns it or shares it.
+    pub async fn find_by_id_any(&self, organization_id: Uuid, id: Uuid) -> Result<Option<Task>> {
+        let task = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = $1 AND organization_id = $2")
+            .bind(id)
+            .bind(organization_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(task)
+    }
 }
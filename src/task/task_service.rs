@@ -2,9 +2,22 @@
 use crate::error::Result;
 use crate::task::task_repository::TaskRepository;
 use crate::task::task_models::Task;
-use crate::task::task_dto::{CreateTaskRequest, UpdateTaskRequest, UpdateTaskStatusRequest};
+use crate::task::task_dto::{
+    CalendarResponse, CreateReminderRequest, CreateTaskRequest, QuickAddResponse, SnoozeReminderRequest,
+    UpdateTaskRequest, UpdateTaskStatusRequest, WorkloadResponse,
+};
+use crate::task::task_models::{TaskReminder, TaskRole};
+use crate::task::task_calendar::{build_calendar_days, local_date, local_day_start, MAX_CALENDAR_DAYS};
+use crate::task::task_quick_add::parse_quick_add;
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
 use uuid::Uuid;
+use validator::Validate;
 
+/// Longest look-ahead and look-back of the workload view, in days.
+const MAX_WORKLOAD_DAYS: i64 = 90;
+/// Most people one workload request may name.
+const MAX_WORKLOAD_USERS: usize = 100;
 
 /// Service layer for task‑related business logic.
 #[derive(Clone)]
@@ -19,77 +32,247 @@ impl TaskService {
 
     pub async fn list_tasks(
         &self,
+        organization_id: Uuid,
         user_id: Uuid,
         filters: crate::task::task_repository::TaskFilters,
     ) -> Result<(Vec<Task>, i64)> {
         // Use the method that includes shared tasks
-        self.repo.get_user_tasks_including_shared(user_id, filters).await
+        self.repo.get_user_tasks_including_shared(organization_id, user_id, filters).await
     }
 
-    pub async fn get_task(&self, user_id: Uuid, task_id: Uuid) -> Result<Task> {
+    /// Fail unless the user holds at least `required` on the task.
+    async fn require_role(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        user_id: Uuid,
+        required: TaskRole,
+    ) -> Result<TaskRole> {
+        match self.repo.find_member_role(organization_id, task_id, user_id).await? {
+            Some(role) if role >= required => Ok(role),
+            Some(_) => Err(crate::error::AppError::Forbidden(format!(
+                "Requires the {} role on this task",
+                required
+            ))),
+            None => Err(crate::error::AppError::Forbidden("Access denied".to_string())),
+        }
+    }
+
+    pub async fn get_task(&self, organization_id: Uuid, user_id: Uuid, task_id: Uuid) -> Result<Task> {
+        self.repo
+            .find_by_id_with_access(organization_id, task_id, user_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("Task not found".into()))
+    }
+
+    /// Any task, for users allowed to read every task.
+    pub async fn get_any_task(&self, organization_id: Uuid, task_id: Uuid) -> Result<Task> {
         self.repo
-            .find_by_id_with_access(task_id, user_id)
+            .find_by_id_any(organization_id, task_id)
             .await?
             .ok_or_else(|| crate::error::AppError::NotFound("Task not found".into()))
     }
 
+    /// Tasks due or reminded in `[from, to)`, grouped per day in `tz`.
+    pub async fn get_calendar(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        tz: Tz,
+    ) -> Result<CalendarResponse> {
+        if from >= to {
+            return Err(crate::error::AppError::BadRequest("'from' must be before 'to'".to_string()));
+        }
+        if to - from > Duration::days(MAX_CALENDAR_DAYS) {
+            return Err(crate::error::AppError::BadRequest(format!(
+                "Calendar range cannot exceed {} days",
+                MAX_CALENDAR_DAYS
+            )));
+        }
+
+        // Overdue tasks are only carried over when today is on screen.
+        let today = local_date(Utc::now(), tz);
+        let shows_today = today >= local_date(from, tz)
+            && today <= local_date(to - Duration::microseconds(1), tz);
+        let overdue_before = shows_today.then(|| local_day_start(today, tz));
+
+        let tasks = self.repo
+            .find_calendar_tasks(organization_id, user_id, from, to, overdue_before)
+            .await?;
+        let reminders = self.repo
+            .find_calendar_reminders(organization_id, user_id, from, to)
+            .await?;
+
+        Ok(CalendarResponse {
+            from,
+            to,
+            timezone: tz.name().to_string(),
+            days: build_calendar_days(tasks, reminders, from, to, tz, today),
+        })
+    }
+
+    /// Open work per person for `user_ids`, or for everyone the user shares
+    /// tasks with when none are named.
+    pub async fn get_workload(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        user_ids: Option<Vec<Uuid>>,
+        due_within_days: i64,
+        completed_within_days: i64,
+    ) -> Result<WorkloadResponse> {
+        for days in [due_within_days, completed_within_days] {
+            if !(1..=MAX_WORKLOAD_DAYS).contains(&days) {
+                return Err(crate::error::AppError::BadRequest(format!(
+                    "Day ranges must be between 1 and {}",
+                    MAX_WORKLOAD_DAYS
+                )));
+            }
+        }
+
+        let user_ids = match user_ids {
+            Some(user_ids) => user_ids,
+            None => self.repo.find_collaborator_ids(organization_id, user_id).await?,
+        };
+        if user_ids.len() > MAX_WORKLOAD_USERS {
+            return Err(crate::error::AppError::BadRequest(format!(
+                "At most {} users can be compared at once",
+                MAX_WORKLOAD_USERS
+            )));
+        }
+
+        let now = Utc::now();
+        let members = self.repo
+            .get_workload(
+                organization_id,
+                &user_ids,
+                now,
+                now + Duration::days(due_within_days),
+                now - Duration::days(completed_within_days),
+            )
+            .await?;
+
+        Ok(WorkloadResponse {
+            due_within_days,
+            completed_within_days,
+            members,
+        })
+    }
+
     pub async fn create_task(
         &self,
+        organization_id: Uuid,
         user_id: Uuid,
         payload: CreateTaskRequest,
     ) -> Result<Task> {
+        // Only the creator is a member yet, so every reminder is theirs
+        let shorthand = payload.reminder_time.map(|remind_at| CreateReminderRequest {
+            remind_at: Some(remind_at),
+            offset_minutes: None,
+            user_id: None,
+        });
+        let reminders: Vec<CreateReminderRequest> = payload.reminders.into_iter().flatten().chain(shorthand).collect();
+        // Reject bad reminders before anything is written
+        for reminder in &reminders {
+            Self::check_reminder_kind(reminder)?;
+            if reminder.user_id.is_some_and(|recipient| recipient != user_id) {
+                return Err(crate::error::AppError::Validation(
+                    "Reminders on a new task can only be for its creator".to_string(),
+                ));
+            }
+        }
+        let reminders: Vec<_> = reminders
+            .iter()
+            .map(|reminder| (reminder.remind_at, reminder.offset_minutes))
+            .collect();
+
         let priority = payload.priority.unwrap_or_else(|| "Medium".to_string());
         let task = self.repo
             .create(
+                organization_id,
                 user_id,
                 &payload.title,
                 payload.description.as_deref(),
                 &priority,
                 payload.due_date,
-                payload.reminder_time,
+                &reminders,
             )
             .await?;
 
-        // Log activity
-        let _ = self.repo.log_task_activity(
-            task.id,
-            user_id,
-            "created",
-            Some(serde_json::json!({"title": task.title})),
-        ).await;
-
-        // Add creator as owner
-        let _ = self.repo.add_task_member(task.id, user_id, "owner", user_id).await;
-
         Ok(task)
     }
 
+    /// Interpret a quick-add text in `tz` and, unless previewing, create the task.
+    pub async fn quick_add(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        text: &str,
+        tz: Tz,
+        preview: bool,
+    ) -> Result<QuickAddResponse> {
+        let parsed = parse_quick_add(text, Utc::now(), tz)?;
+
+        let payload = CreateTaskRequest {
+            title: parsed.title.clone(),
+            description: None,
+            priority: parsed.priority.clone(),
+            due_date: parsed.due_date,
+            reminder_time: None,
+            reminders: parsed.reminder_offset_minutes.map(|offset_minutes| {
+                vec![CreateReminderRequest {
+                    remind_at: None,
+                    offset_minutes: Some(offset_minutes),
+                    user_id: None,
+                }]
+            }),
+        };
+        // The text may be longer than a title can be
+        payload.validate()?;
+
+        let task = if preview {
+            None
+        } else {
+            Some(self.create_task(organization_id, user_id, payload).await?)
+        };
+
+        Ok(QuickAddResponse {
+            timezone: tz.name().to_string(),
+            parsed,
+            task,
+        })
+    }
+
     pub async fn update_task(
         &self,
+        organization_id: Uuid,
         user_id: Uuid,
         task_id: Uuid,
         payload: UpdateTaskRequest,
     ) -> Result<Task> {
-        // Check access
-        if !self.repo.has_task_access(task_id, user_id).await? {
-            return Err(crate::error::AppError::Forbidden("Access denied".to_string()));
-        }
+        self.require_role(organization_id, task_id, user_id, TaskRole::Editor).await?;
 
         let task = self.repo
             .update(
+                organization_id,
                 task_id,
-                user_id,
                 payload.title.as_deref(),
                 payload.description.as_deref(),
                 payload.status.as_deref(),
                 payload.priority.as_deref(),
                 payload.due_date,
-                payload.reminder_time,
             )
             .await?;
 
+        if payload.due_date.is_some() {
+            self.repo.reschedule_relative_reminders(organization_id, task_id).await?;
+        }
+
         // Log activity
         let _ = self.repo.log_task_activity(
+            organization_id,
             task_id,
             user_id,
             "updated",
@@ -99,33 +282,29 @@ impl TaskService {
         Ok(task)
     }
 
-    pub async fn delete_task(&self, user_id: Uuid, task_id: Uuid) -> Result<u64> {
-        // Only owner can delete
-        if !self.repo.is_task_owner(task_id, user_id).await? {
-            return Err(crate::error::AppError::Forbidden("Only task owner can delete".to_string()));
-        }
+    pub async fn delete_task(&self, organization_id: Uuid, user_id: Uuid, task_id: Uuid) -> Result<u64> {
+        self.require_role(organization_id, task_id, user_id, TaskRole::Owner).await?;
 
-        self.repo.delete(task_id, user_id).await
+        self.repo.delete(organization_id, task_id, user_id).await
     }
 
     pub async fn update_status(
         &self,
+        organization_id: Uuid,
         user_id: Uuid,
         task_id: Uuid,
         payload: UpdateTaskStatusRequest,
     ) -> Result<Task> {
-        // Check access
-        if !self.repo.has_task_access(task_id, user_id).await? {
-            return Err(crate::error::AppError::Forbidden("Access denied".to_string()));
-        }
+        self.require_role(organization_id, task_id, user_id, TaskRole::Editor).await?;
 
         let task = self.repo
-            .update_status(task_id, user_id, &payload.status)
+            .update_status(organization_id, task_id, &payload.status)
             .await?
             .ok_or_else(|| crate::error::AppError::NotFound("Task not found".into()))?;
 
         // Log activity
         let _ = self.repo.log_task_activity(
+            organization_id,
             task_id,
             user_id,
             "status_updated",
@@ -136,52 +315,25 @@ impl TaskService {
     }
 
     // Collaboration methods
-    pub async fn share_task(
-        &self,
-        task_id: Uuid,
-        user_ids: Vec<Uuid>,
-        shared_by: Uuid,
-    ) -> Result<()> {
-        // Only owner can share
-        if !self.repo.is_task_owner(task_id, shared_by).await? {
-            return Err(crate::error::AppError::Forbidden("Only task owner can share".to_string()));
-        }
-
-        for user_id in user_ids {
-            self.repo.add_task_member(task_id, user_id, "collaborator", shared_by).await?;
-            
-            // Log activity
-            let _ = self.repo.log_task_activity(
-                task_id,
-                shared_by,
-                "member_added",
-                Some(serde_json::json!({"added_user_id": user_id})),
-            ).await;
-        }
-
-        Ok(())
-    }
-
     pub async fn remove_collaborator(
         &self,
+        organization_id: Uuid,
         task_id: Uuid,
         user_id: Uuid,
         removed_by: Uuid,
     ) -> Result<()> {
-        // Only owner can remove collaborators
-        if !self.repo.is_task_owner(task_id, removed_by).await? {
-            return Err(crate::error::AppError::Forbidden("Only task owner can remove collaborators".to_string()));
-        }
+        self.require_role(organization_id, task_id, removed_by, TaskRole::Owner).await?;
 
         // Cannot remove owner
-        if self.repo.is_task_owner(task_id, user_id).await? {
+        if self.repo.is_task_owner(organization_id, task_id, user_id).await? {
             return Err(crate::error::AppError::BadRequest("Cannot remove task owner".to_string()));
         }
 
-        self.repo.remove_task_member(task_id, user_id).await?;
+        self.repo.remove_task_member(organization_id, task_id, user_id).await?;
 
         // Log activity
         let _ = self.repo.log_task_activity(
+            organization_id,
             task_id,
             removed_by,
             "member_removed",
@@ -191,19 +343,85 @@ impl TaskService {
         Ok(())
     }
 
+    /// Make a member a viewer or an editor. Only the owner may do this.
+    pub async fn change_member_role(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        member_id: Uuid,
+        role: TaskRole,
+        changed_by: Uuid,
+    ) -> Result<Vec<crate::task::task_models::TaskMemberInfo>> {
+        self.require_role(organization_id, task_id, changed_by, TaskRole::Owner).await?;
+        if role == TaskRole::Owner {
+            return Err(crate::error::AppError::BadRequest(
+                "Transfer ownership to make someone the owner".to_string(),
+            ));
+        }
+
+        let previous = match self.repo.find_member_role(organization_id, task_id, member_id).await? {
+            Some(TaskRole::Owner) => {
+                return Err(crate::error::AppError::BadRequest(
+                    "The owner's role can only change by transferring ownership".to_string(),
+                ))
+            }
+            Some(previous) => previous,
+            None => return Err(crate::error::AppError::NotFound("User is not a task member".into())),
+        };
+
+        if previous != role {
+            self.repo.update_member_role(organization_id, task_id, member_id, role).await?;
+
+            let _ = self.repo.log_task_activity(
+                organization_id,
+                task_id,
+                changed_by,
+                "member_role_changed",
+                Some(serde_json::json!({"user_id": member_id, "old_role": previous, "new_role": role})),
+            ).await;
+        }
+
+        self.repo.get_task_members(organization_id, task_id).await
+    }
+
+    /// Hand the task to another member; the previous owner stays on as an editor.
+    pub async fn transfer_ownership(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        new_owner: Uuid,
+        requested_by: Uuid,
+    ) -> Result<Task> {
+        self.require_role(organization_id, task_id, requested_by, TaskRole::Owner).await?;
+        if new_owner == requested_by {
+            return Err(crate::error::AppError::BadRequest("You already own this task".to_string()));
+        }
+        if !self.repo.is_task_member(organization_id, task_id, new_owner).await? {
+            return Err(crate::error::AppError::BadRequest(
+                "The new owner must already be a task member".to_string(),
+            ));
+        }
+
+        self.repo
+            .transfer_ownership(organization_id, task_id, requested_by, new_owner)
+            .await?
+            .ok_or_else(|| crate::error::AppError::Forbidden("Only task owner can transfer ownership".to_string()))
+    }
+
     pub async fn get_task_with_members(
         &self,
+        organization_id: Uuid,
         task_id: Uuid,
         requesting_user: Uuid,
     ) -> Result<crate::task::task_models::TaskWithMembers> {
         // Check access
-        if !self.repo.has_task_access(task_id, requesting_user).await? {
+        if !self.repo.has_task_access(organization_id, task_id, requesting_user).await? {
             return Err(crate::error::AppError::Forbidden("Access denied".to_string()));
         }
 
-        let task = self.get_task(requesting_user, task_id).await?;
-        let members = self.repo.get_task_members(task_id).await?;
-        let is_owner = self.repo.is_task_owner(task_id, requesting_user).await?;
+        let task = self.get_task(organization_id, requesting_user, task_id).await?;
+        let members = self.repo.get_task_members(organization_id, task_id).await?;
+        let is_owner = self.repo.is_task_owner(organization_id, task_id, requesting_user).await?;
 
         Ok(crate::task::task_models::TaskWithMembers {
             task,
@@ -212,21 +430,171 @@ impl TaskService {
         })
     }
 
-    pub async fn get_task_members(&self, task_id: Uuid, requesting_user: Uuid) -> Result<Vec<crate::task::task_models::TaskMemberInfo>> {
+    pub async fn get_task_members(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        requesting_user: Uuid,
+    ) -> Result<Vec<crate::task::task_models::TaskMemberInfo>> {
         // Check access
-        if !self.repo.has_task_access(task_id, requesting_user).await? {
+        if !self.repo.has_task_access(organization_id, task_id, requesting_user).await? {
             return Err(crate::error::AppError::Forbidden("Access denied".to_string()));
         }
 
-        self.repo.get_task_members(task_id).await
+        self.repo.get_task_members(organization_id, task_id).await
     }
 
-    pub async fn get_task_activity(&self, task_id: Uuid, requesting_user: Uuid) -> Result<Vec<crate::task::task_dto::TaskActivityResponse>> {
+    pub async fn get_task_activity(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        requesting_user: Uuid,
+    ) -> Result<Vec<crate::task::task_dto::TaskActivityResponse>> {
         // Check access
-        if !self.repo.has_task_access(task_id, requesting_user).await? {
+        if !self.repo.has_task_access(organization_id, task_id, requesting_user).await? {
+            return Err(crate::error::AppError::Forbidden("Access denied".to_string()));
+        }
+
+        self.repo.get_task_activity(organization_id, task_id).await
+    }
+
+    // Reminder methods
+    fn check_reminder_kind(reminder: &CreateReminderRequest) -> Result<()> {
+        if reminder.remind_at.is_some() == reminder.offset_minutes.is_some() {
+            return Err(crate::error::AppError::Validation(
+                "A reminder needs exactly one of remind_at or offset_minutes".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn get_reminders(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        requesting_user: Uuid,
+    ) -> Result<Vec<TaskReminder>> {
+        if !self.repo.has_task_access(organization_id, task_id, requesting_user).await? {
+            return Err(crate::error::AppError::Forbidden("Access denied".to_string()));
+        }
+
+        self.repo.get_task_reminders(organization_id, task_id).await
+    }
+
+    /// Add a reminder for the caller, or for another member if the caller owns the task.
+    pub async fn add_reminder(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        requesting_user: Uuid,
+        payload: CreateReminderRequest,
+    ) -> Result<TaskReminder> {
+        Self::check_reminder_kind(&payload)?;
+
+        if !self.repo.has_task_access(organization_id, task_id, requesting_user).await? {
+            return Err(crate::error::AppError::Forbidden("Access denied".to_string()));
+        }
+
+        let recipient = payload.user_id.unwrap_or(requesting_user);
+        if recipient != requesting_user {
+            if !self.repo.is_task_owner(organization_id, task_id, requesting_user).await? {
+                return Err(crate::error::AppError::Forbidden(
+                    "Only task owner can set reminders for other members".to_string(),
+                ));
+            }
+            if !self.repo.has_task_access(organization_id, task_id, recipient).await? {
+                return Err(crate::error::AppError::BadRequest("User is not a task member".to_string()));
+            }
+        }
+
+        let reminder = self.repo
+            .create_reminder(organization_id, task_id, recipient, payload.remind_at, payload.offset_minutes, requesting_user)
+            .await?;
+
+        let _ = self.repo.log_task_activity(
+            organization_id,
+            task_id,
+            requesting_user,
+            "reminder_added",
+            Some(serde_json::json!({
+                "reminder_id": reminder.id,
+                "user_id": recipient,
+                "remind_at": reminder.remind_at,
+                "offset_minutes": reminder.offset_minutes,
+            })),
+        ).await;
+
+        Ok(reminder)
+    }
+
+    /// Reminders can be removed by their recipient, their creator or the task owner.
+    pub async fn delete_reminder(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        reminder_id: Uuid,
+        requesting_user: Uuid,
+    ) -> Result<()> {
+        let reminder = self.repo
+            .find_reminder(organization_id, task_id, reminder_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("Reminder not found".into()))?;
+
+        let allowed = reminder.user_id == requesting_user
+            || reminder.created_by == Some(requesting_user)
+            || self.repo.is_task_owner(organization_id, task_id, requesting_user).await?;
+        if !allowed {
             return Err(crate::error::AppError::Forbidden("Access denied".to_string()));
         }
 
-        self.repo.get_task_activity(task_id).await
+        self.repo.delete_reminder(organization_id, reminder_id).await?;
+
+        let _ = self.repo.log_task_activity(
+            organization_id,
+            task_id,
+            requesting_user,
+            "reminder_removed",
+            Some(serde_json::json!({"reminder_id": reminder_id})),
+        ).await;
+
+        Ok(())
+    }
+
+    /// Re-arm a reminder that already fired. Only its recipient may snooze it.
+    pub async fn snooze_reminder(
+        &self,
+        organization_id: Uuid,
+        task_id: Uuid,
+        reminder_id: Uuid,
+        requesting_user: Uuid,
+        payload: SnoozeReminderRequest,
+    ) -> Result<TaskReminder> {
+        let reminder = self.repo
+            .find_reminder(organization_id, task_id, reminder_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("Reminder not found".into()))?;
+
+        if reminder.user_id != requesting_user {
+            return Err(crate::error::AppError::Forbidden("Only the reminded user can snooze".to_string()));
+        }
+        if reminder.notified_at.is_none() {
+            return Err(crate::error::AppError::BadRequest("Reminder has not fired yet".to_string()));
+        }
+
+        let now = Utc::now();
+        let until = match (payload.until, payload.minutes) {
+            (Some(until), None) => until,
+            (None, Some(minutes)) => now + Duration::minutes(minutes),
+            _ => {
+                return Err(crate::error::AppError::Validation(
+                    "Provide exactly one of minutes or until".to_string(),
+                ))
+            }
+        };
+        if until <= now {
+            return Err(crate::error::AppError::Validation("Snooze time must be in the future".to_string()));
+        }
+
+        self.repo.snooze_reminder(organization_id, reminder_id, until).await
     }
 }
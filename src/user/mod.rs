@@ -4,3 +4,4 @@ pub mod user_dto;
 pub mod user_repository;
 pub mod user_handlers;
 pub mod user_service;
+pub mod user_locale;
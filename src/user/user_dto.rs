@@ -1,15 +1,48 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use uuid::Uuid;
 use validator::Validate;
 
+use super::{
+    user_locale::{validate_locale, validate_timezone},
+    user_models::UserResponse,
+};
+
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateProfileRequest {
     #[validate(length(min = 3, max = 50))]
     pub username: Option<String>,
+    /// Changing the email marks it unverified and sends a new verification link
+    #[validate(email)]
+    pub email: Option<String>,
     pub bio: Option<String>,
     #[validate(length(min = 1, max = 50))]
     pub theme: Option<String>,
     pub avatar_url: Option<String>,
+    /// IANA time zone name, e.g. `Europe/Berlin`
+    #[validate(custom(function = "validate_timezone"))]
+    pub timezone: Option<String>,
+    /// Locale tag, e.g. `en-US`
+    #[validate(custom(function = "validate_locale"))]
+    pub locale: Option<String>,
+}
+
+/// The signed-in user, flagged when an admin is acting as them.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CurrentUserResponse {
+    #[serde(flatten)]
+    pub user: UserResponse,
+    pub impersonated: bool,
+    pub impersonator: Option<ImpersonatorResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImpersonatorResponse {
+    pub admin_id: Uuid,
+    pub admin_username: Option<String>,
+    pub impersonation_id: Uuid,
+    pub expires_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -24,6 +57,9 @@ pub struct UserStatsResponse {
     pub medium_priority_tasks: i64,
     pub high_priority_tasks: i64,
     pub urgent_priority_tasks: i64,
+    pub due_today: i64,
+    pub due_this_week: i64,
+    pub overdue_tasks: i64,
 }
 
 // Admin DTOs
@@ -36,7 +72,6 @@ pub struct AdminUpdateUserRequest {
     pub bio: Option<String>,
     pub theme: Option<String>,
     pub avatar_url: Option<String>,
-    pub is_admin: Option<bool>,
     pub is_active: Option<bool>,
 }
 
@@ -45,7 +80,9 @@ pub struct UpdateUserStatusRequest {
     pub is_active: bool,
 }
 
-#[derive(Debug, Deserialize, ToSchema)]
-pub struct UpdateAdminStatusRequest {
-    pub is_admin: bool,
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ImpersonateUserRequest {
+    /// Why support needs to act as the user, e.g. a ticket number
+    #[validate(length(max = 500))]
+    pub reason: Option<String>,
 }
@@ -5,13 +5,18 @@ use axum::{
     Json,
 };
 use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    error::Result,
-    middleware::AuthUser,
+    audit::audit_models::{AuditAction, AuditTarget, NewAuditEntry},
+    auth::auth_dto::ImpersonationResponse,
+    error::{AppError, Result},
+    middleware::{AuthUser, Authorized, ClientInfo, CurrentImpersonation, CurrentOrganization},
+    role::permission::Permission,
     state::AppState,
-    user::user_dto::UpdateProfileRequest,
+    user::user_dto::{ImpersonateUserRequest, UpdateProfileRequest},
 };
 
 #[derive(Debug, Deserialize)]
@@ -26,7 +31,7 @@ pub struct PaginationParams {
     path = "/api/users/me",
     tag = "users",
     responses(
-        (status = 200, description = "User profile retrieved successfully"),
+        (status = 200, description = "User profile, flagged when an admin is impersonating the user", body = crate::user::user_dto::CurrentUserResponse),
         (status = 401, description = "Unauthorized")
     ),
     security(
@@ -36,10 +41,11 @@ pub struct PaginationParams {
 pub async fn get_current_user(
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
+    CurrentImpersonation(impersonation): CurrentImpersonation,
 ) -> Result<impl IntoResponse> {
     let user = state
         .user_service
-        .get_current_user(user_id)
+        .get_current_user(user_id, impersonation)
         .await?;
 
     Ok((StatusCode::OK, Json(user)))
@@ -67,11 +73,16 @@ pub async fn update_current_user(
 ) -> Result<impl IntoResponse> {
     payload.validate()?;
 
+    let email_changed = payload.email.is_some();
     let user = state
         .user_service
         .update_current_user(user_id, payload)
         .await?;
 
+    if email_changed && !user.email_verified {
+        state.auth_service.send_email_verification(user_id).await?;
+    }
+
     Ok((StatusCode::OK, Json(user)))
 }
 
@@ -91,15 +102,16 @@ pub async fn update_current_user(
 pub async fn get_user_stats(
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
 ) -> Result<impl IntoResponse> {
-    let stats = state.user_service.get_user_stats(user_id).await?;
+    let stats = state.user_service.get_user_stats(organization_id, user_id).await?;
 
     Ok((StatusCode::OK, Json(stats)))
 }
 
 // Admin endpoints
 
-/// Get all users (admin only)
+/// Get all users (requires `users.read`)
 #[utoipa::path(
     get,
     path = "/api/admin/users",
@@ -107,7 +119,7 @@ pub async fn get_user_stats(
     responses(
         (status = 200, description = "Users retrieved successfully"),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - Admin access required")
+        (status = 403, description = "Forbidden - missing permission")
     ),
     security(
         ("bearer_auth" = [])
@@ -115,18 +127,21 @@ pub async fn get_user_stats(
 )]
 pub async fn get_all_users(
     State(state): State<AppState>,
+    authorized: Authorized,
     Query(params): Query<PaginationParams>,
 ) -> Result<impl IntoResponse> {
+    authorized.require(Permission::UsersRead)?;
+
     let page = params.page.unwrap_or(1).max(1);
     let limit = params.limit.unwrap_or(10).min(100).max(1);
     let offset = ((page - 1) * limit) as i64;
 
     let users = state
         .user_repository
-        .find_all(limit as i64, offset)
+        .find_all(authorized.organization_id, limit as i64, offset)
         .await?;
 
-    let total = state.user_repository.count_all().await?;
+    let total = state.user_repository.count_all(authorized.organization_id).await?;
     let total_pages = ((total as f64) / (limit as f64)).ceil() as u32;
 
     let user_responses: Vec<crate::user::user_models::UserResponse> = users
@@ -145,18 +160,18 @@ pub async fn get_all_users(
     Ok((StatusCode::OK, Json(response)))
 }
 
-/// Get specific user by ID (admin only)
+/// Get specific user by ID (requires `users.read`)
 #[utoipa::path(
     get,
     path = "/api/admin/users/{user_id}",
     tag = "admin",
     params(
-        ("user_id" = uuid::Uuid, Path, description = "User ID")
+        ("user_id" = Uuid, Path, description = "User ID")
     ),
     responses(
         (status = 200, description = "User retrieved successfully"),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 403, description = "Forbidden - missing permission"),
         (status = 404, description = "User not found")
     ),
     security(
@@ -165,31 +180,30 @@ pub async fn get_all_users(
 )]
 pub async fn get_user_by_id(
     State(state): State<AppState>,
-    Path(user_id): Path<uuid::Uuid>,
+    authorized: Authorized,
+    Path(user_id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
-    let user = state
-        .user_repository
-        .find_by_id(user_id)
-        .await?
-        .ok_or(crate::error::AppError::NotFound("User not found".to_string()))?;
+    authorized.require(Permission::UsersRead)?;
+
+    let user = find_organization_user(&state, &authorized, user_id).await?;
 
     Ok((StatusCode::OK, Json(crate::user::user_models::UserResponse::from(user))))
 }
 
-/// Update user (admin only)
+/// Update user (requires `users.update`)
 #[utoipa::path(
     put,
     path = "/api/admin/users/{user_id}",
     tag = "admin",
     params(
-        ("user_id" = uuid::Uuid, Path, description = "User ID")
+        ("user_id" = Uuid, Path, description = "User ID")
     ),
     request_body = crate::user::user_dto::AdminUpdateUserRequest,
     responses(
         (status = 200, description = "User updated successfully"),
         (status = 400, description = "Invalid input"),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 403, description = "Forbidden - missing permission"),
         (status = 404, description = "User not found")
     ),
     security(
@@ -198,40 +212,68 @@ pub async fn get_user_by_id(
 )]
 pub async fn admin_update_user(
     State(state): State<AppState>,
-    Path(user_id): Path<uuid::Uuid>,
+    authorized: Authorized,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
     Json(payload): Json<crate::user::user_dto::AdminUpdateUserRequest>,
 ) -> Result<impl IntoResponse> {
+    authorized.require(Permission::UsersUpdate)?;
+
     payload.validate()?;
 
+    let before = find_organization_user(&state, &authorized, user_id).await?;
+    let email_changed = payload.email.is_some();
     let user = state
         .user_repository
         .admin_update_user(
+            authorized.organization_id,
             user_id,
             payload.username,
             payload.email,
             payload.bio,
             payload.theme,
             payload.avatar_url,
-            payload.is_admin,
             payload.is_active,
         )
-        .await?;
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
 
-    Ok((StatusCode::OK, Json(crate::user::user_models::UserResponse::from(user))))
+    let user = crate::user::user_models::UserResponse::from(user);
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                authorized.organization_id,
+                authorized.user_id,
+                AuditAction::UserUpdated,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_before(crate::user::user_models::UserResponse::from(before))
+            .with_after(&user),
+            &client,
+        )
+        .await;
+
+    if email_changed && !user.email_verified {
+        state.auth_service.send_email_verification(user.id).await?;
+    }
+
+    Ok((StatusCode::OK, Json(user)))
 }
 
-/// Delete user (admin only)
+/// Delete user (requires `users.delete`)
 #[utoipa::path(
     delete,
     path = "/api/admin/users/{user_id}",
     tag = "admin",
     params(
-        ("user_id" = uuid::Uuid, Path, description = "User ID")
+        ("user_id" = Uuid, Path, description = "User ID")
     ),
     responses(
         (status = 204, description = "User deleted successfully"),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 403, description = "Forbidden - missing permission"),
         (status = 404, description = "User not found")
     ),
     security(
@@ -240,33 +282,48 @@ pub async fn admin_update_user(
 )]
 pub async fn delete_user(
     State(state): State<AppState>,
-    Path(user_id): Path<uuid::Uuid>,
+    authorized: Authorized,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
-    // Verify user exists
-    let _ = state
-        .user_repository
-        .find_by_id(user_id)
-        .await?
-        .ok_or(crate::error::AppError::NotFound("User not found".to_string()))?;
+    authorized.require(Permission::UsersDelete)?;
 
-    state.user_repository.delete_user(user_id).await?;
+    let before = find_organization_user(&state, &authorized, user_id).await?;
+    if state.user_repository.delete_user(authorized.organization_id, user_id).await? == 0 {
+        return Err(AppError::NotFound("User not found".to_string()));
+    }
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                authorized.organization_id,
+                authorized.user_id,
+                AuditAction::UserDeleted,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_before(crate::user::user_models::UserResponse::from(before)),
+            &client,
+        )
+        .await;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
-/// Update user active status (admin only)
+/// Update user active status (requires `users.deactivate`)
 #[utoipa::path(
     patch,
     path = "/api/admin/users/{user_id}/status",
     tag = "admin",
     params(
-        ("user_id" = uuid::Uuid, Path, description = "User ID")
+        ("user_id" = Uuid, Path, description = "User ID")
     ),
     request_body = crate::user::user_dto::UpdateUserStatusRequest,
     responses(
         (status = 200, description = "User status updated successfully"),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 403, description = "Forbidden - missing permission"),
         (status = 404, description = "User not found")
     ),
     security(
@@ -275,45 +332,384 @@ pub async fn delete_user(
 )]
 pub async fn update_user_status(
     State(state): State<AppState>,
-    Path(user_id): Path<uuid::Uuid>,
+    authorized: Authorized,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
     Json(payload): Json<crate::user::user_dto::UpdateUserStatusRequest>,
 ) -> Result<impl IntoResponse> {
+    authorized.require(Permission::UsersDeactivate)?;
+
+    let before = find_organization_user(&state, &authorized, user_id).await?;
     let user = state
         .user_repository
-        .update_active_status(user_id, payload.is_active)
-        .await?;
+        .update_active_status(authorized.organization_id, user_id, payload.is_active)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let action = if user.is_active { AuditAction::UserActivated } else { AuditAction::UserDeactivated };
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                authorized.organization_id,
+                authorized.user_id,
+                action,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_before(json!({ "is_active": before.is_active }))
+            .with_after(json!({ "is_active": user.is_active })),
+            &client,
+        )
+        .await;
 
     Ok((StatusCode::OK, Json(crate::user::user_models::UserResponse::from(user))))
 }
 
-/// Update user admin status (admin only)
+/// Reset a user's two-factor authentication (requires `users.manage_security`)
 #[utoipa::path(
-    patch,
-    path = "/api/admin/users/{user_id}/admin",
+    delete,
+    path = "/api/admin/users/{user_id}/2fa",
     tag = "admin",
     params(
-        ("user_id" = uuid::Uuid, Path, description = "User ID")
+        ("user_id" = Uuid, Path, description = "User ID")
     ),
-    request_body = crate::user::user_dto::UpdateAdminStatusRequest,
     responses(
-        (status = 200, description = "User admin status updated successfully"),
+        (status = 204, description = "Two-factor authentication reset"),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 403, description = "Forbidden - missing permission"),
         (status = 404, description = "User not found")
     ),
     security(
         ("bearer_auth" = [])
     )
 )]
-pub async fn update_admin_status(
+pub async fn reset_two_factor(
     State(state): State<AppState>,
-    Path(user_id): Path<uuid::Uuid>,
-    Json(payload): Json<crate::user::user_dto::UpdateAdminStatusRequest>,
+    authorized: Authorized,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
-    let user = state
-        .user_repository
-        .update_admin_status(user_id, payload.is_admin)
+    authorized.require(Permission::UsersManageSecurity)?;
+
+    let user = find_organization_user(&state, &authorized, user_id).await?;
+    state.auth_service.reset_two_factor(user_id).await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                authorized.organization_id,
+                authorized.user_id,
+                AuditAction::TwoFactorReset,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_before(json!({ "two_factor_enabled": user.two_factor_enabled }))
+            .with_after(json!({ "two_factor_enabled": false })),
+            &client,
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Act as a user through a short-lived access token (requires `users.impersonate`)
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{user_id}/impersonate",
+    tag = "admin",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID")
+    ),
+    request_body = ImpersonateUserRequest,
+    responses(
+        (status = 200, description = "Impersonation started", body = crate::auth::auth_dto::ImpersonationResponse),
+        (status = 400, description = "Cannot impersonate yourself or an inactive user"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission, or the user holds permissions the caller lacks"),
+        (status = 404, description = "User not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn impersonate_user(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<ImpersonateUserRequest>,
+) -> Result<impl IntoResponse> {
+    authorized.require(Permission::UsersImpersonate)?;
+    payload.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    if user_id == authorized.user_id {
+        return Err(AppError::BadRequest("Cannot impersonate yourself".to_string()));
+    }
+    let user = find_organization_user(&state, &authorized, user_id).await?;
+    if !user.is_active {
+        return Err(AppError::BadRequest("Cannot impersonate an inactive user".to_string()));
+    }
+    // Acting as someone must never grant more than the caller already has
+    let user_permissions = state.role_service.permissions_for_user(user_id).await?;
+    if !user_permissions.is_subset(&authorized.permissions) {
+        return Err(AppError::Forbidden("The user holds permissions you do not have".to_string()));
+    }
+
+    let reason = payload.reason.as_deref().map(str::trim).filter(|reason| !reason.is_empty());
+    let (access_token, session) = state
+        .auth_service
+        .start_impersonation(authorized.user_id, &user, authorized.organization_id, reason)
         .await?;
 
-    Ok((StatusCode::OK, Json(crate::user::user_models::UserResponse::from(user))))
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                authorized.organization_id,
+                authorized.user_id,
+                AuditAction::ImpersonationStarted,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_after(json!({
+                "impersonation_id": session.id,
+                "expires_at": session.expires_at,
+                "reason": session.reason,
+            })),
+            &client,
+        )
+        .await;
+
+    Ok(Json(ImpersonationResponse {
+        access_token,
+        impersonation_id: session.id,
+        expires_at: session.expires_at,
+        user: user.into(),
+    }))
+}
+
+/// List a user's active sessions (requires `users.read`)
+#[utoipa::path(
+    get,
+    path = "/api/admin/users/{user_id}/sessions",
+    tag = "admin",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Active sessions", body = Vec<crate::auth::auth_dto::SessionResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission"),
+        (status = 404, description = "User not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn admin_list_sessions(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    Path(user_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    authorized.require(Permission::UsersRead)?;
+    find_organization_user(&state, &authorized, user_id).await?;
+
+    let sessions: Vec<crate::auth::auth_dto::SessionResponse> = state
+        .auth_service
+        .list_sessions(user_id)
+        .await?
+        .into_iter()
+        .map(|session| crate::auth::auth_dto::SessionResponse::from_token(session, None))
+        .collect();
+
+    Ok((StatusCode::OK, Json(sessions)))
+}
+
+/// Revoke one of a user's sessions (requires `users.manage_security`)
+#[utoipa::path(
+    delete,
+    path = "/api/admin/users/{user_id}/sessions/{session_id}",
+    tag = "admin",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID"),
+        ("session_id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission"),
+        (status = 404, description = "Session not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn admin_revoke_session(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    client: ClientInfo,
+    Path((user_id, session_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse> {
+    authorized.require(Permission::UsersManageSecurity)?;
+
+    find_organization_user(&state, &authorized, user_id).await?;
+    state.auth_service.revoke_session(user_id, session_id).await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                authorized.organization_id,
+                authorized.user_id,
+                AuditAction::SessionRevoked,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_before(json!({ "session_id": session_id })),
+            &client,
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List current login lockouts (requires `users.manage_security`)
+#[utoipa::path(
+    get,
+    path = "/api/admin/lockouts",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Accounts and IP addresses locked after failed logins", body = Vec<crate::auth::auth_dto::LockoutResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_lockouts(
+    State(state): State<AppState>,
+    authorized: Authorized,
+) -> Result<impl IntoResponse> {
+    authorized.require(Permission::UsersManageSecurity)?;
+
+    let lockouts: Vec<crate::auth::auth_dto::LockoutResponse> = state
+        .auth_service
+        .list_lockouts()
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok((StatusCode::OK, Json(lockouts)))
+}
+
+/// Lift a login lockout (requires `users.manage_security`)
+#[utoipa::path(
+    delete,
+    path = "/api/admin/lockouts/{lockout_id}",
+    tag = "admin",
+    params(
+        ("lockout_id" = Uuid, Path, description = "Lockout ID")
+    ),
+    responses(
+        (status = 204, description = "Lockout lifted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission"),
+        (status = 404, description = "Lockout not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn clear_lockout(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    client: ClientInfo,
+    Path(lockout_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    authorized.require(Permission::UsersManageSecurity)?;
+
+    let lockout = state.auth_service.clear_lockout(lockout_id, &client).await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                authorized.organization_id,
+                authorized.user_id,
+                AuditAction::LockoutCleared,
+                AuditTarget::Lockout,
+                lockout_id,
+            )
+            .with_before(crate::auth::auth_dto::LockoutResponse::from(lockout)),
+            &client,
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Lift the login lockout of a user's account (requires `users.manage_security`)
+#[utoipa::path(
+    delete,
+    path = "/api/admin/users/{user_id}/lockout",
+    tag = "admin",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 204, description = "Lockout lifted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission"),
+        (status = 404, description = "User or lockout not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn clear_user_lockout(
+    State(state): State<AppState>,
+    authorized: Authorized,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    authorized.require(Permission::UsersManageSecurity)?;
+
+    find_organization_user(&state, &authorized, user_id).await?;
+    let lockout = state.auth_service.clear_user_lockout(user_id, &client).await?;
+
+    state
+        .audit_service
+        .record(
+            NewAuditEntry::new(
+                authorized.organization_id,
+                authorized.user_id,
+                AuditAction::LockoutCleared,
+                AuditTarget::User,
+                user_id,
+            )
+            .with_before(crate::auth::auth_dto::LockoutResponse::from(lockout)),
+            &client,
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Admin views only reach the members of the caller's organization; anyone
+/// else is reported as not found.
+async fn find_organization_user(
+    state: &AppState,
+    authorized: &Authorized,
+    user_id: Uuid,
+) -> Result<crate::user::user_models::User> {
+    state
+        .user_repository
+        .find_by_id_in_organization(authorized.organization_id, user_id)
+        .await?
+        .ok_or(AppError::NotFound("User not found".to_string()))
 }
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Locale, Utc};
+use chrono_tz::Tz;
+use validator::ValidationError;
+
+/// Parse an IANA time zone name such as `Europe/Berlin`.
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.parse::<Tz>().ok()
+}
+
+/// Parse a BCP 47 style locale tag such as `en-US` or `de_DE`.
+pub fn parse_locale(tag: &str) -> Option<Locale> {
+    Locale::try_from(tag.replace('-', "_").as_str()).ok()
+}
+
+pub fn validate_timezone(name: &str) -> Result<(), ValidationError> {
+    match parse_timezone(name) {
+        Some(_) => Ok(()),
+        None => Err(ValidationError::new("timezone")
+            .with_message("must be an IANA time zone name such as Europe/Berlin".into())),
+    }
+}
+
+pub fn validate_locale(tag: &str) -> Result<(), ValidationError> {
+    match parse_locale(tag) {
+        Some(_) => Ok(()),
+        None => Err(ValidationError::new("locale")
+            .with_message("must be a locale tag such as en-US".into())),
+    }
+}
+
+/// Render `at` in the user's zone using the date/time layout of their locale.
+pub fn format_local_datetime(at: DateTime<Utc>, tz: Tz, locale: Locale) -> String {
+    at.with_timezone(&tz).format_localized("%c", locale).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timezone_and_locale() {
+        assert!(parse_timezone("America/New_York").is_some());
+        assert!(parse_timezone("Mars/Olympus_Mons").is_none());
+        assert!(parse_locale("de-DE").is_some());
+        assert!(parse_locale("xx-YY").is_none());
+    }
+
+    #[test]
+    fn test_format_local_datetime() {
+        let at = "2025-03-01T15:04:00Z".parse().unwrap();
+        let formatted = format_local_datetime(at, chrono_tz::Europe::Berlin, Locale::de_DE);
+        assert!(formatted.contains("16:04"));
+        assert!(formatted.contains("Mär"));
+    }
+}
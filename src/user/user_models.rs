@@ -1,9 +1,12 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Locale, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use super::user_locale::{parse_locale, parse_timezone};
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct User {
     pub id: Uuid,
@@ -11,18 +14,31 @@ pub struct User {
     pub email: String,
     #[serde(skip_serializing)]
     pub password_hash: Option<String>,
-    pub google_id: Option<String>,
     pub avatar_url: Option<String>,
     pub bio: Option<String>,
     pub theme: String,
-    pub role: String,
-    pub is_admin: bool,
     pub is_active: bool,
+    pub email_verified: bool,
+    pub two_factor_enabled: bool,
     pub notification_enabled: bool,
+    pub timezone: String,
+    pub locale: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl User {
+    /// The user's time zone, falling back to UTC if the stored name is unknown.
+    pub fn tz(&self) -> Tz {
+        parse_timezone(&self.timezone).unwrap_or(Tz::UTC)
+    }
+
+    /// The user's locale, falling back to en-US if the stored tag is unknown.
+    pub fn chrono_locale(&self) -> Locale {
+        parse_locale(&self.locale).unwrap_or(Locale::en_US)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UserResponse {
     pub id: Uuid,
@@ -31,10 +47,12 @@ pub struct UserResponse {
     pub avatar_url: Option<String>,
     pub bio: Option<String>,
     pub theme: String,
-    pub role: String,
-    pub is_admin: bool,
     pub is_active: bool,
+    pub email_verified: bool,
+    pub two_factor_enabled: bool,
     pub notification_enabled: bool,
+    pub timezone: String,
+    pub locale: String,
     pub created_at: DateTime<Utc>,
 }
 
@@ -47,10 +65,12 @@ impl From<User> for UserResponse {
             avatar_url: user.avatar_url,
             bio: user.bio,
             theme: user.theme,
-            role: user.role,
-            is_admin: user.is_admin,
             is_active: user.is_active,
+            email_verified: user.email_verified,
+            two_factor_enabled: user.two_factor_enabled,
             notification_enabled: user.notification_enabled,
+            timezone: user.timezone,
+            locale: user.locale,
             created_at: user.created_at,
         }
     }
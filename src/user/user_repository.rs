@@ -1,8 +1,11 @@
 use crate::error::Result;
 use sqlx::PgPool;
 use uuid::Uuid;
-use super::user_models::User;
+use super::{user_dto::UpdateProfileRequest, user_models::User};
 
+/// Accounts are global: sign-in, sign-up and profile lookups go by id, email
+/// or username across all organizations. The admin queries only ever see the
+/// members of the organization passed in.
 #[derive(Clone)]
 pub struct UserRepository {
     pool: PgPool,
@@ -45,6 +48,31 @@ impl UserRepository {
         Ok(user)
     }
 
+    pub async fn update_password(&self, user_id: Uuid, password_hash: &str) -> Result<()> {
+        sqlx::query("UPDATE users SET password_hash = $1, updated_at = NOW() WHERE id = $2")
+            .bind(password_hash)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_password_with_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: Uuid,
+        password_hash: &str,
+    ) -> Result<()> {
+        sqlx::query("UPDATE users SET password_hash = $1, updated_at = NOW() WHERE id = $2")
+            .bind(password_hash)
+            .bind(user_id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn find_by_email(&self, email: &str) -> Result<Option<User>> {
         let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
             .bind(email)
@@ -54,34 +82,50 @@ impl UserRepository {
         Ok(user)
     }
 
+    pub async fn find_by_username(&self, username: &str) -> Result<Option<User>> {
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE username = $1")
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(user)
+    }
+
 
 
-    pub async fn upsert_google_user_with_tx(
+    /// Create an account for someone signing in through a login provider.
+    pub async fn create_external_user_with_tx(
         &self,
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         username: &str,
         email: &str,
-        google_id: &str,
-        avatar_url: &str,
+        avatar_url: Option<&str>,
+        email_verified: bool,
     ) -> Result<User> {
         let user = sqlx::query_as::<_, User>(
-            "INSERT INTO users (username, email, google_id, avatar_url)
+            "INSERT INTO users (username, email, avatar_url, email_verified)
              VALUES ($1, $2, $3, $4)
-             ON CONFLICT (google_id) DO UPDATE SET
-                avatar_url = EXCLUDED.avatar_url,
-                updated_at = NOW()
              RETURNING *"
         )
         .bind(username)
         .bind(email)
-        .bind(google_id)
         .bind(avatar_url)
+        .bind(email_verified)
         .fetch_one(&mut **tx)
         .await?;
 
         Ok(user)
     }
 
+    pub async fn username_exists(&self, username: &str) -> Result<bool> {
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE username = $1)")
+            .bind(username)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(exists)
+    }
+
     pub async fn update_notification_preferences(&self, user_id: Uuid, enabled: bool) -> Result<()> {
         sqlx::query("UPDATE users SET notification_enabled = $1 WHERE id = $2")
             .bind(enabled)
@@ -101,14 +145,17 @@ impl UserRepository {
         Ok(user)
     }
 
-    pub async fn update_profile(
-        &self,
-        user_id: Uuid,
-        username: Option<String>,
-        bio: Option<String>,
-        theme: Option<String>,
-        avatar_url: Option<String>,
-    ) -> Result<User> {
+    pub async fn update_profile(&self, user_id: Uuid, profile: UpdateProfileRequest) -> Result<User> {
+        let UpdateProfileRequest {
+            username,
+            email,
+            bio,
+            theme,
+            avatar_url,
+            timezone,
+            locale,
+        } = profile;
+
         let mut query = String::from("UPDATE users SET updated_at = NOW()");
         let mut param_count = 1;
         let mut bindings: Vec<String> = vec![];
@@ -118,6 +165,15 @@ impl UserRepository {
             query.push_str(&format!(", username = ${}", param_count));
             bindings.push("username".to_string());
         }
+        if email.is_some() {
+            param_count += 1;
+            // A different address has to be verified again
+            query.push_str(&format!(
+                ", email = ${0}, email_verified = CASE WHEN email = ${0} THEN email_verified ELSE false END",
+                param_count
+            ));
+            bindings.push("email".to_string());
+        }
         if bio.is_some() {
             param_count += 1;
             query.push_str(&format!(", bio = ${}", param_count));
@@ -133,6 +189,16 @@ impl UserRepository {
             query.push_str(&format!(", avatar_url = ${}", param_count));
             bindings.push("avatar_url".to_string());
         }
+        if timezone.is_some() {
+            param_count += 1;
+            query.push_str(&format!(", timezone = ${}", param_count));
+            bindings.push("timezone".to_string());
+        }
+        if locale.is_some() {
+            param_count += 1;
+            query.push_str(&format!(", locale = ${}", param_count));
+            bindings.push("locale".to_string());
+        }
 
         query.push_str(&format!(" WHERE id = $1 RETURNING *"));
 
@@ -141,9 +207,12 @@ impl UserRepository {
         for binding in bindings {
             match binding.as_str() {
                 "username" => q = q.bind(username.clone().unwrap()),
+                "email" => q = q.bind(email.clone().unwrap()),
                 "bio" => q = q.bind(bio.clone()),
                 "theme" => q = q.bind(theme.clone().unwrap()),
                 "avatar_url" => q = q.bind(avatar_url.clone()),
+                "timezone" => q = q.bind(timezone.clone().unwrap()),
+                "locale" => q = q.bind(locale.clone().unwrap()),
                 _ => {}
             }
         }
@@ -154,10 +223,13 @@ impl UserRepository {
     }
 
     // Admin methods
-    pub async fn find_all(&self, limit: i64, offset: i64) -> Result<Vec<User>> {
+    pub async fn find_all(&self, organization_id: Uuid, limit: i64, offset: i64) -> Result<Vec<User>> {
         let users = sqlx::query_as::<_, User>(
-            "SELECT * FROM users ORDER BY created_at DESC LIMIT $1 OFFSET $2"
+            "SELECT u.* FROM users u
+             JOIN organization_members m ON m.user_id = u.id AND m.organization_id = $1
+             ORDER BY u.created_at DESC LIMIT $2 OFFSET $3"
         )
+        .bind(organization_id)
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
@@ -166,58 +238,73 @@ impl UserRepository {
         Ok(users)
     }
 
-    pub async fn count_all(&self) -> Result<i64> {
-        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
-            .fetch_one(&self.pool)
-            .await?;
+    pub async fn count_all(&self, organization_id: Uuid) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM organization_members WHERE organization_id = $1"
+        )
+        .bind(organization_id)
+        .fetch_one(&self.pool)
+        .await?;
 
         Ok(count)
     }
 
-    pub async fn update_admin_status(&self, user_id: Uuid, is_admin: bool) -> Result<User> {
+    /// A user, provided they belong to the organization.
+    pub async fn find_by_id_in_organization(&self, organization_id: Uuid, user_id: Uuid) -> Result<Option<User>> {
         let user = sqlx::query_as::<_, User>(
-            "UPDATE users SET is_admin = $1, updated_at = NOW() WHERE id = $2 RETURNING *"
+            "SELECT u.* FROM users u
+             JOIN organization_members m ON m.user_id = u.id AND m.organization_id = $1
+             WHERE u.id = $2"
         )
-        .bind(is_admin)
+        .bind(organization_id)
         .bind(user_id)
-        .fetch_one(&self.pool)
+        .fetch_optional(&self.pool)
         .await?;
 
         Ok(user)
     }
 
-    pub async fn update_active_status(&self, user_id: Uuid, is_active: bool) -> Result<User> {
+    pub async fn update_active_status(&self, organization_id: Uuid, user_id: Uuid, is_active: bool) -> Result<Option<User>> {
         let user = sqlx::query_as::<_, User>(
-            "UPDATE users SET is_active = $1, updated_at = NOW() WHERE id = $2 RETURNING *"
+            "UPDATE users SET is_active = $1, updated_at = NOW()
+             WHERE id = $2
+               AND id IN (SELECT user_id FROM organization_members WHERE organization_id = $3)
+             RETURNING *"
         )
         .bind(is_active)
         .bind(user_id)
-        .fetch_one(&self.pool)
+        .bind(organization_id)
+        .fetch_optional(&self.pool)
         .await?;
 
         Ok(user)
     }
 
-    pub async fn delete_user(&self, user_id: Uuid) -> Result<()> {
-        sqlx::query("DELETE FROM users WHERE id = $1")
-            .bind(user_id)
-            .execute(&self.pool)
-            .await?;
+    pub async fn delete_user(&self, organization_id: Uuid, user_id: Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM users
+             WHERE id = $1
+               AND id IN (SELECT user_id FROM organization_members WHERE organization_id = $2)"
+        )
+        .bind(user_id)
+        .bind(organization_id)
+        .execute(&self.pool)
+        .await?;
 
-        Ok(())
+        Ok(result.rows_affected())
     }
 
     pub async fn admin_update_user(
         &self,
+        organization_id: Uuid,
         user_id: Uuid,
         username: Option<String>,
         email: Option<String>,
         bio: Option<String>,
         theme: Option<String>,
         avatar_url: Option<String>,
-        is_admin: Option<bool>,
         is_active: Option<bool>,
-    ) -> Result<User> {
+    ) -> Result<Option<User>> {
         let mut query = String::from("UPDATE users SET updated_at = NOW()");
         let mut param_count = 1;
         let mut bindings: Vec<String> = vec![];
@@ -229,7 +316,11 @@ impl UserRepository {
         }
         if email.is_some() {
             param_count += 1;
-            query.push_str(&format!(", email = ${}", param_count));
+            // A different address has to be verified again
+            query.push_str(&format!(
+                ", email = ${0}, email_verified = CASE WHEN email = ${0} THEN email_verified ELSE false END",
+                param_count
+            ));
             bindings.push("email".to_string());
         }
         if bio.is_some() {
@@ -247,18 +338,16 @@ impl UserRepository {
             query.push_str(&format!(", avatar_url = ${}", param_count));
             bindings.push("avatar_url".to_string());
         }
-        if is_admin.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", is_admin = ${}", param_count));
-            bindings.push("is_admin".to_string());
-        }
         if is_active.is_some() {
             param_count += 1;
             query.push_str(&format!(", is_active = ${}", param_count));
             bindings.push("is_active".to_string());
         }
 
-        query.push_str(&format!(" WHERE id = $1 RETURNING *"));
+        query.push_str(&format!(
+            " WHERE id = $1 AND id IN (SELECT user_id FROM organization_members WHERE organization_id = ${}) RETURNING *",
+            param_count + 1
+        ));
 
         let mut q = sqlx::query_as::<_, User>(&query).bind(user_id);
 
@@ -269,13 +358,12 @@ impl UserRepository {
                 "bio" => q = q.bind(bio.clone()),
                 "theme" => q = q.bind(theme.clone().unwrap()),
                 "avatar_url" => q = q.bind(avatar_url.clone()),
-                "is_admin" => q = q.bind(is_admin.unwrap()),
                 "is_active" => q = q.bind(is_active.unwrap()),
                 _ => {}
             }
         }
 
-        let user = q.fetch_one(&self.pool).await?;
+        let user = q.bind(organization_id).fetch_optional(&self.pool).await?;
 
         Ok(user)
     }
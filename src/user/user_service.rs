@@ -1,12 +1,17 @@
 use crate::{
     error::Result,
-    task::task_repository::TaskRepository,
+    middleware::auth::Impersonation,
+    task::{
+        task_calendar::{local_date, local_day_start},
+        task_repository::TaskRepository,
+    },
     user::{
-        user_dto::{UpdateProfileRequest, UserStatsResponse},
+        user_dto::{CurrentUserResponse, ImpersonatorResponse, UpdateProfileRequest, UserStatsResponse},
         user_models::UserResponse,
         user_repository::UserRepository,
     },
 };
+use chrono::{Datelike, Duration, Utc};
 use uuid::Uuid;
 
 #[derive(Clone)]
@@ -23,14 +28,38 @@ impl UserService {
         }
     }
 
-    pub async fn get_current_user(&self, user_id: Uuid) -> Result<UserResponse> {
+    /// The signed-in user, and who is acting as them when the request comes
+    /// from an impersonation token.
+    pub async fn get_current_user(
+        &self,
+        user_id: Uuid,
+        impersonation: Option<Impersonation>,
+    ) -> Result<CurrentUserResponse> {
         let user = self
             .user_repository
             .find_by_id(user_id)
             .await?
             .ok_or_else(|| crate::error::AppError::NotFound("User not found".to_string()))?;
 
-        Ok(user.into())
+        let impersonator = match impersonation {
+            Some(impersonation) => Some(ImpersonatorResponse {
+                admin_id: impersonation.admin_id,
+                admin_username: self
+                    .user_repository
+                    .find_by_id(impersonation.admin_id)
+                    .await?
+                    .map(|admin| admin.username),
+                impersonation_id: impersonation.session_id,
+                expires_at: impersonation.expires_at,
+            }),
+            None => None,
+        };
+
+        Ok(CurrentUserResponse {
+            user: user.into(),
+            impersonated: impersonator.is_some(),
+            impersonator,
+        })
     }
 
     pub async fn update_current_user(
@@ -40,13 +69,36 @@ impl UserService {
     ) -> Result<UserResponse> {
         let user = self
             .user_repository
-            .update_profile(user_id, payload.bio, payload.theme, payload.avatar_url, None)
+            .update_profile(user_id, payload)
             .await?;
 
         Ok(UserResponse::from(user))
     }
 
-    pub async fn get_user_stats(&self, user_id: Uuid) -> Result<UserStatsResponse> {
+    pub async fn get_user_stats(&self, organization_id: Uuid, user_id: Uuid) -> Result<UserStatsResponse> {
+        let user = self
+            .user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| crate::error::AppError::NotFound("User not found".to_string()))?;
+
+        // "Today" and "this week" (Monday to Sunday) are taken in the user's zone.
+        let tz = user.tz();
+        let now = Utc::now();
+        let today = local_date(now, tz);
+        let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+
+        let (due_today, due_this_week, overdue_tasks) = self
+            .task_repository
+            .get_user_due_counts(
+                organization_id,
+                user_id,
+                (local_day_start(today, tz), local_day_start(today + Duration::days(1), tz)),
+                (local_day_start(week_start, tz), local_day_start(week_start + Duration::days(7), tz)),
+                now,
+            )
+            .await?;
+
         let (
             total_tasks,
             pending_tasks,
@@ -57,7 +109,7 @@ impl UserService {
             medium_priority_tasks,
             high_priority_tasks,
             urgent_priority_tasks,
-        ) = self.task_repository.get_user_stats(user_id).await?;
+        ) = self.task_repository.get_user_stats(organization_id, user_id).await?;
 
         let completion_rate = if total_tasks > 0 {
             (completed_tasks as f64 / total_tasks as f64) * 100.0
@@ -76,6 +128,9 @@ impl UserService {
             medium_priority_tasks,
             high_priority_tasks,
             urgent_priority_tasks,
+            due_today,
+            due_this_week,
+            overdue_tasks,
         })
     }
 }
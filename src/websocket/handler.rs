@@ -11,7 +11,7 @@ use uuid::Uuid;
 
 use crate::{
     error::{AppError, Result},
-    middleware::AuthUser,
+    middleware::{AuthUser, CurrentOrganization},
     state::AppState,
     websocket::types::{ChatMessagePayload, ClientMessage, ErrorPayload, UserStatusPayload, WsMessage},
 };
@@ -23,12 +23,14 @@ pub async fn ws_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
     AuthUser(user_id): AuthUser,
+    CurrentOrganization(organization_id): CurrentOrganization,
 ) -> Response {
-    ws.on_upgrade(move |socket| handle_socket(socket, user_id, state))
+    ws.on_upgrade(move |socket| handle_socket(socket, user_id, organization_id, state))
 }
 
-/// Handle individual WebSocket connection
-async fn handle_socket(socket: WebSocket, user_id: Uuid, state: AppState) {
+/// Handle individual WebSocket connection; messages sent over it belong to
+/// the organization the connection was opened in
+async fn handle_socket(socket: WebSocket, user_id: Uuid, organization_id: Uuid, state: AppState) {
     let (mut sender, mut receiver) = socket.split();
     let (tx, mut rx) = mpsc::unbounded_channel::<WsMessage>();
 
@@ -59,7 +61,7 @@ async fn handle_socket(socket: WebSocket, user_id: Uuid, state: AppState) {
     let mut recv_task = tokio::spawn(async move {
         while let Some(Ok(msg)) = receiver.next().await {
             if let Message::Text(text) = msg {
-                if let Err(e) = process_client_message(&text, user_id, &state_clone, &tx_clone).await {
+                if let Err(e) = process_client_message(&text, user_id, organization_id, &state_clone, &tx_clone).await {
                     tracing::error!("Error processing message: {:?}", e);
                     let error_msg = WsMessage::Error(ErrorPayload {
                         message: e.to_string(),
@@ -93,6 +95,7 @@ async fn handle_socket(socket: WebSocket, user_id: Uuid, state: AppState) {
 async fn process_client_message(
     text: &str,
     user_id: Uuid,
+    organization_id: Uuid,
     state: &AppState,
     _tx: &WsSender,
 ) -> Result<()> {
@@ -105,17 +108,17 @@ async fn process_client_message(
             content,
             image_url,
         } => {
-            // Verify receiver exists
+            // Only members of the organization can be messaged in it
             let _receiver = state
                 .user_repository
-                .find_by_id(receiver_id)
+                .find_by_id_in_organization(organization_id, receiver_id)
                 .await?
                 .ok_or(AppError::NotFound("Receiver not found".to_string()))?;
 
             // Create message in database
             let message = state
                 .message_repository
-                .create(user_id, receiver_id, &content, image_url.as_deref())
+                .create(organization_id, user_id, receiver_id, &content, image_url.as_deref())
                 .await?;
 
             // Send via WebSocket to receiver
@@ -158,7 +161,7 @@ async fn process_client_message(
         }
         ClientMessage::MarkMessageDelivered { message_id } => {
             // Mark message as read
-            let _ = state.message_repository.mark_as_read(message_id, user_id).await;
+            let _ = state.message_repository.mark_as_read(organization_id, message_id, user_id).await;
         }
     }
 
@@ -9,8 +9,11 @@ pub enum WsMessage {
     TypingIndicator(TypingIndicatorPayload),
     UserStatus(UserStatusPayload),
     TaskUpdated(TaskUpdatedPayload),
-    TaskShared(TaskSharedPayload),
+    TaskInvitation(TaskInvitationPayload),
+    TaskInvitationAccepted(TaskInvitationAcceptedPayload),
     TaskMemberRemoved(TaskMemberRemovedPayload),
+    TaskMemberRoleChanged(TaskMemberRoleChangedPayload),
+    TaskOwnershipTransferred(TaskOwnershipTransferredPayload),
     MessageDelivered(MessageDeliveredPayload),
     Error(ErrorPayload),
 }
@@ -48,11 +51,24 @@ pub struct TaskUpdatedPayload {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
-pub struct TaskSharedPayload {
+pub struct TaskInvitationPayload {
+    pub invitation_id: Uuid,
     pub task_id: Uuid,
     pub task_title: String,
-    pub shared_by: Uuid,
-    pub shared_by_username: String,
+    pub invited_by: Uuid,
+    pub invited_by_username: String,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TaskInvitationAcceptedPayload {
+    /// Not set when the user joined through a link
+    pub invitation_id: Option<Uuid>,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub user_id: Uuid,
+    pub username: String,
+    pub role: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -62,6 +78,22 @@ pub struct TaskMemberRemovedPayload {
     pub removed_by: Uuid,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TaskMemberRoleChangedPayload {
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub role: crate::task::task_models::TaskRole,
+    pub changed_by: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TaskOwnershipTransferredPayload {
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub previous_owner_id: Uuid,
+    pub new_owner_id: Uuid,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct MessageDeliveredPayload {
     pub message_id: Uuid,